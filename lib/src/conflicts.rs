@@ -28,6 +28,28 @@ const CONFLICT_END_LINE: &[u8] = b">>>>>>>\n";
 const CONFLICT_DIFF_LINE: &[u8] = b"%%%%%%%\n";
 const CONFLICT_MINUS_LINE: &[u8] = b"-------\n";
 const CONFLICT_PLUS_LINE: &[u8] = b"+++++++\n";
+const GIT_CONFLICT_BASE_LINE: &[u8] = b"|||||||\n";
+const GIT_CONFLICT_SEPARATOR_LINE: &[u8] = b"=======\n";
+
+/// How `materialize_conflict()` writes the conflicting regions of a file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConflictMarkerStyle {
+    /// jj's markers, showing each side as a diff from a base ("%%%%%%%") where
+    /// possible. This is the only style that can be parsed back.
+    Diff,
+    /// jj's markers, showing each side ("+++++++") and base ("-------") in
+    /// full
+    Snapshot,
+    /// Git's "diff3" markers ("<<<<<<<", "|||||||", "=======", ">>>>>>>").
+    /// Conflicts with more than two sides are written in the `Snapshot` style.
+    Git,
+}
+
+impl Default for ConflictMarkerStyle {
+    fn default() -> Self {
+        ConflictMarkerStyle::Diff
+    }
+}
 
 fn describe_conflict_part(part: &ConflictPart) -> String {
     match &part.value {
@@ -132,6 +154,16 @@ pub fn materialize_conflict(
     path: &RepoPath,
     conflict: &Conflict,
     output: &mut dyn Write,
+) -> std::io::Result<()> {
+    materialize_conflict_with_style(store, path, conflict, ConflictMarkerStyle::Diff, output)
+}
+
+pub fn materialize_conflict_with_style(
+    store: &Store,
+    path: &RepoPath,
+    conflict: &Conflict,
+    style: ConflictMarkerStyle,
+    output: &mut dyn Write,
 ) -> std::io::Result<()> {
     match extract_file_conflict_as_single_hunk(store, path, conflict) {
         None => {
@@ -139,7 +171,7 @@ pub fn materialize_conflict(
             // describe the conflict.
             describe_conflict(conflict, output)
         }
-        Some(content) => materialize_merge_result(&content, output),
+        Some(content) => materialize_merge_result_with_style(&content, style, output),
     }
 }
 
@@ -172,6 +204,14 @@ pub fn extract_file_conflict_as_single_hunk(
 pub fn materialize_merge_result(
     single_hunk: &ConflictHunk,
     output: &mut dyn Write,
+) -> std::io::Result<()> {
+    materialize_merge_result_with_style(single_hunk, ConflictMarkerStyle::Diff, output)
+}
+
+pub fn materialize_merge_result_with_style(
+    single_hunk: &ConflictHunk,
+    style: ConflictMarkerStyle,
+    output: &mut dyn Write,
 ) -> std::io::Result<()> {
     let removed_slices = single_hunk.removes.iter().map(Vec::as_slice).collect_vec();
     let added_slices = single_hunk.adds.iter().map(Vec::as_slice).collect_vec();
@@ -186,48 +226,112 @@ pub fn materialize_merge_result(
                     MergeHunk::Resolved(content) => {
                         output.write_all(&content)?;
                     }
-                    MergeHunk::Conflict(ConflictHunk {
-                        mut removes,
-                        mut adds,
-                    }) => {
-                        output.write_all(CONFLICT_START_LINE)?;
-                        while !removes.is_empty() && !adds.is_empty() {
-                            let left = &removes[0];
-                            let mut diffs = vec![];
-                            for right in &adds {
-                                diffs.push(
-                                    Diff::for_tokenizer(&[left, right], &find_line_ranges)
-                                        .hunks()
-                                        .collect_vec(),
-                                );
-                            }
-                            let min_diff_index = diffs
-                                .iter()
-                                .position_min_by_key(|diff| diff_size(diff))
-                                .unwrap();
-                            output.write_all(CONFLICT_DIFF_LINE)?;
-                            write_diff_hunks(&diffs[min_diff_index], output)?;
-                            removes.remove(0);
-                            adds.remove(min_diff_index);
-                        }
+                    MergeHunk::Conflict(hunk) => {
+                        write_conflict_hunk(hunk, style, output)?;
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
 
-                        for slice in removes {
-                            output.write_all(CONFLICT_MINUS_LINE)?;
-                            output.write_all(&slice)?;
-                        }
-                        for slice in adds {
-                            output.write_all(CONFLICT_PLUS_LINE)?;
-                            output.write_all(&slice)?;
-                        }
-                        output.write_all(CONFLICT_END_LINE)?;
+fn write_conflict_hunk(
+    hunk: ConflictHunk,
+    style: ConflictMarkerStyle,
+    output: &mut dyn Write,
+) -> std::io::Result<()> {
+    let ConflictHunk {
+        mut removes,
+        mut adds,
+    } = hunk;
+    match style {
+        ConflictMarkerStyle::Git if removes.len() == 1 && adds.len() == 2 => {
+            output.write_all(CONFLICT_START_LINE)?;
+            output.write_all(&adds[0])?;
+            output.write_all(GIT_CONFLICT_BASE_LINE)?;
+            output.write_all(&removes[0])?;
+            output.write_all(GIT_CONFLICT_SEPARATOR_LINE)?;
+            output.write_all(&adds[1])?;
+            output.write_all(CONFLICT_END_LINE)?;
+        }
+        ConflictMarkerStyle::Git | ConflictMarkerStyle::Snapshot => {
+            output.write_all(CONFLICT_START_LINE)?;
+            // Each base comes between the sides it's the base of
+            for (i, add) in adds.iter().enumerate() {
+                if i > 0 {
+                    if let Some(remove) = removes.get(i - 1) {
+                        output.write_all(CONFLICT_MINUS_LINE)?;
+                        output.write_all(remove)?;
                     }
                 }
+                output.write_all(CONFLICT_PLUS_LINE)?;
+                output.write_all(add)?;
             }
+            output.write_all(CONFLICT_END_LINE)?;
+        }
+        ConflictMarkerStyle::Diff => {
+            output.write_all(CONFLICT_START_LINE)?;
+            while !removes.is_empty() && !adds.is_empty() {
+                let left = &removes[0];
+                let mut diffs = vec![];
+                for right in &adds {
+                    diffs.push(
+                        Diff::for_tokenizer(&[left, right], &find_line_ranges)
+                            .hunks()
+                            .collect_vec(),
+                    );
+                }
+                let min_diff_index = diffs
+                    .iter()
+                    .position_min_by_key(|diff| diff_size(diff))
+                    .unwrap();
+                output.write_all(CONFLICT_DIFF_LINE)?;
+                write_diff_hunks(&diffs[min_diff_index], output)?;
+                removes.remove(0);
+                adds.remove(min_diff_index);
+            }
+
+            for slice in removes {
+                output.write_all(CONFLICT_MINUS_LINE)?;
+                output.write_all(&slice)?;
+            }
+            for slice in adds {
+                output.write_all(CONFLICT_PLUS_LINE)?;
+                output.write_all(&slice)?;
+            }
+            output.write_all(CONFLICT_END_LINE)?;
         }
     }
     Ok(())
 }
 
+/// Merges `single_hunk` like `materialize_merge_result()` does, but instead of
+/// writing conflict markers for a conflicting region, writes the contents that
+/// `pick` selects from it.
+pub fn resolve_merge_result(
+    single_hunk: &ConflictHunk,
+    pick: impl Fn(&ConflictHunk) -> &[u8],
+) -> Vec<u8> {
+    let removed_slices = single_hunk.removes.iter().map(Vec::as_slice).collect_vec();
+    let added_slices = single_hunk.adds.iter().map(Vec::as_slice).collect_vec();
+    match files::merge(&removed_slices, &added_slices) {
+        MergeResult::Resolved(content) => content,
+        MergeResult::Conflict(hunks) => {
+            let mut output = vec![];
+            for hunk in &hunks {
+                match hunk {
+                    MergeHunk::Resolved(content) => output.extend_from_slice(content),
+                    MergeHunk::Conflict(conflict_hunk) => {
+                        output.extend_from_slice(pick(conflict_hunk));
+                    }
+                }
+            }
+            output
+        }
+    }
+}
+
 fn diff_size(hunks: &[DiffHunk]) -> usize {
     hunks
         .iter()
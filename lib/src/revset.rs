@@ -28,9 +28,10 @@ use pest::iterators::{Pair, Pairs};
 use pest::pratt_parser::{Assoc, Op, PrattParser};
 use pest::Parser;
 use pest_derive::Parser;
+use regex::Regex;
 use thiserror::Error;
 
-use crate::backend::{BackendError, BackendResult, CommitId};
+use crate::backend::{BackendError, BackendResult, CommitId, MillisSinceEpoch};
 use crate::commit::Commit;
 use crate::index::{HexPrefix, IndexEntry, PrefixResolution, RevWalk};
 use crate::matchers::{EverythingMatcher, Matcher, PrefixMatcher};
@@ -47,6 +48,10 @@ pub enum RevsetError {
     NoSuchRevision(String),
     #[error("Commit or change id prefix \"{0}\" is ambiguous")]
     AmbiguousIdPrefix(String),
+    #[error("Cannot follow \"~N\" through merge commit {0}; use \"-\" to select all parents")]
+    AmbiguousAncestor(String),
+    #[error("No branch name is within edit distance {max_distance} of \"{name}\"")]
+    NoNearbyBranch { name: String, max_distance: usize },
     #[error("Unexpected error from store: {0}")]
     StoreError(#[from] BackendError),
 }
@@ -217,6 +222,8 @@ pub enum RevsetParseErrorKind {
     SyntaxError,
     #[error("Revset function \"{0}\" doesn't exist")]
     NoSuchFunction(String),
+    #[error("Invalid ancestor generation: {0}")]
+    InvalidAncestorGeneration(String),
     #[error("Invalid arguments to revset function \"{name}\": {message}")]
     InvalidFunctionArguments { name: String, message: String },
     #[error("Invalid file pattern: {0}")]
@@ -318,6 +325,29 @@ impl error::Error for RevsetParseError {
     }
 }
 
+/// A regular expression to match commits against. Two of them are equal if
+/// they were compiled from the same pattern.
+#[derive(Clone, Debug)]
+pub struct RevsetRegex(Regex);
+
+impl RevsetRegex {
+    pub fn new(regex: Regex) -> Self {
+        RevsetRegex(regex)
+    }
+
+    pub fn is_match(&self, text: &str) -> bool {
+        self.0.is_match(text)
+    }
+}
+
+impl PartialEq for RevsetRegex {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.as_str() == other.0.as_str()
+    }
+}
+
+impl Eq for RevsetRegex {}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum RevsetFilterPredicate {
     /// Commits with number of parents in the range.
@@ -326,8 +356,18 @@ pub enum RevsetFilterPredicate {
     Description(String),
     /// Commits with author's name or email containing the needle.
     Author(String),
+    /// Commits with author's name or email matching the regex.
+    AuthorRegex(RevsetRegex),
     /// Commits with committer's name or email containing the needle.
     Committer(String),
+    /// Commits authored at or after the timestamp.
+    AuthoredAfter(MillisSinceEpoch),
+    /// Commits authored before the timestamp.
+    AuthoredBefore(MillisSinceEpoch),
+    /// Commits committed at or after the timestamp.
+    CommittedAfter(MillisSinceEpoch),
+    /// Commits committed before the timestamp.
+    CommittedBefore(MillisSinceEpoch),
     /// Commits modifying no files. Equivalent to `Not(File(["."]))`.
     Empty,
     /// Commits modifying the paths specified by the pattern.
@@ -343,6 +383,12 @@ pub enum RevsetExpression {
     Parents(Rc<RevsetExpression>),
     Children(Rc<RevsetExpression>),
     Ancestors(Rc<RevsetExpression>),
+    // The ancestor "generation" steps back from each commit in "base",
+    // following the only parent at each step
+    NthAncestor {
+        base: Rc<RevsetExpression>,
+        generation: u32,
+    },
     // Commits that are ancestors of "heads" but not ancestors of "roots"
     Range {
         roots: Rc<RevsetExpression>,
@@ -358,6 +404,12 @@ pub enum RevsetExpression {
     VisibleHeads,
     PublicHeads,
     Branches,
+    // The local branch whose name is closest to "name", if it's within
+    // "max_distance" edits
+    NearestBranch {
+        name: String,
+        max_distance: usize,
+    },
     RemoteBranches,
     Tags,
     GitRefs,
@@ -403,6 +455,10 @@ impl RevsetExpression {
         Rc::new(RevsetExpression::Branches)
     }
 
+    pub fn nearest_branch(name: String, max_distance: usize) -> Rc<RevsetExpression> {
+        Rc::new(RevsetExpression::NearestBranch { name, max_distance })
+    }
+
     pub fn remote_branches() -> Rc<RevsetExpression> {
         Rc::new(RevsetExpression::RemoteBranches)
     }
@@ -438,6 +494,15 @@ impl RevsetExpression {
         Rc::new(RevsetExpression::Parents(self.clone()))
     }
 
+    /// Ancestor of each commit in `self` that is `generation` steps back. It is
+    /// an error to step back through a merge commit.
+    pub fn nth_ancestor(self: &Rc<RevsetExpression>, generation: u32) -> Rc<RevsetExpression> {
+        Rc::new(RevsetExpression::NthAncestor {
+            base: self.clone(),
+            generation,
+        })
+    }
+
     /// Ancestors of `self`, including `self`.
     pub fn ancestors(self: &Rc<RevsetExpression>) -> Rc<RevsetExpression> {
         Rc::new(RevsetExpression::Ancestors(self.clone()))
@@ -471,6 +536,17 @@ impl RevsetExpression {
         self.dag_range_to(self)
     }
 
+    /// The greatest common ancestors of `self` and `other`: the heads of the
+    /// commits that are ancestors of both. Since all commits descend from the
+    /// root commit, this is the root commit if the histories are otherwise
+    /// unrelated, and empty only if `self` or `other` is.
+    pub fn fork_point(
+        self: &Rc<RevsetExpression>,
+        other: &Rc<RevsetExpression>,
+    ) -> Rc<RevsetExpression> {
+        self.ancestors().intersection(&other.ancestors()).heads()
+    }
+
     /// Commits reachable from `heads` but not from `self`.
     pub fn range(
         self: &Rc<RevsetExpression>,
@@ -692,7 +768,9 @@ fn parse_expression_rule(
             .op(Op::prefix(Rule::dag_range_pre_op) | Op::prefix(Rule::range_pre_op))
             .op(Op::postfix(Rule::dag_range_post_op) | Op::postfix(Rule::range_post_op))
             // Neighbors
-            .op(Op::postfix(Rule::parents_op) | Op::postfix(Rule::children_op))
+            .op(Op::postfix(Rule::parents_op)
+                | Op::postfix(Rule::children_op)
+                | Op::postfix(Rule::ancestor_op))
     });
     PRATT
         .map_primary(|primary| parse_primary_rule(primary, state))
@@ -706,6 +784,17 @@ fn parse_expression_rule(
             Rule::range_post_op => Ok(lhs?.range(&RevsetExpression::visible_heads())),
             Rule::parents_op => Ok(lhs?.parents()),
             Rule::children_op => Ok(lhs?.children()),
+            Rule::ancestor_op => {
+                let generation = op.as_str()[1..].parse().map_err(|_| {
+                    RevsetParseError::with_span(
+                        RevsetParseErrorKind::InvalidAncestorGeneration(
+                            op.as_str()[1..].to_owned(),
+                        ),
+                        op.as_span(),
+                    )
+                })?;
+                Ok(lhs?.nth_ancestor(generation))
+            }
             r => panic!("unexpected postfix operator rule {r:?}"),
         })
         .map_infix(|lhs, op, rhs| match op.as_rule() {
@@ -809,6 +898,10 @@ fn parse_function_expression(
     }
 }
 
+/// How many edits `nearest(name)` allows between `name` and a branch name when
+/// no maximum is given.
+const DEFAULT_NEAREST_MAX_DISTANCE: usize = 2;
+
 fn parse_builtin_function(
     name_pair: Pair<Rule>,
     arguments_pair: Pair<Rule>,
@@ -841,6 +934,12 @@ fn parse_builtin_function(
             let candidates = parse_expression_rule(arg.into_inner(), state)?;
             Ok(candidates.connected())
         }
+        "fork_point" => {
+            let (arg1, arg2) = expect_two_arguments(name, arguments_pair)?;
+            let expression1 = parse_expression_rule(arg1.into_inner(), state)?;
+            let expression2 = parse_expression_rule(arg2.into_inner(), state)?;
+            Ok(expression1.fork_point(&expression2))
+        }
         "none" => {
             expect_no_arguments(name, arguments_pair)?;
             Ok(RevsetExpression::none())
@@ -870,6 +969,31 @@ fn parse_builtin_function(
             expect_no_arguments(name, arguments_pair)?;
             Ok(RevsetExpression::branches())
         }
+        "nearest" => {
+            let arguments_span = arguments_pair.as_span();
+            let mut argument_pairs = arguments_pair.into_inner().fuse();
+            match (
+                argument_pairs.next(),
+                argument_pairs.next(),
+                argument_pairs.next(),
+            ) {
+                (Some(needle_arg), distance_arg, None) => {
+                    let needle = parse_function_argument_to_string(name, needle_arg, state)?;
+                    let max_distance = match distance_arg {
+                        Some(arg) => parse_function_argument_to_usize(name, arg, state)?,
+                        None => DEFAULT_NEAREST_MAX_DISTANCE,
+                    };
+                    Ok(RevsetExpression::nearest_branch(needle, max_distance))
+                }
+                _ => Err(RevsetParseError::with_span(
+                    RevsetParseErrorKind::InvalidFunctionArguments {
+                        name: name.to_owned(),
+                        message: "Expected 1 or 2 arguments".to_string(),
+                    },
+                    arguments_span,
+                )),
+            }
+        }
         "remote_branches" => {
             expect_no_arguments(name, arguments_pair)?;
             Ok(RevsetExpression::remote_branches())
@@ -913,6 +1037,18 @@ fn parse_builtin_function(
                 needle,
             )))
         }
+        "authored_after" | "authored_before" | "committed_after" | "committed_before" => {
+            let arg = expect_one_argument(name, arguments_pair)?;
+            let timestamp = parse_function_argument_to_timestamp(name, arg, state)?;
+            let predicate = match name {
+                "authored_after" => RevsetFilterPredicate::AuthoredAfter(timestamp),
+                "authored_before" => RevsetFilterPredicate::AuthoredBefore(timestamp),
+                "committed_after" => RevsetFilterPredicate::CommittedAfter(timestamp),
+                "committed_before" => RevsetFilterPredicate::CommittedBefore(timestamp),
+                _ => unreachable!(),
+            };
+            Ok(RevsetExpression::filter(predicate))
+        }
         "empty" => {
             expect_no_arguments(name, arguments_pair)?;
             Ok(RevsetExpression::filter(RevsetFilterPredicate::Empty))
@@ -999,6 +1135,29 @@ fn expect_one_argument<'i>(
     }
 }
 
+fn expect_two_arguments<'i>(
+    name: &str,
+    arguments_pair: Pair<'i, Rule>,
+) -> Result<(Pair<'i, Rule>, Pair<'i, Rule>), RevsetParseError> {
+    let span = arguments_pair.as_span();
+    let mut argument_pairs = arguments_pair.into_inner().fuse();
+    if let (Some(arg1), Some(arg2), None) = (
+        argument_pairs.next(),
+        argument_pairs.next(),
+        argument_pairs.next(),
+    ) {
+        Ok((arg1, arg2))
+    } else {
+        Err(RevsetParseError::with_span(
+            RevsetParseErrorKind::InvalidFunctionArguments {
+                name: name.to_owned(),
+                message: "Expected 2 arguments".to_string(),
+            },
+            span,
+        ))
+    }
+}
+
 fn expect_one_optional_argument<'i>(
     name: &str,
     arguments_pair: Pair<'i, Rule>,
@@ -1037,6 +1196,95 @@ fn parse_function_argument_to_string(
     }
 }
 
+fn parse_function_argument_to_usize(
+    name: &str,
+    pair: Pair<Rule>,
+    state: ParseState,
+) -> Result<usize, RevsetParseError> {
+    let span = pair.as_span();
+    let text = parse_function_argument_to_string(name, pair, state)?;
+    text.parse().map_err(|_| {
+        RevsetParseError::with_span(
+            RevsetParseErrorKind::InvalidFunctionArguments {
+                name: name.to_string(),
+                message: "Expected function argument of type non-negative integer".to_owned(),
+            },
+            span,
+        )
+    })
+}
+
+/// Parses a date given either as "2022-01-31" (midnight UTC) or as an RFC 3339
+/// timestamp like "2022-01-31T12:00:00+01:00".
+fn parse_function_argument_to_timestamp(
+    name: &str,
+    pair: Pair<Rule>,
+    state: ParseState,
+) -> Result<MillisSinceEpoch, RevsetParseError> {
+    let span = pair.as_span();
+    let text = parse_function_argument_to_string(name, pair, state)?;
+    parse_date(&text, &chrono::Local::now()).ok_or_else(|| {
+        RevsetParseError::with_span(
+            RevsetParseErrorKind::InvalidFunctionArguments {
+                name: name.to_string(),
+                message: format!(
+                    r#"Invalid date "{text}", expected e.g. "2022-01-31", "2022-01-31T12:00:00+01:00", or "3 days ago""#
+                ),
+            },
+            span,
+        )
+    })
+}
+
+/// Parses a date given by the user. It can be absolute, like "2022-01-31" (at
+/// midnight UTC) or "2022-01-31T12:00:00+01:00", or relative to `now`, like
+/// "now", "today" and "yesterday" (at midnight in `now`'s time zone), or "3
+/// days ago".
+pub fn parse_date<Tz: chrono::TimeZone>(
+    text: &str,
+    now: &chrono::DateTime<Tz>,
+) -> Option<MillisSinceEpoch> {
+    if let Ok(datetime) = chrono::DateTime::parse_from_rfc3339(text) {
+        return Some(MillisSinceEpoch(datetime.timestamp_millis()));
+    }
+    if let Ok(date) = chrono::NaiveDate::parse_from_str(text, "%Y-%m-%d") {
+        let datetime = date.and_hms_opt(0, 0, 0).unwrap();
+        return Some(MillisSinceEpoch(datetime.timestamp_millis()));
+    }
+    let start_of_day = |days_ago: i64| {
+        let date = now.date_naive() - chrono::Duration::days(days_ago);
+        let datetime = now
+            .timezone()
+            .from_local_datetime(&date.and_hms_opt(0, 0, 0).unwrap())
+            .earliest()?;
+        Some(MillisSinceEpoch(datetime.timestamp_millis()))
+    };
+    match text {
+        "now" => return Some(MillisSinceEpoch(now.timestamp_millis())),
+        "today" => return start_of_day(0),
+        "yesterday" => return start_of_day(1),
+        _ => {}
+    }
+    let words = text.split_whitespace().collect_vec();
+    if let [count, unit, "ago"] = words.as_slice() {
+        let count: i64 = count.parse().ok()?;
+        let unit_millis: i64 = match unit.strip_suffix('s').unwrap_or(unit) {
+            "second" => 1000,
+            "minute" => 60 * 1000,
+            "hour" => 60 * 60 * 1000,
+            "day" => 24 * 60 * 60 * 1000,
+            "week" => 7 * 24 * 60 * 60 * 1000,
+            _ => return None,
+        };
+        // Counts that are too large for a date are rejected rather than
+        // overflowing
+        let duration = chrono::Duration::milliseconds(count.checked_mul(unit_millis)?);
+        let datetime = now.clone().checked_sub_signed(duration)?;
+        return Some(MillisSinceEpoch(datetime.timestamp_millis()));
+    }
+    None
+}
+
 pub fn parse(
     revset_str: &str,
     aliases_map: &RevsetAliasesMap,
@@ -1078,6 +1326,12 @@ fn transform_expression_bottom_up(
             RevsetExpression::Ancestors(base) => {
                 transform_rec(base, f).map(RevsetExpression::Ancestors)
             }
+            RevsetExpression::NthAncestor { base, generation } => {
+                transform_rec(base, f).map(|base| RevsetExpression::NthAncestor {
+                    base,
+                    generation: *generation,
+                })
+            }
             RevsetExpression::Range { roots, heads } => transform_rec_pair((roots, heads), f)
                 .map(|(roots, heads)| RevsetExpression::Range { roots, heads }),
             RevsetExpression::DagRange { roots, heads } => transform_rec_pair((roots, heads), f)
@@ -1091,6 +1345,7 @@ fn transform_expression_bottom_up(
             }
             RevsetExpression::PublicHeads => None,
             RevsetExpression::Branches => None,
+            RevsetExpression::NearestBranch { .. } => None,
             RevsetExpression::RemoteBranches => None,
             RevsetExpression::Tags => None,
             RevsetExpression::GitRefs => None,
@@ -1609,6 +1864,29 @@ pub fn evaluate_expression<'repo>(
         RevsetExpression::Ancestors(base_expression) => RevsetExpression::none()
             .range(base_expression)
             .evaluate(repo, workspace_ctx),
+        RevsetExpression::NthAncestor { base, generation } => {
+            let base_set = base.evaluate(repo, workspace_ctx)?;
+            let mut ancestor_entries = vec![];
+            'entries: for mut entry in base_set.iter() {
+                for _ in 0..*generation {
+                    let mut parents = entry.parents();
+                    if parents.len() > 1 {
+                        return Err(RevsetError::AmbiguousAncestor(entry.commit_id().hex()));
+                    }
+                    match parents.pop() {
+                        Some(parent) => entry = parent,
+                        // Stepped back past the root commit
+                        None => continue 'entries,
+                    }
+                }
+                ancestor_entries.push(entry);
+            }
+            ancestor_entries.sort_by_key(|b| Reverse(b.position()));
+            ancestor_entries.dedup();
+            Ok(Box::new(EagerRevset {
+                index_entries: ancestor_entries,
+            }))
+        }
         RevsetExpression::Range { roots, heads } => {
             let root_set = roots.evaluate(repo, workspace_ctx)?;
             let root_ids = root_set.iter().commit_ids().collect_vec();
@@ -1683,6 +1961,26 @@ pub fn evaluate_expression<'repo>(
             }
             Ok(revset_for_commit_ids(repo, &commit_ids))
         }
+        RevsetExpression::NearestBranch { name, max_distance } => {
+            // Ties go to the branch that sorts first
+            let nearest_target = repo
+                .view()
+                .branches()
+                .iter()
+                .filter_map(|(branch_name, branch_target)| {
+                    let local_target = branch_target.local_target.as_ref()?;
+                    let distance = edit_distance(name, branch_name);
+                    (distance <= *max_distance).then(|| (distance, local_target))
+                })
+                .min_by_key(|(distance, _)| *distance);
+            match nearest_target {
+                Some((_, local_target)) => Ok(revset_for_commit_ids(repo, &local_target.adds())),
+                None => Err(RevsetError::NoNearbyBranch {
+                    name: name.clone(),
+                    max_distance: *max_distance,
+                }),
+            }
+        }
         RevsetExpression::RemoteBranches => {
             let mut commit_ids = vec![];
             for branch_target in repo.view().branches().values() {
@@ -1716,8 +2014,14 @@ pub fn evaluate_expression<'repo>(
         })),
         RevsetExpression::Present(candidates) => match candidates.evaluate(repo, workspace_ctx) {
             Ok(set) => Ok(set),
-            Err(RevsetError::NoSuchRevision(_)) => Ok(Box::new(EagerRevset::empty())),
-            r @ Err(RevsetError::AmbiguousIdPrefix(_) | RevsetError::StoreError(_)) => r,
+            Err(RevsetError::NoSuchRevision(_) | RevsetError::NoNearbyBranch { .. }) => {
+                Ok(Box::new(EagerRevset::empty()))
+            }
+            r @ Err(
+                RevsetError::AmbiguousIdPrefix(_)
+                | RevsetError::AmbiguousAncestor(_)
+                | RevsetError::StoreError(_),
+            ) => r,
         },
         RevsetExpression::NotIn(complement) => {
             let set1 = RevsetExpression::All.evaluate(repo, workspace_ctx)?;
@@ -1752,6 +2056,23 @@ pub fn evaluate_expression<'repo>(
     }
 }
 
+/// The Levenshtein distance between `a` and `b`, counted in chars.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect_vec();
+    // Distances from the prefix of `a` seen so far to each prefix of `b`
+    let mut row = (0..=b.len()).collect_vec();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(a_char != *b_char);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
 fn revset_for_commit_ids<'revset, 'repo: 'revset>(
     repo: RepoRef<'repo>,
     commit_ids: &[CommitId],
@@ -1809,6 +2130,13 @@ fn build_predicate_fn<'repo>(
                     || commit.author().email.contains(needle.as_str())
             })
         }
+        RevsetFilterPredicate::AuthorRegex(regex) => {
+            let regex = regex.clone();
+            Box::new(move |entry| {
+                let commit = repo.store().get_commit(&entry.commit_id()).unwrap();
+                regex.is_match(&commit.author().name) || regex.is_match(&commit.author().email)
+            })
+        }
         RevsetFilterPredicate::Committer(needle) => {
             let needle = needle.clone();
             Box::new(move |entry| {
@@ -1817,6 +2145,34 @@ fn build_predicate_fn<'repo>(
                     || commit.committer().email.contains(needle.as_str())
             })
         }
+        RevsetFilterPredicate::AuthoredAfter(timestamp) => {
+            let timestamp = timestamp.clone();
+            Box::new(move |entry| {
+                let commit = repo.store().get_commit(&entry.commit_id()).unwrap();
+                commit.author().timestamp.timestamp >= timestamp
+            })
+        }
+        RevsetFilterPredicate::AuthoredBefore(timestamp) => {
+            let timestamp = timestamp.clone();
+            Box::new(move |entry| {
+                let commit = repo.store().get_commit(&entry.commit_id()).unwrap();
+                commit.author().timestamp.timestamp < timestamp
+            })
+        }
+        RevsetFilterPredicate::CommittedAfter(timestamp) => {
+            let timestamp = timestamp.clone();
+            Box::new(move |entry| {
+                let commit = repo.store().get_commit(&entry.commit_id()).unwrap();
+                commit.committer().timestamp.timestamp >= timestamp
+            })
+        }
+        RevsetFilterPredicate::CommittedBefore(timestamp) => {
+            let timestamp = timestamp.clone();
+            Box::new(move |entry| {
+                let commit = repo.store().get_commit(&entry.commit_id()).unwrap();
+                commit.committer().timestamp.timestamp < timestamp
+            })
+        }
         RevsetFilterPredicate::Empty => {
             Box::new(move |entry| !has_diff_from_parent(repo, entry, &EverythingMatcher))
         }
@@ -1897,6 +2253,13 @@ mod tests {
             wc_symbol.ancestors(),
             Rc::new(RevsetExpression::Ancestors(wc_symbol.clone()))
         );
+        assert_eq!(
+            wc_symbol.nth_ancestor(2),
+            Rc::new(RevsetExpression::NthAncestor {
+                base: wc_symbol.clone(),
+                generation: 2,
+            })
+        );
         assert_eq!(
             foo_symbol.children(),
             Rc::new(RevsetExpression::Children(foo_symbol.clone()))
@@ -2048,6 +2411,25 @@ mod tests {
             parse("foo+++"),
             Ok(foo_symbol.children().children().children())
         );
+        // Parse "ancestor" operator
+        assert_eq!(parse("foo~2"), Ok(foo_symbol.nth_ancestor(2)));
+        assert_eq!(parse("foo~0-"), Ok(foo_symbol.nth_ancestor(0).parents()));
+        assert_eq!(parse("foo-~2"), Ok(foo_symbol.parents().nth_ancestor(2)));
+        // "~" followed by something other than digits is still a difference
+        assert_eq!(
+            parse("foo~2a"),
+            Ok(foo_symbol.minus(&RevsetExpression::symbol("2a".to_string())))
+        );
+        assert_eq!(
+            parse("foo ~2"),
+            Ok(foo_symbol.minus(&RevsetExpression::symbol("2".to_string())))
+        );
+        assert_eq!(
+            parse("foo~99999999999"),
+            Err(RevsetParseErrorKind::InvalidAncestorGeneration(
+                "99999999999".to_string()
+            ))
+        );
         // Set operator associativity/precedence
         assert_eq!(parse("~x|y").unwrap(), parse("(~x)|y").unwrap());
         assert_eq!(parse("x&~y").unwrap(), parse("x&(~y)").unwrap());
@@ -2126,6 +2508,58 @@ mod tests {
                 RevsetFilterPredicate::Description("(foo)".to_string())
             ))
         );
+        assert_eq!(
+            parse("authored_after(2022-01-31)"),
+            Ok(RevsetExpression::filter(
+                RevsetFilterPredicate::AuthoredAfter(MillisSinceEpoch(1643587200000))
+            ))
+        );
+        assert_eq!(
+            parse(r#"committed_before("2022-01-31T01:00:00+01:00")"#),
+            Ok(RevsetExpression::filter(
+                RevsetFilterPredicate::CommittedBefore(MillisSinceEpoch(1643587200000))
+            ))
+        );
+        assert_eq!(
+            parse("authored_before(someday)"),
+            Err(RevsetParseErrorKind::InvalidFunctionArguments {
+                name: "authored_before".to_string(),
+                message: r#"Invalid date "someday", expected e.g. "2022-01-31", "2022-01-31T12:00:00+01:00", or "3 days ago""#.to_string()
+            })
+        );
+        assert_eq!(
+            parse("fork_point(@, foo)"),
+            Ok(wc_symbol.fork_point(&RevsetExpression::symbol("foo".to_string())))
+        );
+        assert_eq!(
+            parse("fork_point(@)"),
+            Err(RevsetParseErrorKind::InvalidFunctionArguments {
+                name: "fork_point".to_string(),
+                message: "Expected 2 arguments".to_string()
+            })
+        );
+        assert_eq!(
+            parse("nearest(mian)"),
+            Ok(RevsetExpression::nearest_branch("mian".to_string(), 2))
+        );
+        assert_eq!(
+            parse(r#"nearest("mian", 1)"#),
+            Ok(RevsetExpression::nearest_branch("mian".to_string(), 1))
+        );
+        assert_eq!(
+            parse("nearest(mian, two)"),
+            Err(RevsetParseErrorKind::InvalidFunctionArguments {
+                name: "nearest".to_string(),
+                message: "Expected function argument of type non-negative integer".to_string()
+            })
+        );
+        assert_eq!(
+            parse("nearest()"),
+            Err(RevsetParseErrorKind::InvalidFunctionArguments {
+                name: "nearest".to_string(),
+                message: "Expected 1 or 2 arguments".to_string()
+            })
+        );
         assert_eq!(
             parse("empty()"),
             Ok(RevsetExpression::filter(RevsetFilterPredicate::Empty))
@@ -2866,4 +3300,67 @@ mod tests {
         )
         "###);
     }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("", ""), 0);
+        assert_eq!(edit_distance("main", ""), 4);
+        assert_eq!(edit_distance("", "main"), 4);
+        assert_eq!(edit_distance("main", "main"), 0);
+        assert_eq!(edit_distance("mian", "main"), 2);
+        assert_eq!(edit_distance("mian", "maint"), 3);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("héllo", "hello"), 1);
+    }
+
+    #[test]
+    fn test_parse_date() {
+        let now = chrono::DateTime::parse_from_rfc3339("2022-01-31T12:30:00+01:00").unwrap();
+        let millis = |text: &str| {
+            MillisSinceEpoch(
+                chrono::DateTime::parse_from_rfc3339(text)
+                    .unwrap()
+                    .timestamp_millis(),
+            )
+        };
+        assert_eq!(
+            parse_date("2022-01-30", &now),
+            Some(millis("2022-01-30T00:00:00Z"))
+        );
+        assert_eq!(
+            parse_date("2022-01-30T08:00:00-05:00", &now),
+            Some(millis("2022-01-30T08:00:00-05:00"))
+        );
+        assert_eq!(
+            parse_date("now", &now),
+            Some(millis("2022-01-31T12:30:00+01:00"))
+        );
+        assert_eq!(
+            parse_date("today", &now),
+            Some(millis("2022-01-31T00:00:00+01:00"))
+        );
+        assert_eq!(
+            parse_date("yesterday", &now),
+            Some(millis("2022-01-30T00:00:00+01:00"))
+        );
+        assert_eq!(
+            parse_date("3 days ago", &now),
+            Some(millis("2022-01-28T12:30:00+01:00"))
+        );
+        assert_eq!(
+            parse_date("1 hour ago", &now),
+            Some(millis("2022-01-31T11:30:00+01:00"))
+        );
+        assert_eq!(
+            parse_date("2  weeks   ago", &now),
+            Some(millis("2022-01-17T12:30:00+01:00"))
+        );
+        assert_eq!(parse_date("3 fortnights ago", &now), None);
+        assert_eq!(parse_date("a day ago", &now), None);
+        assert_eq!(parse_date("someday", &now), None);
+        assert_eq!(parse_date("2022-13-01", &now), None);
+        assert_eq!(parse_date("1000000000 days ago", &now), None);
+        assert_eq!(parse_date("99999999999999 weeks ago", &now), None);
+        assert_eq!(parse_date("-9223372036854775808 seconds ago", &now), None);
+    }
 }
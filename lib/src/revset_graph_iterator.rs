@@ -314,7 +314,9 @@ pub struct ReverseRevsetGraphIterator<'repo> {
 }
 
 impl<'repo> ReverseRevsetGraphIterator<'repo> {
-    fn new<'revset>(input: RevsetGraphIterator<'revset, 'repo>) -> Self {
+    /// Reverses the graph yielded by `input`, which is usually a (possibly
+    /// truncated) `RevsetGraphIterator`.
+    pub fn new(input: impl IntoIterator<Item = (IndexEntry<'repo>, Vec<RevsetGraphEdge>)>) -> Self {
         let mut entries = vec![];
         let mut reverse_edges: HashMap<IndexPosition, Vec<RevsetGraphEdge>> = HashMap::new();
         for (entry, edges) in input {
@@ -349,3 +351,64 @@ impl<'repo> Iterator for ReverseRevsetGraphIterator<'repo> {
         self.items.pop()
     }
 }
+
+pub struct TopoGroupedRevsetGraphIterator<'repo> {
+    items: Vec<(IndexEntry<'repo>, Vec<RevsetGraphEdge>)>,
+}
+
+impl<'repo> TopoGroupedRevsetGraphIterator<'repo> {
+    /// Reorders the graph yielded by `input`, which is usually a (possibly
+    /// truncated) `RevsetGraphIterator`, so each branch's revisions are yielded
+    /// one after another instead of interleaved by index position. Revisions
+    /// are still yielded before their ancestors.
+    pub fn new(input: impl IntoIterator<Item = (IndexEntry<'repo>, Vec<RevsetGraphEdge>)>) -> Self {
+        let mut entries: HashMap<IndexPosition, (IndexEntry<'repo>, Vec<RevsetGraphEdge>)> =
+            HashMap::new();
+        let mut positions = vec![];
+        for (entry, edges) in input {
+            positions.push(entry.position());
+            entries.insert(entry.position(), (entry, edges));
+        }
+        // The number of children of each revision that haven't been yielded yet
+        let mut pending_children: HashMap<IndexPosition, usize> = HashMap::new();
+        for (_, edges) in entries.values() {
+            for edge in edges {
+                if entries.contains_key(&edge.target) {
+                    *pending_children.entry(edge.target).or_default() += 1;
+                }
+            }
+        }
+
+        // Start from the heads, newest first, and follow each branch down
+        // until reaching a revision that still has other children to yield
+        let mut stack = positions
+            .iter()
+            .rev()
+            .filter(|position| !pending_children.contains_key(position))
+            .copied()
+            .collect::<Vec<_>>();
+        let mut items = vec![];
+        while let Some(position) = stack.pop() {
+            let (entry, edges) = entries.remove(&position).unwrap();
+            for edge in edges.iter().rev() {
+                if let Some(count) = pending_children.get_mut(&edge.target) {
+                    *count -= 1;
+                    if *count == 0 {
+                        stack.push(edge.target);
+                    }
+                }
+            }
+            items.push((entry, edges));
+        }
+        items.reverse();
+        Self { items }
+    }
+}
+
+impl<'repo> Iterator for TopoGroupedRevsetGraphIterator<'repo> {
+    type Item = (IndexEntry<'repo>, Vec<RevsetGraphEdge>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.items.pop()
+    }
+}
@@ -60,6 +60,37 @@ impl UserSettings {
         })
     }
 
+    /// Returns settings with the repo's `config.toml` layered over these ones.
+    pub fn with_repo_config(&self, repo_path: &Path) -> Result<UserSettings, config::ConfigError> {
+        let config = config::Config::builder()
+            .add_source(self.config.clone())
+            .add_source(
+                config::File::from(repo_path.join("config.toml"))
+                    .required(false)
+                    .format(config::FileFormat::Toml),
+            )
+            .build()?;
+        Ok(UserSettings {
+            config,
+            timestamp: self.timestamp.clone(),
+        })
+    }
+
+    /// Returns settings with `overrides` layered over these ones.
+    pub fn with_overrides(
+        &self,
+        overrides: config::Config,
+    ) -> Result<UserSettings, config::ConfigError> {
+        let config = config::Config::builder()
+            .add_source(self.config.clone())
+            .add_source(overrides)
+            .build()?;
+        Ok(UserSettings {
+            config,
+            timestamp: self.timestamp.clone(),
+        })
+    }
+
     pub fn with_repo(&self, repo_path: &Path) -> Result<RepoSettings, config::ConfigError> {
         let config = config::Config::builder()
             .add_source(self.config.clone())
@@ -143,6 +174,84 @@ impl UserSettings {
             .unwrap_or(false)
     }
 
+    pub fn log_hide_empty_working_copy(&self) -> bool {
+        self.config
+            .get_bool("ui.log-hide-empty-working-copy")
+            .unwrap_or(false)
+    }
+
+    pub fn log_dim_root(&self) -> bool {
+        self.config.get_bool("ui.dim-root").unwrap_or(false)
+    }
+
+    pub fn log_color_working_copy(&self) -> bool {
+        self.config
+            .get_bool("ui.log-color-working-copy")
+            .unwrap_or(true)
+    }
+
+    pub fn graph_gutter_width(&self) -> usize {
+        self.config
+            .get_int("ui.graph.gutter-width")
+            .ok()
+            .and_then(|width| usize::try_from(width).ok())
+            .unwrap_or(0)
+    }
+
+    pub fn diff_word_wrap(&self) -> usize {
+        self.config
+            .get_int("ui.diff.word-wrap")
+            .ok()
+            .and_then(|width| usize::try_from(width).ok())
+            .unwrap_or(0)
+    }
+
+    /// Whether `jj log` warns when its only path argument looks like it was
+    /// meant to be a revision.
+    pub fn warn_path_revset(&self) -> bool {
+        self.config.get_bool("ui.warn-path-revset").unwrap_or(true)
+    }
+
+    /// The number of bytes `jj print` stops after, if any.
+    pub fn print_max_bytes(&self) -> Option<usize> {
+        self.config
+            .get_int("ui.print-max-bytes")
+            .ok()
+            .and_then(|max_bytes| usize::try_from(max_bytes).ok())
+    }
+
+    /// The `ui.graph.<name>-symbol` setting, or its alias
+    /// `ui.graph.<name>-char`.
+    fn graph_symbol(&self, name: &str) -> Option<String> {
+        self.config
+            .get_string(&format!("ui.graph.{name}-symbol"))
+            .or_else(|_| self.config.get_string(&format!("ui.graph.{name}-char")))
+            .ok()
+    }
+
+    pub fn graph_working_copy_symbol(&self) -> String {
+        self.graph_symbol("working-copy")
+            .unwrap_or_else(|| "@".to_string())
+    }
+
+    pub fn graph_commit_symbol(&self) -> String {
+        self.graph_symbol("commit")
+            .unwrap_or_else(|| "o".to_string())
+    }
+
+    pub fn graph_elided_symbol(&self) -> String {
+        self.graph_symbol("elided")
+            .unwrap_or_else(|| "~".to_string())
+    }
+
+    pub fn graph_style(&self) -> Option<String> {
+        self.config.get_string("ui.graph.style").ok()
+    }
+
+    pub fn graph_node_template(&self) -> Option<String> {
+        self.config.get_string("ui.graph.node-template").ok()
+    }
+
     pub fn config(&self) -> &config::Config {
         &self.config
     }
@@ -13,7 +13,10 @@
 // limitations under the License.
 
 use jujutsu_lib::backend::{Conflict, ConflictPart, TreeValue};
-use jujutsu_lib::conflicts::{materialize_conflict, parse_conflict, update_conflict_from_content};
+use jujutsu_lib::conflicts::{
+    materialize_conflict, materialize_conflict_with_style, parse_conflict,
+    update_conflict_from_content, ConflictMarkerStyle,
+};
 use jujutsu_lib::files::{ConflictHunk, MergeHunk};
 use jujutsu_lib::repo_path::RepoPath;
 use jujutsu_lib::store::Store;
@@ -269,6 +272,76 @@ line 5
     );
 }
 
+#[test]
+fn test_materialize_conflict_styles() {
+    let test_repo = TestRepo::init(false);
+    let store = test_repo.repo.store();
+
+    let path = RepoPath::from_internal_string("file");
+    let file_part = |contents: &str| ConflictPart {
+        value: TreeValue::File {
+            id: testutils::write_file(store, &path, contents),
+            executable: false,
+        },
+    };
+    let mut conflict = Conflict {
+        removes: vec![file_part("line 1\nbase\nline 3\n")],
+        adds: vec![
+            file_part("line 1\nleft\nline 3\n"),
+            file_part("line 1\nright\nline 3\n"),
+        ],
+    };
+    let materialize = |conflict: &Conflict, style| {
+        let mut result: Vec<u8> = vec![];
+        materialize_conflict_with_style(store, &path, conflict, style, &mut result).unwrap();
+        String::from_utf8(result).unwrap()
+    };
+    insta::assert_snapshot!(materialize(&conflict, ConflictMarkerStyle::Git), @r###"
+    line 1
+    <<<<<<<
+    left
+    |||||||
+    base
+    =======
+    right
+    >>>>>>>
+    line 3
+    "###);
+    insta::assert_snapshot!(materialize(&conflict, ConflictMarkerStyle::Snapshot), @r###"
+    line 1
+    <<<<<<<
+    +++++++
+    left
+    -------
+    base
+    +++++++
+    right
+    >>>>>>>
+    line 3
+    "###);
+
+    // Git's markers can't represent more than two sides, so those conflicts are
+    // written as snapshots
+    conflict.removes.push(file_part("line 1\nbase 2\nline 3\n"));
+    conflict.adds.push(file_part("line 1\nthird\nline 3\n"));
+    insta::assert_snapshot!(materialize(&conflict, ConflictMarkerStyle::Git), @r###"
+    line 1
+    <<<<<<<
+    +++++++
+    left
+    -------
+    base
+    +++++++
+    right
+    -------
+    base 2
+    +++++++
+    third
+    >>>>>>>
+    line 3
+    "###);
+}
+
 #[test]
 fn test_parse_conflict_resolved() {
     assert_eq!(
@@ -23,7 +23,7 @@ use jujutsu_lib::repo::RepoRef;
 use jujutsu_lib::repo_path::RepoPath;
 use jujutsu_lib::revset::{
     self, optimize, parse, resolve_symbol, RevsetAliasesMap, RevsetError, RevsetExpression,
-    RevsetWorkspaceContext,
+    RevsetFilterPredicate, RevsetRegex, RevsetWorkspaceContext,
 };
 use jujutsu_lib::workspace::Workspace;
 use test_case::test_case;
@@ -648,6 +648,75 @@ fn test_evaluate_expression_roots(use_git: bool) {
     );
 }
 
+#[test_case(false ; "local backend")]
+#[test_case(true ; "git backend")]
+fn test_evaluate_expression_fork_point(use_git: bool) {
+    let settings = testutils::user_settings();
+    let test_repo = TestRepo::init(use_git);
+    let repo = &test_repo.repo;
+
+    let root_commit = repo.store().root_commit();
+    let mut tx = repo.start_transaction(&settings, "test");
+    let mut_repo = tx.mut_repo();
+    let mut graph_builder = CommitGraphBuilder::new(&settings, mut_repo);
+    let commit1 = graph_builder.initial_commit();
+    let commit2 = graph_builder.commit_with_parents(&[&commit1]);
+    let commit3 = graph_builder.commit_with_parents(&[&commit2]);
+    let commit4 = graph_builder.commit_with_parents(&[&commit2]);
+    let commit5 = graph_builder.commit_with_parents(&[&commit4]);
+    let commit6 = graph_builder.initial_commit();
+    let commit7 = graph_builder.commit_with_parents(&[&commit3, &commit5]);
+    let commit8 = graph_builder.commit_with_parents(&[&commit5, &commit3]);
+    mut_repo.set_local_branch("main".to_string(), RefTarget::Normal(commit3.id().clone()));
+    mut_repo.set_local_branch(
+        "feature".to_string(),
+        RefTarget::Normal(commit5.id().clone()),
+    );
+
+    // The fork point of two branches is the commit they forked off from
+    assert_eq!(
+        resolve_commit_ids(mut_repo.as_repo_ref(), "fork_point(main, feature)"),
+        vec![commit2.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo.as_repo_ref(), "fork_point(feature, main)"),
+        vec![commit2.id().clone()]
+    );
+
+    // The fork point of a commit and its ancestor is the ancestor
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo.as_repo_ref(),
+            &format!("fork_point({}, feature)", commit1.id().hex())
+        ),
+        vec![commit1.id().clone()]
+    );
+
+    // Otherwise unrelated histories fork off from the root commit
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo.as_repo_ref(),
+            &format!("fork_point(main, {})", commit6.id().hex())
+        ),
+        vec![root_commit.id().clone()]
+    );
+
+    // Criss-cross merges have several fork points
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo.as_repo_ref(),
+            &format!("fork_point({}, {})", commit7.id().hex(), commit8.id().hex())
+        ),
+        vec![commit5.id().clone(), commit3.id().clone()]
+    );
+
+    // The fork point with an empty set is empty
+    assert_eq!(
+        resolve_commit_ids(mut_repo.as_repo_ref(), "fork_point(main, none())"),
+        vec![]
+    );
+}
+
 #[test_case(false ; "local backend")]
 #[test_case(true ; "git backend")]
 fn test_evaluate_expression_parents(use_git: bool) {
@@ -716,6 +785,71 @@ fn test_evaluate_expression_parents(use_git: bool) {
     );
 }
 
+#[test_case(false ; "local backend")]
+#[test_case(true ; "git backend")]
+fn test_evaluate_expression_nth_ancestor(use_git: bool) {
+    let settings = testutils::user_settings();
+    let test_workspace = TestWorkspace::init(&settings, use_git);
+    let repo = &test_workspace.repo;
+
+    let root_commit = repo.store().root_commit();
+    let mut tx = repo.start_transaction(&settings, "test");
+    let mut_repo = tx.mut_repo();
+    let mut graph_builder = CommitGraphBuilder::new(&settings, mut_repo);
+    let commit1 = graph_builder.initial_commit();
+    let commit2 = graph_builder.commit_with_parents(&[&commit1]);
+    let commit3 = graph_builder.commit_with_parents(&[&commit2]);
+    let commit4 = graph_builder.commit_with_parents(&[&commit3, &commit1]);
+    let commit5 = graph_builder.commit_with_parents(&[&commit4]);
+
+    // Generation 0 is the commit itself
+    assert_eq!(
+        resolve_commit_ids(mut_repo.as_repo_ref(), &format!("{}~0", commit3.id().hex())),
+        vec![commit3.id().clone()]
+    );
+
+    // Follows the only parent
+    assert_eq!(
+        resolve_commit_ids(mut_repo.as_repo_ref(), &format!("{}~2", commit3.id().hex())),
+        vec![commit1.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo.as_repo_ref(), &format!("{}~3", commit3.id().hex())),
+        vec![root_commit.id().clone()]
+    );
+
+    // Stepping back past the root commit yields nothing
+    assert_eq!(
+        resolve_commit_ids(mut_repo.as_repo_ref(), &format!("{}~4", commit3.id().hex())),
+        vec![]
+    );
+
+    // Ancestors of all commits in input are returned, without duplicates
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo.as_repo_ref(),
+            &format!("({} | {})~1", commit2.id().hex(), commit3.id().hex())
+        ),
+        vec![commit2.id().clone(), commit1.id().clone()]
+    );
+
+    // Stepping to a merge commit is fine, but not through it
+    assert_eq!(
+        resolve_commit_ids(mut_repo.as_repo_ref(), &format!("{}~1", commit5.id().hex())),
+        vec![commit4.id().clone()]
+    );
+    let expression = parse(
+        &format!("{}~2", commit5.id().hex()),
+        &RevsetAliasesMap::new(),
+        None,
+    )
+    .unwrap();
+    assert_eq!(
+        expression.evaluate(mut_repo.as_repo_ref(), None).err(),
+        Some(RevsetError::AmbiguousAncestor(commit4.id().hex()))
+    );
+}
+
 #[test_case(false ; "local backend")]
 #[test_case(true ; "git backend")]
 fn test_evaluate_expression_children(use_git: bool) {
@@ -1321,6 +1455,58 @@ fn test_evaluate_expression_branches(use_git: bool) {
     );
 }
 
+#[test_case(false ; "local backend")]
+#[test_case(true ; "git backend")]
+fn test_evaluate_expression_nearest(use_git: bool) {
+    let settings = testutils::user_settings();
+    let test_repo = TestRepo::init(use_git);
+    let repo = &test_repo.repo;
+
+    let mut tx = repo.start_transaction(&settings, "test");
+    let mut_repo = tx.mut_repo();
+
+    let commit1 = create_random_commit(&settings, repo).write_to_repo(mut_repo);
+    let commit2 = create_random_commit(&settings, repo).write_to_repo(mut_repo);
+    mut_repo.set_local_branch("main".to_string(), RefTarget::Normal(commit1.id().clone()));
+    mut_repo.set_local_branch("maint".to_string(), RefTarget::Normal(commit2.id().clone()));
+
+    // The closest branch name wins
+    assert_eq!(
+        resolve_commit_ids(mut_repo.as_repo_ref(), r#"nearest("mian")"#),
+        vec![commit1.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo.as_repo_ref(), "nearest(mainn)"),
+        vec![commit1.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo.as_repo_ref(), "nearest(miant)"),
+        vec![commit2.id().clone()]
+    );
+    // An exact match is at distance 0
+    assert_eq!(
+        resolve_commit_ids(mut_repo.as_repo_ref(), "nearest(maint, 0)"),
+        vec![commit2.id().clone()]
+    );
+
+    // It's an error if no branch is close enough
+    let expression = optimize(parse("nearest(mian, 1)", &RevsetAliasesMap::new(), None).unwrap());
+    assert_eq!(
+        expression
+            .evaluate(mut_repo.as_repo_ref(), None)
+            .map(|set| set.iter().commit_ids().collect::<Vec<_>>()),
+        Err(RevsetError::NoNearbyBranch {
+            name: "mian".to_string(),
+            max_distance: 1
+        })
+    );
+    // ...unless it's wrapped in present()
+    assert_eq!(
+        resolve_commit_ids(mut_repo.as_repo_ref(), "present(nearest(develop))"),
+        vec![]
+    );
+}
+
 #[test_case(false ; "local backend")]
 #[test_case(true ; "git backend")]
 fn test_evaluate_expression_remote_branches(use_git: bool) {
@@ -1539,6 +1725,21 @@ fn test_evaluate_expression_author(use_git: bool) {
         resolve_commit_ids(mut_repo.as_repo_ref(), "heads() & author(\"name2\")"),
         vec![]
     );
+    // Can match with a regex
+    let resolve_regex = |pattern: &str| -> Vec<CommitId> {
+        let regex = RevsetRegex::new(regex::Regex::new(pattern).unwrap());
+        RevsetExpression::filter(RevsetFilterPredicate::AuthorRegex(regex))
+            .evaluate(mut_repo.as_repo_ref(), None)
+            .unwrap()
+            .iter()
+            .commit_ids()
+            .collect()
+    };
+    assert_eq!(
+        resolve_regex("^(NAME|email)[13]$"),
+        vec![commit3.id().clone(), commit1.id().clone()]
+    );
+    assert_eq!(resolve_regex("(?i)^NAME2$"), vec![commit2.id().clone()]);
 }
 
 #[test_case(false ; "local backend")]
@@ -1604,6 +1805,94 @@ fn test_evaluate_expression_committer(use_git: bool) {
     );
 }
 
+#[test_case(false ; "local backend")]
+#[test_case(true ; "git backend")]
+fn test_evaluate_expression_date(use_git: bool) {
+    let settings = testutils::user_settings();
+    let test_repo = TestRepo::init(use_git);
+    let repo = &test_repo.repo;
+
+    let mut tx = repo.start_transaction(&settings, "test");
+    let mut_repo = tx.mut_repo();
+
+    let signature = |seconds: i64| Signature {
+        name: "name".to_string(),
+        email: "email".to_string(),
+        timestamp: Timestamp {
+            timestamp: MillisSinceEpoch(seconds * 1000),
+            tz_offset: 0,
+        },
+    };
+    let jan1 = 1640995200; // 2022-01-01T00:00:00Z
+    let feb1 = 1643673600; // 2022-02-01T00:00:00Z
+    let mar1 = 1646092800; // 2022-03-01T00:00:00Z
+    let root_commit = repo.store().root_commit();
+    let commit1 = create_random_commit(&settings, repo)
+        .set_author(signature(jan1))
+        .set_committer(signature(mar1))
+        .write_to_repo(mut_repo);
+    let commit2 = create_random_commit(&settings, repo)
+        .set_parents(vec![commit1.id().clone()])
+        .set_author(signature(feb1))
+        .set_committer(signature(feb1))
+        .write_to_repo(mut_repo);
+    let commit3 = create_random_commit(&settings, repo)
+        .set_parents(vec![commit2.id().clone()])
+        .set_author(signature(mar1))
+        .set_committer(signature(mar1))
+        .write_to_repo(mut_repo);
+
+    // "after" includes the given time
+    assert_eq!(
+        resolve_commit_ids(mut_repo.as_repo_ref(), "authored_after(2022-02-01)"),
+        vec![commit3.id().clone(), commit2.id().clone()]
+    );
+    // "before" excludes the given time
+    assert_eq!(
+        resolve_commit_ids(mut_repo.as_repo_ref(), "authored_before(2022-02-01)"),
+        vec![commit1.id().clone(), root_commit.id().clone()]
+    );
+    // Committer dates are separate from author dates
+    assert_eq!(
+        resolve_commit_ids(mut_repo.as_repo_ref(), "committed_after(2022-02-15)"),
+        vec![commit3.id().clone(), commit1.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo.as_repo_ref(),
+            "committed_before(\"2022-02-01T00:00:01+00:00\")"
+        ),
+        vec![commit2.id().clone(), root_commit.id().clone()]
+    );
+    // Time zones are taken into account
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo.as_repo_ref(),
+            "authored_after(\"2022-02-01T01:00:00+01:00\")"
+        ),
+        vec![commit3.id().clone(), commit2.id().clone()]
+    );
+    // Composes with other operators to select a range
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo.as_repo_ref(),
+            "authored_after(2022-01-15) & authored_before(2022-02-15)"
+        ),
+        vec![commit2.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo.as_repo_ref(),
+            "authored_before(2022-01-15) | authored_after(2022-02-15)"
+        ),
+        vec![
+            commit3.id().clone(),
+            commit1.id().clone(),
+            root_commit.id().clone()
+        ]
+    );
+}
+
 #[test_case(false ; "local backend")]
 #[test_case(true ; "git backend")]
 fn test_evaluate_expression_union(use_git: bool) {
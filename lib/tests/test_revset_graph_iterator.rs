@@ -14,7 +14,7 @@
 
 use itertools::Itertools;
 use jujutsu_lib::revset::revset_for_commits;
-use jujutsu_lib::revset_graph_iterator::RevsetGraphEdge;
+use jujutsu_lib::revset_graph_iterator::{RevsetGraphEdge, TopoGroupedRevsetGraphIterator};
 use test_case::test_case;
 use testutils::{CommitGraphBuilder, TestRepo};
 
@@ -441,3 +441,53 @@ fn test_reverse_graph_iterator() {
     assert_eq!(commits[3].1, vec![RevsetGraphEdge::direct(pos_f)]);
     assert_eq!(commits[4].1, vec![]);
 }
+
+#[test]
+fn test_topo_grouped_graph_iterator() {
+    let settings = testutils::user_settings();
+    let test_repo = TestRepo::init(true);
+    let repo = &test_repo.repo;
+
+    // Tests that the revisions of each branch are yielded together, with the
+    // edges unchanged, even though the branches were committed interleaved.
+    //
+    //  E D
+    //  | |
+    //  C B
+    //  |/
+    //  A
+    //  |
+    // root
+    let mut tx = repo.start_transaction(&settings, "test");
+    let mut graph_builder = CommitGraphBuilder::new(&settings, tx.mut_repo());
+    let commit_a = graph_builder.initial_commit();
+    let commit_b = graph_builder.commit_with_parents(&[&commit_a]);
+    let commit_c = graph_builder.commit_with_parents(&[&commit_a]);
+    let commit_d = graph_builder.commit_with_parents(&[&commit_b]);
+    let commit_e = graph_builder.commit_with_parents(&[&commit_c]);
+    let repo = tx.commit();
+
+    let pos_a = repo.index().commit_id_to_pos(commit_a.id()).unwrap();
+    let pos_b = repo.index().commit_id_to_pos(commit_b.id()).unwrap();
+    let pos_c = repo.index().commit_id_to_pos(commit_c.id()).unwrap();
+
+    let revset = revset_for_commits(
+        repo.as_repo_ref(),
+        &[&commit_a, &commit_b, &commit_c, &commit_d, &commit_e],
+    );
+    let commits = revset.iter().graph().collect_vec();
+    assert_eq!(commits[1].0.commit_id(), *commit_d.id());
+    assert_eq!(commits[2].0.commit_id(), *commit_c.id());
+
+    let commits = TopoGroupedRevsetGraphIterator::new(revset.iter().graph()).collect_vec();
+    assert_eq!(commits.len(), 5);
+    assert_eq!(commits[0].0.commit_id(), *commit_e.id());
+    assert_eq!(commits[1].0.commit_id(), *commit_c.id());
+    assert_eq!(commits[2].0.commit_id(), *commit_d.id());
+    assert_eq!(commits[3].0.commit_id(), *commit_b.id());
+    assert_eq!(commits[4].0.commit_id(), *commit_a.id());
+    assert_eq!(commits[0].1, vec![RevsetGraphEdge::direct(pos_c)]);
+    assert_eq!(commits[1].1, vec![RevsetGraphEdge::direct(pos_a)]);
+    assert_eq!(commits[2].1, vec![RevsetGraphEdge::direct(pos_b)]);
+    assert_eq!(commits[3].1, vec![RevsetGraphEdge::direct(pos_a)]);
+}
@@ -13,18 +13,16 @@
 // limitations under the License.
 
 use std::collections::{HashSet, VecDeque};
-use std::env::ArgsOs;
 use std::ffi::{OsStr, OsString};
 use std::fmt::Debug;
-use std::iter;
 use std::ops::Deref;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 use std::sync::Arc;
+use std::{env, iter};
 
-use clap;
 use clap::builder::{NonEmptyStringValueParser, TypedValueParser, ValueParserFactory};
-use clap::{Arg, ArgMatches, Command, Error, FromArgMatches};
+use clap::{self, Arg, ArgMatches, Command, Error, FromArgMatches};
 use git2::{Oid, Repository};
 use itertools::Itertools;
 use jujutsu_lib::backend::{BackendError, CommitId, TreeId};
@@ -51,7 +49,7 @@ use jujutsu_lib::working_copy::{
 use jujutsu_lib::workspace::{Workspace, WorkspaceInitError, WorkspaceLoadError};
 use jujutsu_lib::{dag_walk, file_util, git, revset};
 
-use crate::config::read_config;
+use crate::config::{read_config, shell_quote, split_shell_words, ConfigLayers, ConfigSource};
 use crate::diff_edit::{ConflictResolveError, DiffEditError};
 use crate::formatter::Formatter;
 use crate::templater::TemplateFormatter;
@@ -240,7 +238,9 @@ impl CommandHelper {
         self.backend_factories = backend_factories;
     }
 
-    pub fn workspace_helper(&self, ui: &mut Ui) -> Result<WorkspaceCommandHelper, CommandError> {
+    /// Loads the workspace at `-R` (or the current directory) and layers the
+    /// config of its repo over the user's config in `ui`.
+    pub fn load_workspace(&self, ui: &mut Ui) -> Result<Workspace, CommandError> {
         let wc_path_str = self.global_args.repository.as_deref().unwrap_or(".");
         let wc_path = ui.cwd().join(wc_path_str);
         let workspace =
@@ -268,6 +268,12 @@ jj init --git-repo=.",
                     WorkspaceLoadError::NonUnicodePath => user_error(err.to_string()),
                 }
             })?;
+        ui.load_repo_config(workspace.repo_path(), &self.global_args.config_toml)?;
+        Ok(workspace)
+    }
+
+    pub fn workspace_helper(&self, ui: &mut Ui) -> Result<WorkspaceCommandHelper, CommandError> {
+        let workspace = self.load_workspace(ui)?;
         let repo_loader = workspace.repo_loader();
         let op_heads = resolve_op_for_load(
             repo_loader.op_store(),
@@ -535,6 +541,12 @@ impl WorkspaceCommandHelper {
         RepoPath::parse_fs_path(&self.cwd, self.workspace_root(), input)
     }
 
+    /// Like `parse_file_path()`, but relative to the workspace root instead of
+    /// the current directory.
+    pub fn parse_repo_relative_path(&self, input: &str) -> Result<RepoPath, FsPathParseError> {
+        RepoPath::parse_fs_path(self.workspace_root(), self.workspace_root(), input)
+    }
+
     pub fn matcher_from_values(&self, values: &[String]) -> Result<Box<dyn Matcher>, CommandError> {
         if values.is_empty() {
             Ok(Box::new(EverythingMatcher))
@@ -627,6 +639,38 @@ impl WorkspaceCommandHelper {
         Ok(revset::optimize(expression))
     }
 
+    /// Parses the configured `ui.default-revset`, reporting errors in terms of
+    /// the config option rather than the command line.
+    pub fn parse_default_revset(&self) -> Result<Rc<RevsetExpression>, CommandError> {
+        let revset_str = self.settings.default_revset();
+        let err = match self.parse_revset(&revset_str) {
+            Ok(expression) => return Ok(expression),
+            Err(err) => err,
+        };
+        let message = iter::successors(Some(&err), |e| e.origin()).join("\n");
+        let repo_config_path = self.workspace.repo_path().join("config.toml");
+        let layers = load_config_layers(&self.global_args, Some(&repo_config_path))?;
+        let hint = match layers.find_source("ui.default-revset") {
+            Some(ConfigSource::Env(var)) => {
+                format!("The setting comes from the {var} environment variable. Fix or unset it.")
+            }
+            Some(ConfigSource::CommandLine) => {
+                "The setting comes from a --config-toml argument.".to_string()
+            }
+            Some(ConfigSource::File(path)) => {
+                format!("Fix the `ui.default-revset` setting in {}.", path.display())
+            }
+            Some(ConfigSource::Profile(name)) => {
+                format!("Fix the `ui.default-revset` setting in the {name:?} profile.")
+            }
+            _ => "Fix the `ui.default-revset` setting in your config.".to_string(),
+        };
+        Err(user_error_with_hint(
+            format!("Invalid `ui.default-revset` {revset_str:?}: {message}"),
+            hint,
+        ))
+    }
+
     pub fn evaluate_revset<'repo>(
         &'repo self,
         revset_expression: &RevsetExpression,
@@ -1255,6 +1299,10 @@ pub struct GlobalArgs {
     )]
     pub at_operation: String,
     /// When to colorize output (always, never, auto)
+    ///
+    /// This overrides the `ui.color` setting and the `NO_COLOR` environment
+    /// variable for this command. With `auto`, color is only used when the
+    /// output goes to a terminal.
     #[arg(
         long,
         value_name = "WHEN",
@@ -1263,12 +1311,11 @@ pub struct GlobalArgs {
     )]
     pub color: Option<ColorChoice>,
     /// Disable the pager
-    #[arg(
-        long,
-        value_name = "WHEN",
-        global = true,
-        help_heading = "Global Options"
-    )]
+    ///
+    /// The output is written directly to stdout, even if `ui.pager` or
+    /// `$PAGER` is set. The pager is also never used when stdout isn't a
+    /// terminal, e.g. when the output is piped to another program.
+    #[arg(long, global = true, help_heading = "Global Options")]
     pub no_pager: bool,
     /// Additional configuration options
     //  TODO: Introduce a `--config` option with simpler syntax for simple
@@ -1280,6 +1327,17 @@ pub struct GlobalArgs {
         help_heading = "Global Options"
     )]
     pub config_toml: Vec<String>,
+    /// Use the settings in the `[profiles.<NAME>]` table of the config
+    ///
+    /// The profile's settings override the rest of the user's config. This
+    /// overrides the `JJ_PROFILE` environment variable.
+    #[arg(
+        long,
+        value_name = "NAME",
+        global = true,
+        help_heading = "Global Options"
+    )]
+    pub profile: Option<String>,
     /// Enable verbose logging
     #[arg(long, short = 'v', global = true, help_heading = "Global Options")]
     pub verbose: bool,
@@ -1321,6 +1379,31 @@ impl ValueParserFactory for RevisionArg {
     }
 }
 
+/// Loads the layers of the config separately, taking the `--profile`,
+/// `--config-toml`, and `--color` arguments into account.
+pub fn load_config_layers(
+    global_args: &GlobalArgs,
+    repo_config_path: Option<&Path>,
+) -> Result<ConfigLayers, CommandError> {
+    // `--color` is passed on as the last `--config-toml` argument
+    let (color_toml, toml_strs) = match global_args.config_toml.split_last() {
+        Some((last, rest)) if global_args.color.is_some() => (Some(last), rest),
+        _ => (None, global_args.config_toml.as_slice()),
+    };
+    let profile = global_args
+        .profile
+        .clone()
+        .or_else(crate::config::env_profile);
+    let mut layers = ConfigLayers::load(profile.as_deref(), repo_config_path, toml_strs)
+        .map_err(|err| CommandError::ConfigError(err.to_string()))?;
+    if let Some(toml_str) = color_toml {
+        layers
+            .push_override(ConfigSource::ColorArg, toml_str)
+            .map_err(|err| CommandError::ConfigError(err.to_string()))?;
+    }
+    Ok(layers)
+}
+
 pub fn create_ui() -> (Ui, Result<(), CommandError>) {
     // TODO: We need to do some argument parsing here, at least for things like
     // --config, and for reading user configs from the repo pointed to by -R.
@@ -1421,10 +1504,149 @@ fn resolve_aliases(
     }
 }
 
+/// Returns the `ui.log-default-args` setting. It's either a list, or a string
+/// of arguments that are split like in a shell.
+fn log_default_args(user_settings: &UserSettings) -> Vec<String> {
+    let config = user_settings.config();
+    if let Ok(args) = config.get_string("ui.log-default-args") {
+        return split_shell_words(&args);
+    }
+    config
+        .get::<Vec<String>>("ui.log-default-args")
+        .unwrap_or_default()
+}
+
+/// Returns the index of the subcommand in `string_args`, skipping the global
+/// options before it and their values.
+fn find_subcommand_index(app: &clap::Command, string_args: &[String]) -> Option<usize> {
+    let takes_value = |arg: &str| {
+        app.get_arguments().any(|option| {
+            let matches_name = match arg.strip_prefix("--") {
+                Some(name) => option
+                    .get_long_and_visible_aliases()
+                    .map_or(false, |names| names.contains(&name)),
+                None => arg.len() == 2 && option.get_short() == arg.chars().nth(1),
+            };
+            matches_name && option.get_action().takes_values()
+        })
+    };
+    let mut i = 1;
+    while i < string_args.len() {
+        let arg = &string_args[i];
+        if arg == "--" {
+            return None;
+        } else if !arg.starts_with('-') {
+            return Some(i);
+        } else if !arg.contains('=') && takes_value(arg) {
+            i += 1;
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Inserts the `ui.log-default-args` setting right after the `log` subcommand,
+/// so that the arguments given on the command line override it. Since several
+/// templates are concatenated, it's an error to give templates both there and
+/// on the command line. A boolean flag can be turned off again with its
+/// negation, e.g. `--no-reversed` for `--reversed` and `--graph` for
+/// `--no-graph`.
+fn insert_log_default_args(
+    user_settings: &UserSettings,
+    app: &clap::Command,
+    mut string_args: Vec<String>,
+) -> Result<Vec<String>, CommandError> {
+    let log_index = match find_subcommand_index(app, &string_args) {
+        Some(i) if string_args[i] == "log" => i,
+        _ => return Ok(string_args),
+    };
+    let log_command = app.find_subcommand("log").unwrap();
+    let mut log_args = log_default_args(user_settings);
+    let command_line_args = string_args.split_off(log_index + 1);
+    let default_template_args = template_args(&log_args);
+    if !default_template_args.is_empty() && !template_args(&command_line_args).is_empty() {
+        let source = if env::var_os("JJ_LOG_DEFAULT_ARGS").is_some() {
+            "the JJ_LOG_DEFAULT_ARGS environment variable"
+        } else {
+            "the ui.log-default-args config"
+        };
+        return Err(user_error_with_hint(
+            format!(
+                "Templates were given both on the command line and in {source} (`{}`)",
+                default_template_args
+                    .iter()
+                    .map(|arg| shell_quote(arg))
+                    .join(" ")
+            ),
+            "Give the templates in only one of them.",
+        ));
+    }
+    log_args.extend(command_line_args);
+    string_args.extend(without_negated_flags(log_command, log_args));
+    Ok(string_args)
+}
+
+fn is_template_arg(arg: &str) -> bool {
+    arg.starts_with("-T") || arg == "--template" || arg.starts_with("--template=")
+}
+
+/// Returns the template arguments in `args`, including the values of the ones
+/// that are given separately.
+fn template_args(args: &[String]) -> Vec<&str> {
+    let mut result = vec![];
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            break;
+        } else if arg == "-T" || arg == "--template" {
+            result.push(arg.as_str());
+            result.extend(args.next().map(|template| template.as_str()));
+        } else if is_template_arg(arg) {
+            result.push(arg.as_str());
+        }
+    }
+    result
+}
+
+/// Removes the negations of boolean flags from `log_args`, together with the
+/// earlier occurrences of the flags they negate.
+fn without_negated_flags(log_command: &clap::Command, log_args: Vec<String>) -> Vec<String> {
+    let find_long = |name: &str| {
+        log_command
+            .get_arguments()
+            .find(|arg| arg.get_long() == Some(name))
+    };
+    let mut result = vec![];
+    let mut log_args = log_args.into_iter();
+    while let Some(arg) = log_args.next() {
+        if arg == "--" {
+            result.push(arg);
+            result.extend(log_args.by_ref());
+            break;
+        }
+        let negated_flag = arg
+            .strip_prefix("--")
+            .filter(|name| find_long(*name).is_none())
+            .and_then(|name| match name.strip_prefix("no-") {
+                Some(flag_name) => find_long(flag_name),
+                None => find_long(&format!("no-{name}")),
+            })
+            .filter(|flag| matches!(flag.get_action(), clap::ArgAction::SetTrue));
+        if let Some(flag) = negated_flag {
+            let long = format!("--{}", flag.get_long().unwrap());
+            let short = flag.get_short().map(|c| format!("-{c}"));
+            result.retain(|arg| *arg != long && Some(arg) != short.as_ref());
+        } else {
+            result.push(arg);
+        }
+    }
+    result
+}
+
 pub fn parse_args(
     ui: &mut Ui,
     app: clap::Command,
-    args_os: ArgsOs,
+    args_os: impl IntoIterator<Item = OsString>,
 ) -> Result<(CommandHelper, ArgMatches), CommandError> {
     let mut string_args: Vec<String> = vec![];
     for arg_os in args_os {
@@ -1436,6 +1658,7 @@ pub fn parse_args(
     }
 
     let string_args = resolve_aliases(ui.settings(), &app, &string_args)?;
+    let string_args = insert_log_default_args(ui.settings(), &app, string_args)?;
     let matches = app.clone().try_get_matches_from(&string_args)?;
 
     let mut args: Args = Args::from_arg_matches(&matches).unwrap();
@@ -1447,6 +1670,13 @@ pub fn parse_args(
     if args.global_args.no_pager {
         ui.set_pagination(crate::ui::PaginationChoice::No);
     }
+    if let Some(profile) = &args.global_args.profile {
+        // The config was read before the arguments were parsed, so read it
+        // again with the requested profile
+        let settings = crate::config::read_config_with_profile(Some(profile))
+            .map_err(|err| CommandError::ConfigError(err.to_string()))?;
+        ui.reset_settings(settings);
+    }
     if !args.global_args.config_toml.is_empty() {
         ui.extra_toml_settings(&args.global_args.config_toml)?;
     }
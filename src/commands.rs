@@ -12,37 +12,45 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::{HashSet, VecDeque};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::fmt::Debug;
 use std::fs::OpenOptions;
 use std::io::{Read, Seek, SeekFrom, Write};
 use std::ops::{Deref, Range};
 use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
+use std::rc::Rc;
 use std::sync::{Arc, Mutex};
 use std::time::Instant;
 use std::{fs, io};
 
-use chrono::{FixedOffset, LocalResult, TimeZone, Utc};
+use chrono::{DateTime, FixedOffset, LocalResult, SecondsFormat, TimeZone, Utc};
 use clap::builder::NonEmptyStringValueParser;
 use clap::{ArgGroup, ArgMatches, CommandFactory, FromArgMatches, Subcommand};
 use itertools::Itertools;
-use jujutsu_lib::backend::{BackendError, CommitId, Timestamp, TreeValue};
+use jujutsu_lib::backend::{
+    BackendError, ChangeId, CommitId, Conflict, ConflictPart, Signature, Timestamp, TreeValue,
+};
 use jujutsu_lib::commit::Commit;
 use jujutsu_lib::commit_builder::CommitBuilder;
 use jujutsu_lib::dag_walk::topo_order_reverse;
 use jujutsu_lib::diff::{Diff, DiffHunk};
-use jujutsu_lib::files::DiffLine;
+use jujutsu_lib::files::{ConflictHunk, DiffLine};
 use jujutsu_lib::git::{GitFetchError, GitRefUpdate};
-use jujutsu_lib::index::IndexEntry;
-use jujutsu_lib::matchers::{EverythingMatcher, Matcher};
+use jujutsu_lib::index::{HexPrefix, IndexEntry, IndexPosition};
+use jujutsu_lib::matchers::{EverythingMatcher, FilesMatcher, Matcher};
 use jujutsu_lib::op_store::{BranchTarget, RefTarget, WorkspaceId};
 use jujutsu_lib::operation::Operation;
 use jujutsu_lib::refs::{classify_branch_push_action, BranchPushAction, BranchPushUpdate};
 use jujutsu_lib::repo::{ReadonlyRepo, RepoRef};
 use jujutsu_lib::repo_path::RepoPath;
-use jujutsu_lib::revset::{RevsetAliasesMap, RevsetExpression};
-use jujutsu_lib::revset_graph_iterator::{RevsetGraphEdge, RevsetGraphEdgeType};
+use jujutsu_lib::revset::{
+    Revset, RevsetAliasesMap, RevsetExpression, RevsetFilterPredicate, RevsetRegex,
+};
+use jujutsu_lib::revset_graph_iterator::{
+    ReverseRevsetGraphIterator, RevsetGraphEdge, RevsetGraphEdgeType,
+    TopoGroupedRevsetGraphIterator,
+};
 use jujutsu_lib::rewrite::{back_out_commit, merge_commit_trees, rebase_commit, DescendantRebaser};
 use jujutsu_lib::settings::UserSettings;
 use jujutsu_lib::store::Store;
@@ -59,11 +67,11 @@ use crate::cli_util::{
     CommandHelper, RevisionArg, WorkspaceCommandHelper,
 };
 use crate::config::FullCommandArgs;
-use crate::formatter::{Formatter, PlainTextFormatter};
-use crate::graphlog::{AsciiGraphDrawer, Edge};
+use crate::formatter::{Formatter, FormatterFactory, PlainTextFormatter};
+use crate::graphlog::{wrap_text, AsciiGraphDrawer, Edge, GraphStyle};
 use crate::progress::Progress;
 use crate::template_parser::TemplateParser;
-use crate::templater::Template;
+use crate::templater::{stable_hash, ListTemplate, Template};
 use crate::ui::Ui;
 
 #[derive(clap::Parser, Clone, Debug)]
@@ -107,6 +115,8 @@ enum Commands {
     Backout(BackoutArgs),
     #[command(subcommand)]
     Branch(BranchSubcommand),
+    #[command(subcommand)]
+    Config(ConfigSubcommand),
     /// Undo an operation (shortcut for `jj op undo`)
     Undo(OperationUndoArgs),
     #[command(subcommand)]
@@ -180,22 +190,247 @@ struct FilesArgs {
 }
 
 /// Print contents of a file in a revision
+///
+/// `jj cat` is the same command.
 #[derive(clap::Args, Clone, Debug)]
+#[command(visible_alias = "cat")]
 struct PrintArgs {
-    /// The revision to get the file contents from
+    /// The revision to get the file contents from. If this resolves to
+    /// several revisions, the file is printed at each of them, below a header
+    /// naming the revision.
     #[arg(long, short, default_value = "@")]
     revision: RevisionArg,
-    /// The file to print
-    #[arg(value_hint = clap::ValueHint::FilePath)]
-    path: String,
+    /// Get the file contents from the visible commit of the change with this
+    /// id (or unique prefix of one). Unlike with `-r`, the id is never
+    /// interpreted as a revset, and it's an error if the change is divergent.
+    #[arg(
+        long,
+        value_name = "CHANGE_ID",
+        conflicts_with_all = ["revision", "from", "to", "git_blob"]
+    )]
+    at_change: Option<String>,
+    /// The file to print, relative to the current directory
+    #[arg(value_hint = clap::ValueHint::FilePath, required_unless_present = "git_blob")]
+    path: Option<String>,
+    /// Interpret the path relative to the root of the workspace instead of
+    /// the current directory
+    #[arg(long, conflicts_with = "git_blob")]
+    repo_relative: bool,
+    /// Print the type and mode of the path (e.g. `100755 executable`) instead
+    /// of its contents
+    #[arg(long, visible_alias = "mode-only")]
+    mode: bool,
+    /// Print a line with the mode of the file (e.g. `mode: 100755`) before
+    /// its contents. A symlink's target is printed as `symlink -> TARGET`
+    /// instead of the contents.
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "mode", "count_lines", "git_object_info", "versions", "from", "to", "git_blob"
+        ]
+    )]
+    show_mode: bool,
+    /// Print the number of newline-terminated lines in the file instead of
+    /// its contents
+    #[arg(long, conflicts_with = "mode")]
+    count_lines: bool,
+    /// Print the type, id, and size of the git object that stores the path
+    /// (e.g. `blob 257cc5642cb1a054f08cc83f2d943e56fd3ebe99 4`) instead of its
+    /// contents. For a conflicted path, this is the object that stores the
+    /// conflict. Only in git-backed repos.
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "mode", "count_lines", "number", "resolve", "expand_keywords", "from", "to",
+            "git_blob", "versions", "base_rev", "binary", "max_bytes"
+        ]
+    )]
+    git_object_info: bool,
+    /// Prefix each line with its line number, like `cat -n`
+    #[arg(long, short = 'n', conflicts_with_all = ["mode", "count_lines"])]
+    number: bool,
+    /// If the file has conflicts, print it with each conflict resolved by
+    /// picking one side, instead of with conflict markers
+    #[arg(long, value_enum, value_name = "SIDE", conflicts_with = "mode")]
+    resolve: Option<ConflictSide>,
+    /// How to write the conflict markers if the file has conflicts
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = ConflictStyle::Diff,
+        conflicts_with_all = ["mode", "resolve"]
+    )]
+    conflict_style: ConflictStyle,
+    /// Show a diff between what resolving the file's conflict by picking
+    /// each of the two given sides (e.g. `ours,theirs`) would produce
+    #[arg(
+        long,
+        value_enum,
+        value_name = "SIDE,SIDE",
+        value_delimiter = ',',
+        conflicts_with_all = [
+            "mode", "count_lines", "number", "resolve", "expand_keywords", "from", "to",
+            "git_blob"
+        ]
+    )]
+    versions: Vec<ConflictSide>,
+    /// If the file has conflicts, show them relative to the file in this
+    /// revision instead of to the conflict's own base
+    #[arg(
+        long,
+        value_name = "REVISION",
+        conflicts_with_all = ["mode", "from", "to", "git_blob"]
+    )]
+    base_rev: Option<RevisionArg>,
+    /// Expand the `$Id$`, `$Author$`, and `$Date$` keywords in the printed
+    /// contents with the id, author, and author date of the revision
+    #[arg(long, conflicts_with = "mode")]
+    expand_keywords: bool,
+    /// Print the changes to the file since this revision as a Git-style diff,
+    /// instead of its contents
+    #[arg(
+        long,
+        value_name = "REVISION",
+        conflicts_with_all = [
+            "revision", "mode", "count_lines", "number", "resolve", "expand_keywords"
+        ]
+    )]
+    from: Option<RevisionArg>,
+    /// Print the changes to the file up to this revision as a Git-style diff,
+    /// instead of its contents
+    #[arg(
+        long,
+        value_name = "REVISION",
+        conflicts_with_all = [
+            "revision", "mode", "count_lines", "number", "resolve", "expand_keywords"
+        ]
+    )]
+    to: Option<RevisionArg>,
+    /// Print the raw contents of the git blob with this object id instead of
+    /// a file in a revision (only in git-backed repos)
+    #[arg(long, value_name = "OID", conflicts_with_all = ["path", "mode", "resolve", "expand_keywords", "from", "to"])]
+    git_blob: Option<String>,
+    /// Print the contents even if they look binary (contain a NUL byte or
+    /// aren't valid UTF-8), which is refused otherwise. The bytes are written
+    /// unchanged, e.g. for piping to another program, or for text that only
+    /// looks binary, like UTF-16.
+    #[arg(
+        long,
+        visible_alias = "assume-text",
+        conflicts_with_all = [
+            "mode", "count_lines", "number", "expand_keywords", "from", "to"
+        ]
+    )]
+    binary: bool,
+    /// Print at most this many bytes of the contents, followed by a note
+    /// saying how many were left out. Defaults to the `ui.print-max-bytes`
+    /// config setting, if set and the output goes to a terminal.
+    #[arg(long, value_name = "N", conflicts_with_all = ["mode", "count_lines"])]
+    max_bytes: Option<usize>,
+    /// Print all of the contents, even if `ui.print-max-bytes` is set
+    #[arg(long, conflicts_with_all = ["mode", "count_lines", "max_bytes"])]
+    no_limit: bool,
+    /// Convert the line endings of the contents
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = LineEnding::Keep,
+        conflicts_with_all = ["mode", "binary"]
+    )]
+    line_ending: LineEnding,
+}
+
+/// Which line endings `jj print` writes
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum LineEnding {
+    /// Unix line endings (`\n`)
+    Lf,
+    /// Windows line endings (`\r\n`)
+    Crlf,
+    /// Keep the line endings the file has
+    Keep,
+}
+
+impl LineEnding {
+    fn convert(self, contents: &[u8]) -> Vec<u8> {
+        let newline: &[u8] = match self {
+            LineEnding::Lf => b"\n",
+            LineEnding::Crlf => b"\r\n",
+            LineEnding::Keep => return contents.to_vec(),
+        };
+        let mut result = Vec::with_capacity(contents.len());
+        for line in contents.split_inclusive(|b| *b == b'\n') {
+            match line.strip_suffix(b"\n") {
+                Some(text) => {
+                    result.extend_from_slice(text.strip_suffix(b"\r").unwrap_or(text));
+                    result.extend_from_slice(newline);
+                }
+                None => result.extend_from_slice(line),
+            }
+        }
+        result
+    }
+}
+
+/// How `jj print` writes conflict markers
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum ConflictStyle {
+    /// jj's markers, showing each side as a diff from the base where possible
+    Diff,
+    /// jj's markers, showing each side and the base in full
+    Snapshot,
+    /// Git's diff3 markers (`<<<<<<<`, `|||||||`, `=======`, `>>>>>>>`)
+    Diff3,
+}
+
+impl ConflictStyle {
+    fn marker_style(self) -> conflicts::ConflictMarkerStyle {
+        match self {
+            ConflictStyle::Diff => conflicts::ConflictMarkerStyle::Diff,
+            ConflictStyle::Snapshot => conflicts::ConflictMarkerStyle::Snapshot,
+            ConflictStyle::Diff3 => conflicts::ConflictMarkerStyle::Git,
+        }
+    }
+}
+
+/// A side of a conflict between two sides
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum ConflictSide {
+    /// The common ancestor of both sides
+    Base,
+    /// The first side (e.g. the destination of a rebase)
+    Ours,
+    /// The second side (e.g. the commit being rebased)
+    Theirs,
+}
+
+impl ConflictSide {
+    fn name(self) -> &'static str {
+        match self {
+            ConflictSide::Base => "base",
+            ConflictSide::Ours => "ours",
+            ConflictSide::Theirs => "theirs",
+        }
+    }
+
+    fn pick(self, hunk: &ConflictHunk) -> &[u8] {
+        match self {
+            ConflictSide::Base => &hunk.removes[0],
+            ConflictSide::Ours => &hunk.adds[0],
+            ConflictSide::Theirs => &hunk.adds[1],
+        }
+    }
 }
 
 #[derive(clap::Args, Clone, Debug)]
-#[command(group(ArgGroup::new("format").args(&["summary", "git", "color_words"])))]
+#[command(group(ArgGroup::new("format").args(&["summary", "stat", "git", "color_words"])))]
 struct DiffFormatArgs {
     /// For each path, show only whether it was modified, added, or removed
     #[arg(long, short)]
     summary: bool,
+    /// For each path, show how many lines were inserted and deleted
+    #[arg(long)]
+    stat: bool,
     /// Show a Git-format diff
     #[arg(long)]
     git: bool,
@@ -242,6 +477,10 @@ struct ShowArgs {
     /// Ignored (but lets you pass `-r` for consistency with other commands)
     #[arg(short = 'r', hide = true)]
     unused_revision: bool,
+    /// Only print the trailers at the end of the description, like
+    /// "Signed-off-by: Some One <some.one@example.com>", one per line
+    #[arg(long, conflicts_with = "format")]
+    trailers: bool,
     #[command(flatten)]
     format: DiffFormatArgs,
 }
@@ -259,33 +498,227 @@ struct ShowArgs {
 struct StatusArgs {}
 
 /// Show commit history
+///
+/// Arguments from the `ui.log-default-args` setting (or the
+/// `JJ_LOG_DEFAULT_ARGS` environment variable) are inserted before the given
+/// ones. An option that is given again on the command line overrides them, and
+/// a boolean flag from them can be turned off with e.g. `--no-reversed` (or
+/// `--graph` for `--no-graph`).
 #[derive(clap::Args, Clone, Debug)]
+#[command(args_override_self = true)]
 struct LogArgs {
     /// Which revisions to show. Defaults to the `ui.default-revset` setting,
     /// or `@ | (remote_branches() | tags()).. | ((remote_branches() |
     /// tags())..)-` if it is not set.
     #[arg(long, short)]
     revisions: Option<RevisionArg>,
+    /// Show all versions of the given divergent change, starting from the
+    /// commit where they split
+    #[arg(long, value_name = "REVISION", conflicts_with = "revisions")]
+    since_divergence: Option<RevisionArg>,
+    /// Show the commits that aren't ancestors of the given revisions (e.g. a
+    /// branch), and the commits they forked off from
+    #[arg(
+        long,
+        value_name = "REVISIONS",
+        conflicts_with_all = ["revisions", "since_divergence"]
+    )]
+    since: Option<RevisionArg>,
+    /// Stop at the given revisions: leave them and their ancestors out, and
+    /// mark the cut with "~"
+    #[arg(long, value_name = "REVSET")]
+    oldest_ancestor: Option<RevisionArg>,
+    /// Only show revisions whose author's name or email contains this text
+    /// (ignoring case), or matches this regular expression if prefixed with
+    /// `regex:`
+    #[arg(long, value_name = "PATTERN")]
+    author: Option<String>,
+    /// Only show revisions authored at or after this date (e.g. "2022-01-31",
+    /// "yesterday", or "3 days ago")
+    #[arg(long, value_name = "DATE")]
+    after: Option<String>,
+    /// Only show revisions authored before this date (e.g. "2022-01-31",
+    /// "yesterday", or "3 days ago")
+    #[arg(long, value_name = "DATE")]
+    before: Option<String>,
     /// Show commits modifying the given paths
     #[arg(value_hint = clap::ValueHint::AnyPath)]
     paths: Vec<String>,
     /// Show revisions in the opposite order (older revisions first)
     #[arg(long)]
     reversed: bool,
+    /// Show at most this many revisions. These are always the newest ones
+    /// in the revset: with --reversed, the limit is applied first, and then
+    /// the remaining revisions are shown oldest first.
+    #[arg(long, value_name = "N")]
+    limit: Option<usize>,
     /// Don't show the graph, show a flat list of revisions
     #[arg(long)]
     no_graph: bool,
+    /// Number of extra columns of padding between the graph and the text of
+    /// each revision. Defaults to the `ui.graph.gutter-width` setting, or 0
+    /// if it is not set.
+    #[arg(long)]
+    gutter_width: Option<usize>,
+    /// Print a key explaining the graph symbols before the graph
+    #[arg(long, conflicts_with = "no_graph")]
+    legend: bool,
+    /// Render the graph node of each revision using the given template, which
+    /// should produce a single character. Defaults to the
+    /// `ui.graph.node-template` setting, if it is set.
+    #[arg(long, value_name = "TEMPLATE", conflicts_with = "no_graph")]
+    node_template: Option<String>,
+    /// Use the given symbol as the graph node of the revisions in the given
+    /// revset, e.g. `--mark 'description(reviewed)=✓'`. Can be repeated; if
+    /// several revsets contain a revision, the last one wins.
+    #[arg(long, value_name = "REVSET=SYMBOL", conflicts_with = "no_graph")]
+    mark: Vec<String>,
+    /// Leave out the working-copy commit if it's empty and has no
+    /// description, and show its parent as the working copy instead.
+    /// Defaults to the `ui.log-hide-empty-working-copy` setting.
+    #[arg(long, conflicts_with = "no_graph")]
+    hide_working_copy_if_empty: bool,
+    /// How to draw the edges of the graph
+    #[arg(long, value_enum, default_value_t = EdgeStyle::Merge, conflicts_with = "no_graph")]
+    edge_style: EdgeStyle,
+    /// Which characters to draw the graph with. Defaults to the
+    /// `ui.graph.style` setting, or `ascii` if it is not set.
+    #[arg(long, value_enum, conflicts_with = "no_graph")]
+    graph_style: Option<GraphStyle>,
+    /// Show the revisions of each branch one after another, instead of
+    /// interleaving parallel branches by when their revisions were created
+    #[arg(long, conflicts_with = "no_graph")]
+    topo_groups: bool,
+    /// Collapse the revisions in the given revset into summary nodes, like
+    /// "+ (3 commits)". Connected revisions that are shown one after another
+    /// become a single node, which keeps the edges to the rest of the graph.
+    #[arg(long, value_name = "REVSET", conflicts_with = "no_graph")]
+    collapse: Option<RevisionArg>,
+    /// Collapse the hidden revisions that are shown (e.g. the predecessors of
+    /// an amended revision, given by commit id) into summary nodes, like
+    /// "+ (2 obsolete commits)". Predecessors with the same parents that are
+    /// shown one after another become a single node.
+    #[arg(long, conflicts_with = "no_graph")]
+    collapse_obsolete: bool,
+    /// Prefix each revision with where it is relative to the given revisions
+    /// (e.g. a branch): "[on]" for the revisions themselves, "[behind]" for
+    /// their ancestors, and "[ahead]" for everything else
+    #[arg(long, value_name = "REVSET", conflicts_with = "no_graph")]
+    relative_to: Option<RevisionArg>,
+    /// Prefix each revision with the status of its signature: "G" for a good
+    /// one, "B" for a bad one, and "U" if it can't be verified because
+    /// `signing.verify-command` isn't set. Unsigned revisions get no marker.
+    #[arg(long, conflicts_with = "no_graph")]
+    show_signature_status: bool,
+    /// Draw the root commit's row in a dim color, since it's rarely
+    /// interesting. Defaults to the `ui.dim-root` setting.
+    #[arg(long, conflicts_with = "no_graph")]
+    dim_root: bool,
+    /// Color the working-copy commit's row like any other instead of in the
+    /// brighter working-copy colors. Defaults to the opposite of the
+    /// `ui.log-color-working-copy` setting.
+    #[arg(long, conflicts_with = "no_graph")]
+    no_color_working_copy: bool,
+    /// Show the author of each revision in a column of this width (24 if not
+    /// given) between the graph and the rest of the text. Authors that don't
+    /// fit are cut off with "…".
+    #[arg(
+        long,
+        value_name = "WIDTH",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "24",
+        conflicts_with = "no_graph"
+    )]
+    author_column: Option<usize>,
+    /// Color the graph node of each revision by its author, picking the color
+    /// from a hash of the author's email address
+    #[arg(long, conflicts_with = "no_graph")]
+    color_by_author: bool,
+    /// Highlight the revisions that modify the given path, without leaving
+    /// out the others like `jj log PATH` does. Can be repeated.
+    #[arg(long, value_name = "PATH", conflicts_with = "no_graph")]
+    highlight_path: Vec<String>,
+    /// Group revisions under a header per author, sorted by author and then
+    /// by author date (requires --no-graph)
+    #[arg(long, requires = "no_graph")]
+    group_by_author: bool,
+    /// Show how many files each revision changed, and how many lines it
+    /// inserted and deleted, after the rest of the revision's text
+    #[arg(long)]
+    annotate_change_count: bool,
+    /// After the rest of the text of each merge revision, list the short ids
+    /// of its parents, like "(parents: 0123456789ab, ba9876543210)"
+    #[arg(long)]
+    parents_summary: bool,
+    /// Prefix each revision that changes no files (like the ones in the
+    /// `empty()` revset) with "(empty)"
+    #[arg(long)]
+    show_empty_marker: bool,
+    /// How to print the revisions. `json` prints a JSON array with one object
+    /// per revision and never draws the graph.
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = LogFormat::Text,
+        conflicts_with_all = [
+            "template",
+            "node_template",
+            "legend",
+            "color_by_author",
+            "author_column",
+            "group_by_author",
+            "annotate_change_count",
+            "parents_summary",
+            "show_empty_marker",
+            "tool",
+        ]
+    )]
+    format: LogFormat,
     /// Render each revision using the given template (the syntax is not yet
-    /// documented and is likely to change)
+    /// documented and is likely to change). If given several times, the
+    /// outputs of the templates are concatenated.
     #[arg(long, short = 'T')]
-    template: Option<String>,
+    template: Vec<String>,
     /// Show patch
     #[arg(long, short = 'p')]
     patch: bool,
+    /// Show the patch of each revision by running this tool on directories
+    /// with the two sides of the diff (see `merge-tools.<name>.diff-args`)
+    #[arg(long, value_name = "NAME", requires = "patch")]
+    tool: Option<String>,
     #[command(flatten)]
     diff_format: DiffFormatArgs,
 }
 
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EdgeStyle {
+    /// Draw all edges to parents the same way
+    Merge,
+    /// Draw edges to parents that a commit was rebased onto as dashed lines
+    Rebase,
+}
+
+impl Default for EdgeStyle {
+    fn default() -> Self {
+        EdgeStyle::Merge
+    }
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LogFormat {
+    /// Render each revision using the template, with or without the graph
+    Text,
+    /// Print a JSON array with one object per revision
+    Json,
+}
+
+impl Default for LogFormat {
+    fn default() -> Self {
+        LogFormat::Text
+    }
+}
+
 /// Show how a change has evolved
 ///
 /// Show how a change has evolved as it's been updated, rebased, etc.
@@ -840,6 +1273,145 @@ struct SparseArgs {
     list: bool,
 }
 
+/// Manage config options
+///
+/// For information about the available options, see
+/// https://github.com/martinvonz/jj/blob/main/docs/config.md.
+#[derive(Subcommand, Clone, Debug)]
+enum ConfigSubcommand {
+    List(ConfigListArgs),
+    Get(ConfigGetArgs),
+    Set(ConfigSetArgs),
+    Reset(ConfigResetArgs),
+    Validate(ConfigValidateArgs),
+    Export(ConfigExportArgs),
+    Where(ConfigWhereArgs),
+}
+
+/// List config options and their values
+#[derive(clap::Args, Clone, Debug)]
+struct ConfigListArgs {
+    /// Only list options whose name starts with this (e.g. "ui" or "ui.color")
+    name: Option<String>,
+    /// Only list options whose full name matches this glob pattern (e.g.
+    /// "merge-tools.*"). `*` matches any sequence of characters, including
+    /// `.`
+    #[arg(long, value_name = "GLOB")]
+    filter: Option<String>,
+    /// Omit options that are only set by jj's built-in defaults. An option
+    /// that is set to the same value as its default is still listed.
+    #[arg(long)]
+    only_explicit: bool,
+    /// After each option, print where its value comes from, like `jj config
+    /// where` does (e.g. a config file, or "built-in default")
+    #[arg(long)]
+    show_origin: bool,
+}
+
+/// Print the value of a config option
+#[derive(clap::Args, Clone, Debug)]
+struct ConfigGetArgs {
+    /// The name of the option (e.g. "ui.color")
+    name: String,
+    /// Fail if the option only has its built-in default value
+    #[arg(long)]
+    only_explicit: bool,
+    /// Print this value instead of failing if the option is not set (or, with
+    /// --only-explicit, only has its built-in default value)
+    #[arg(long, value_name = "VALUE")]
+    default: Option<String>,
+    /// If the option is an array, print each of its elements on a separate
+    /// line instead of printing the array in TOML syntax
+    #[arg(long)]
+    all: bool,
+}
+
+/// Set a config option in a config file
+///
+/// The value is parsed as TOML if possible (e.g. `true` or `["a", "b"]`), and
+/// is used as a string otherwise. Comments and formatting in the rest of the
+/// config file are preserved.
+#[derive(clap::Args, Clone, Debug)]
+struct ConfigSetArgs {
+    /// The name of the option (e.g. "ui.color")
+    name: String,
+    /// The new value
+    value: String,
+    /// Which config file to write to
+    #[arg(long, value_enum, default_value_t = ConfigScope::User)]
+    scope: ConfigScope,
+    /// Only print the change that would be made, without writing the file
+    #[arg(long)]
+    dry_run: bool,
+}
+
+/// Remove a config option from a config file
+///
+/// This undoes `jj config set`, so the option goes back to its built-in
+/// default value (or to the value from the user's config, when resetting it
+/// in the repo's config). The value now in effect is printed along with where
+/// it comes from. Comments and formatting in the rest of the config file are
+/// preserved.
+#[derive(clap::Args, Clone, Debug)]
+#[command(visible_alias = "unset")]
+struct ConfigResetArgs {
+    /// The name of the option (e.g. "ui.color")
+    name: String,
+    /// Which config file to remove the option from
+    #[arg(long, value_enum, default_value_t = ConfigScope::User)]
+    scope: ConfigScope,
+    /// Only print the change that would be made, without writing the file
+    #[arg(long)]
+    dry_run: bool,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum ConfigScope {
+    /// The user's config file, which applies to all repos
+    User,
+    /// The config file of the current repo (`.jj/repo/config.toml`), which
+    /// overrides the user's config
+    Repo,
+}
+
+/// Check a config file for errors without loading it
+///
+/// The file is parsed the same way as the user's config files are, but it
+/// doesn't affect the settings of this or any other command. Keys that jj
+/// doesn't know about are reported as warnings.
+#[derive(clap::Args, Clone, Debug)]
+struct ConfigValidateArgs {
+    /// The config file to check
+    #[arg(value_hint = clap::ValueHint::FilePath)]
+    path: String,
+}
+
+/// Print the effective config as a TOML document
+///
+/// The output includes the settings from all config files, the environment,
+/// and the command line, merged the way jj uses them, as well as jj's
+/// built-in defaults. This is useful for sharing your setup or for reporting
+/// a bug.
+#[derive(clap::Args, Clone, Debug)]
+struct ConfigExportArgs {
+    /// Replace the value of this option with "<redacted>" (e.g.
+    /// "user.email"). Can be repeated.
+    #[arg(long, value_name = "NAME")]
+    redact: Vec<String>,
+}
+
+/// Print where the value of a config option comes from
+///
+/// Prints the path of the config file that sets the option, or the
+/// environment variable, profile, or `--config-toml` argument (or `--color`
+/// for `ui.color`), whichever takes precedence. Built-in defaults are reported
+/// as such.
+#[derive(clap::Args, Clone, Debug)]
+struct ConfigWhereArgs {
+    /// The name of the option (e.g. "ui.color")
+    name: String,
+}
+
 /// Commands for working with the underlying Git repo
 ///
 /// For a comparison with Git, including a table of commands, see
@@ -1253,22 +1825,200 @@ fn cmd_files(ui: &mut Ui, command: &CommandHelper, args: &FilesArgs) -> Result<(
 
 fn cmd_print(ui: &mut Ui, command: &CommandHelper, args: &PrintArgs) -> Result<(), CommandError> {
     let workspace_command = command.workspace_helper(ui)?;
-    let commit = workspace_command.resolve_single_rev(&args.revision)?;
-    let path = workspace_command.parse_file_path(&args.path)?;
     let repo = workspace_command.repo();
-    match commit.tree().path_value(&path) {
+    if let Some(oid_str) = &args.git_blob {
+        let git_repo = get_git_repo(repo.store())?;
+        let oid = if oid_str.len() == 40 {
+            git2::Oid::from_str(oid_str).ok()
+        } else {
+            None
+        }
+        .ok_or_else(|| {
+            user_error(format!(
+                "Invalid git object id \"{oid_str}\" (expected 40 hex digits)"
+            ))
+        })?;
+        let object = git_repo
+            .find_object(oid, None)
+            .map_err(|_| user_error(format!("No git object with id {oid}")))?;
+        let blob = object.as_blob().ok_or_else(|| {
+            let kind = object.kind().map_or("unknown", |kind| kind.str());
+            user_error(format!("Git object {oid} is a {kind}, not a blob"))
+        })?;
+        return write_print_output(ui, blob.content(), &format!("Git blob {oid}"), args);
+    }
+    if args.from.is_some() || args.to.is_some() {
+        let from = workspace_command.resolve_single_rev(args.from.as_deref().unwrap_or("@"))?;
+        let to = workspace_command.resolve_single_rev(args.to.as_deref().unwrap_or("@"))?;
+        let path = parse_print_path(&workspace_command, args)?;
+        let (from_tree, to_tree) = (from.tree(), to.tree());
+        if from_tree.path_value(&path).is_none() && to_tree.path_value(&path).is_none() {
+            return Err(user_error("No such path"));
+        }
+        let matcher = FilesMatcher::new(hashset! {path});
+        ui.request_pager();
+        show_git_diff(
+            ui.stdout_formatter().as_mut(),
+            &workspace_command,
+            from_tree.diff(&to_tree, &matcher),
+        )?;
+        return Ok(());
+    }
+    let path = parse_print_path(&workspace_command, args)?;
+    let base = match &args.base_rev {
+        Some(base_rev) => {
+            let base_commit = workspace_command.resolve_single_rev(base_rev)?;
+            match base_commit.tree().path_value(&path) {
+                Some(value @ TreeValue::File { .. }) => Some(value),
+                _ => return Err(user_error("Path is not a file in the base revision")),
+            }
+        }
+        None => None,
+    };
+    if let Some(change_id_prefix) = &args.at_change {
+        let commit = resolve_single_change(&workspace_command, change_id_prefix)?;
+        return print_file_at_commit(ui, repo, &commit, &path, base.as_ref(), args);
+    }
+    let commits = workspace_command.resolve_revset(&args.revision)?;
+    if commits.len() <= 1 {
+        let commit = workspace_command.resolve_single_rev(&args.revision)?;
+        return print_file_at_commit(ui, repo, &commit, &path, base.as_ref(), args);
+    }
+    ui.request_pager();
+    for (i, commit) in commits.iter().enumerate() {
+        {
+            let mut formatter = ui.stdout_formatter();
+            if i > 0 {
+                formatter.write_str("\n")?;
+            }
+            formatter.write_str("==> ")?;
+            write_commit_summary(
+                formatter.as_mut(),
+                repo.as_repo_ref(),
+                &workspace_command.workspace_id(),
+                commit,
+                ui.settings(),
+            )?;
+            formatter.write_str(" <==\n")?;
+        }
+        // Keep going, so the other revisions still show how the file evolved
+        if commit.tree().path_value(&path).is_none() {
+            ui.stdout_formatter().write_str("(absent)\n")?;
+            continue;
+        }
+        print_file_at_commit(ui, repo, commit, &path, base.as_ref(), args)?;
+    }
+    Ok(())
+}
+
+fn parse_print_path(
+    workspace_command: &WorkspaceCommandHelper,
+    args: &PrintArgs,
+) -> Result<RepoPath, CommandError> {
+    let input = args.path.as_ref().unwrap();
+    let path = if args.repo_relative {
+        workspace_command.parse_repo_relative_path(input)?
+    } else {
+        workspace_command.parse_file_path(input)?
+    };
+    Ok(path)
+}
+
+fn print_file_at_commit(
+    ui: &mut Ui,
+    repo: &Arc<ReadonlyRepo>,
+    commit: &Commit,
+    path: &RepoPath,
+    base: Option<&TreeValue>,
+    args: &PrintArgs,
+) -> Result<(), CommandError> {
+    if !args.versions.is_empty() {
+        return print_conflict_versions(ui, repo, commit, path, base, &args.versions);
+    }
+    if args.mode {
+        let mode = match commit.tree().path_value(path) {
+            None => return Err(user_error("No such path")),
+            Some(TreeValue::File {
+                executable: false, ..
+            }) => "100644 regular",
+            Some(TreeValue::File {
+                executable: true, ..
+            }) => "100755 executable",
+            Some(TreeValue::Symlink(_)) => "120000 symlink",
+            Some(TreeValue::Tree(_)) => "040000 tree",
+            Some(TreeValue::GitSubmodule(_)) => "160000 submodule",
+            Some(TreeValue::Conflict(_)) => "conflict",
+        };
+        ui.stdout_formatter().write_str(&format!("{mode}\n"))?;
+        return Ok(());
+    }
+    if args.git_object_info {
+        return print_git_object_info(ui, repo, commit, path);
+    }
+    if args.show_mode {
+        let mode = match commit.tree().path_value(path) {
+            Some(TreeValue::File {
+                executable: false, ..
+            }) => Some("100644"),
+            Some(TreeValue::File {
+                executable: true, ..
+            }) => Some("100755"),
+            Some(TreeValue::Symlink(id)) => {
+                let target = repo.store().read_symlink(path, &id)?;
+                ui.stdout_formatter()
+                    .write_str(&format!("symlink -> {target}\n"))?;
+                return Ok(());
+            }
+            Some(TreeValue::Conflict(_)) => Some("conflict"),
+            // Reported below
+            _ => None,
+        };
+        if let Some(mode) = mode {
+            ui.stdout_formatter()
+                .write_str(&format!("mode: {mode}\n"))?;
+        }
+    }
+    match commit.tree().path_value(path) {
         None => {
             return Err(user_error("No such path"));
         }
+        Some(TreeValue::File { .. }) if base.is_some() => {
+            return Err(user_error("Path is not conflicted"));
+        }
         Some(TreeValue::File { id, .. }) => {
-            let mut contents = repo.store().read_file(&path, &id)?;
-            std::io::copy(&mut contents, &mut ui.stdout_formatter().as_mut())?;
+            let mut contents = vec![];
+            repo.store()
+                .read_file(path, &id)?
+                .read_to_end(&mut contents)?;
+            if args.expand_keywords {
+                contents = expand_keywords(&contents, commit);
+            }
+            write_print_output(ui, &contents, args.path.as_ref().unwrap(), args)?;
         }
         Some(TreeValue::Conflict(id)) => {
-            let conflict = repo.store().read_conflict(&path, &id)?;
-            let mut contents = vec![];
-            conflicts::materialize_conflict(repo.store(), &path, &conflict, &mut contents).unwrap();
-            ui.stdout_formatter().write_all(&contents)?;
+            let conflict = repo.store().read_conflict(path, &id)?;
+            let conflict = conflict_with_base(conflict, base)?;
+            let contents = if let Some(side) = args.resolve {
+                let hunk = two_sided_conflict_hunk(repo, path, &conflict)?;
+                conflicts::resolve_merge_result(&hunk, |hunk| side.pick(hunk))
+            } else {
+                let mut contents = vec![];
+                conflicts::materialize_conflict_with_style(
+                    repo.store(),
+                    path,
+                    &conflict,
+                    args.conflict_style.marker_style(),
+                    &mut contents,
+                )
+                .unwrap();
+                contents
+            };
+            let contents = if args.expand_keywords {
+                expand_keywords(&contents, commit)
+            } else {
+                contents
+            };
+            write_print_output(ui, &contents, args.path.as_ref().unwrap(), args)?;
         }
         _ => {
             return Err(user_error("Path exists but is not a file"));
@@ -1277,6 +2027,250 @@ fn cmd_print(ui: &mut Ui, command: &CommandHelper, args: &PrintArgs) -> Result<(
     Ok(())
 }
 
+/// Prints the type, id, and size of the git object backing `path`, like
+/// `git ls-tree -l` does. Submodules aren't in the object database, so their
+/// size is "-".
+fn print_git_object_info(
+    ui: &mut Ui,
+    repo: &Arc<ReadonlyRepo>,
+    commit: &Commit,
+    path: &RepoPath,
+) -> Result<(), CommandError> {
+    let git_repo = get_git_repo(repo.store())?;
+    let (id_bytes, is_conflict) = match commit.tree().path_value(path) {
+        None => return Err(user_error("No such path")),
+        Some(TreeValue::File { id, .. }) => (id.to_bytes(), false),
+        Some(TreeValue::Symlink(id)) => (id.to_bytes(), false),
+        Some(TreeValue::Tree(id)) => (id.to_bytes(), false),
+        Some(TreeValue::Conflict(id)) => (id.to_bytes(), true),
+        Some(TreeValue::GitSubmodule(id)) => {
+            ui.stdout_formatter()
+                .write_str(&format!("commit {} -\n", id.hex()))?;
+            return Ok(());
+        }
+    };
+    let oid = git2::Oid::from_bytes(&id_bytes).unwrap();
+    let odb = git_repo.odb()?;
+    let (size, kind) = odb.read_header(oid)?;
+    let mut line = format!("{} {oid} {size}", kind.str());
+    if is_conflict {
+        line.push_str(" (conflict)");
+    }
+    line.push('\n');
+    ui.stdout_formatter().write_str(&line)?;
+    Ok(())
+}
+
+/// Prints a diff between the resolutions of the conflict at `path` that pick
+/// each of the two `sides`.
+fn print_conflict_versions(
+    ui: &mut Ui,
+    repo: &Arc<ReadonlyRepo>,
+    commit: &Commit,
+    path: &RepoPath,
+    base: Option<&TreeValue>,
+    sides: &[ConflictSide],
+) -> Result<(), CommandError> {
+    let (left_side, right_side) = match sides {
+        [left_side, right_side] => (*left_side, *right_side),
+        _ => {
+            return Err(user_error(
+                "--versions takes exactly two sides, like \"ours,theirs\"",
+            ))
+        }
+    };
+    let conflict = match commit.tree().path_value(path) {
+        None => return Err(user_error("No such path")),
+        Some(TreeValue::Conflict(id)) => repo.store().read_conflict(path, &id)?,
+        Some(_) => return Err(user_error("Path is not conflicted")),
+    };
+    let conflict = conflict_with_base(conflict, base)?;
+    let hunk = two_sided_conflict_hunk(repo, path, &conflict)?;
+    let left_content = conflicts::resolve_merge_result(&hunk, |hunk| left_side.pick(hunk));
+    let right_content = conflicts::resolve_merge_result(&hunk, |hunk| right_side.pick(hunk));
+    ui.request_pager();
+    let mut formatter = ui.stdout_formatter();
+    formatter.add_label("diff")?;
+    formatter.with_label("file_header", |formatter| {
+        writeln!(formatter, "--- {}", left_side.name())?;
+        writeln!(formatter, "+++ {}", right_side.name())
+    })?;
+    show_unified_diff_hunks(formatter.as_mut(), &left_content, &right_content)?;
+    formatter.remove_label()?;
+    Ok(())
+}
+
+/// Replaces the base of a conflict between two sides with `base`, if given.
+fn conflict_with_base(
+    mut conflict: Conflict,
+    base: Option<&TreeValue>,
+) -> Result<Conflict, CommandError> {
+    if let Some(base) = base {
+        if conflict.adds.len() != 2 {
+            return Err(user_error(format!(
+                "--base-rev only works for conflicts between two sides, but this one has {} sides",
+                conflict.adds.len()
+            )));
+        }
+        conflict.removes = vec![ConflictPart {
+            value: base.clone(),
+        }];
+    }
+    Ok(conflict)
+}
+
+/// Returns the conflict at `path` as a single hunk, if it's a conflict
+/// between two sides of regular files, so a side can be picked.
+fn two_sided_conflict_hunk(
+    repo: &Arc<ReadonlyRepo>,
+    path: &RepoPath,
+    conflict: &Conflict,
+) -> Result<ConflictHunk, CommandError> {
+    let hunk = conflicts::extract_file_conflict_as_single_hunk(repo.store(), path, conflict)
+        .ok_or_else(|| user_error("Only conflicts between regular files can be resolved"))?;
+    if hunk.removes.len() != 1 || hunk.adds.len() != 2 {
+        return Err(user_error(format!(
+            "Only conflicts between two sides can be resolved, but this one has {} sides",
+            hunk.adds.len()
+        )));
+    }
+    Ok(hunk)
+}
+
+/// Whether `contents` would likely garble a terminal if printed.
+fn is_binary(contents: &[u8]) -> bool {
+    contents.contains(&0) || std::str::from_utf8(contents).is_err()
+}
+
+/// Prints `contents` as requested by `args`. `name` describes where the
+/// contents came from, for the error about binary contents.
+fn write_print_output(
+    ui: &mut Ui,
+    contents: &[u8],
+    name: &str,
+    args: &PrintArgs,
+) -> Result<(), CommandError> {
+    if !args.binary && !args.count_lines && is_binary(contents) {
+        return Err(user_error(format!(
+            "{name} appears to be binary; pass --binary to print raw bytes"
+        )));
+    }
+    let contents = &args.line_ending.convert(contents);
+    let max_bytes = if args.no_limit || args.count_lines {
+        None
+    } else {
+        // The configured limit is only meant to keep the terminal from being
+        // flooded, so piped output isn't cut off
+        args.max_bytes.or_else(|| {
+            if ui.stdout_is_terminal() {
+                ui.settings().print_max_bytes()
+            } else {
+                None
+            }
+        })
+    };
+    let (contents, num_truncated) = match max_bytes {
+        Some(max_bytes) if contents.len() > max_bytes => {
+            (&contents[..max_bytes], contents.len() - max_bytes)
+        }
+        _ => (contents, 0),
+    };
+    if args.number {
+        let mut formatter = ui.stdout_formatter();
+        for (i, line) in contents.split_inclusive(|b| *b == b'\n').enumerate() {
+            formatter.write_str(&format!("{:>6}\t", i + 1))?;
+            formatter.write_all(line)?;
+        }
+    } else if !args.count_lines {
+        ui.stdout_formatter().write_all(contents)?;
+    }
+    if !args.count_lines {
+        if num_truncated > 0 {
+            if !contents.is_empty() && !contents.ends_with(b"\n") {
+                ui.stdout_formatter().write_str("\n")?;
+            }
+            ui.write_warn(format!("... (truncated, {num_truncated} more bytes)\n"))?;
+        }
+        return Ok(());
+    }
+    let num_lines = contents.iter().filter(|b| **b == b'\n').count();
+    ui.stdout_formatter().write_str(&format!("{num_lines}\n"))?;
+    if !contents.is_empty() && !contents.ends_with(b"\n") {
+        ui.write_hint("Hint: The last line has no trailing newline, so it wasn't counted\n")?;
+    }
+    Ok(())
+}
+
+/// Resolves `change_id_prefix` to the visible commit of the change it's a
+/// prefix of. Fails if the prefix matches several changes, or if the change
+/// has several visible commits (i.e. is divergent).
+fn resolve_single_change(
+    workspace_command: &WorkspaceCommandHelper,
+    change_id_prefix: &str,
+) -> Result<Commit, CommandError> {
+    let repo = workspace_command.repo();
+    let hex_prefix = HexPrefix::new(change_id_prefix.to_owned())
+        .ok_or_else(|| user_error(format!("Invalid change id \"{change_id_prefix}\"")))?;
+    // TODO: Use an index from change id to commit ids once there is one.
+    let all_commits = RevsetExpression::all().evaluate(repo.as_repo_ref(), None)?;
+    let mut change_id = None;
+    let mut commit_ids = vec![];
+    for index_entry in all_commits.iter() {
+        let entry_change_id = index_entry.change_id();
+        if !entry_change_id.hex().starts_with(hex_prefix.hex()) {
+            continue;
+        }
+        if let Some(previous_change_id) = change_id.replace(entry_change_id.clone()) {
+            if previous_change_id != entry_change_id {
+                return Err(user_error(format!(
+                    "Change id prefix \"{change_id_prefix}\" is ambiguous"
+                )));
+            }
+        }
+        commit_ids.push(index_entry.commit_id());
+    }
+    let change_id = change_id.ok_or_else(|| {
+        user_error(format!(
+            "No visible commit has change id \"{change_id_prefix}\""
+        ))
+    })?;
+    if let [commit_id] = commit_ids.as_slice() {
+        return Ok(repo.store().get_commit(commit_id)?);
+    }
+    let mut hint = "Pass one of them with -r instead:".to_string();
+    for commit_id in &commit_ids {
+        let commit = repo.store().get_commit(commit_id)?;
+        hint.push_str(&format!("\n  {}", short_commit_description(&commit)));
+    }
+    Err(user_error_with_hint(
+        format!(
+            "Change {} is divergent: it has {} visible commits",
+            &change_id.hex()[..12],
+            commit_ids.len()
+        ),
+        hint,
+    ))
+}
+
+/// Expands the `$Id$`, `$Author$`, and `$Date$` keywords in `contents` with
+/// the metadata of `commit`. Keywords that are already expanded (e.g.
+/// `$Id: 1234 $`) are expanded again.
+fn expand_keywords(contents: &[u8], commit: &Commit) -> Vec<u8> {
+    let keyword_regex = regex::bytes::Regex::new(r"\$(Id|Author|Date)(?::[^$\n]*)?\$").unwrap();
+    let author = commit.author();
+    keyword_regex
+        .replace_all(contents, |captures: &regex::bytes::Captures| {
+            let keyword = String::from_utf8_lossy(&captures[1]);
+            let value = match keyword.as_ref() {
+                "Id" => commit.id().hex(),
+                "Author" => format!("{} <{}>", author.name, author.email),
+                _ => format_timestamp(&author.timestamp),
+            };
+            format!("${keyword}: {value} $")
+        })
+        .into_owned()
+}
+
 fn show_color_words_diff_hunks(
     left: &[u8],
     right: &[u8],
@@ -1407,6 +2401,16 @@ fn cmd_diff(ui: &mut Ui, command: &CommandHelper, args: &DiffArgs) -> Result<(),
 fn cmd_show(ui: &mut Ui, command: &CommandHelper, args: &ShowArgs) -> Result<(), CommandError> {
     let workspace_command = command.workspace_helper(ui)?;
     let commit = workspace_command.resolve_single_rev(&args.revision)?;
+    if args.trailers {
+        ui.request_pager();
+        let mut formatter = ui.stdout_formatter();
+        for (key, value) in parse_trailers(commit.description()) {
+            formatter.with_label("trailer", |formatter| {
+                formatter.write_str(&format!("{key}: {value}\n"))
+            })?;
+        }
+        return Ok(());
+    }
     let parents = commit.parents();
     let from_tree = merge_commit_trees(workspace_command.repo().as_repo_ref(), &parents);
     let to_tree = commit.tree();
@@ -1447,9 +2451,39 @@ fn cmd_show(ui: &mut Ui, command: &CommandHelper, args: &ShowArgs) -> Result<(),
     Ok(())
 }
 
+/// Parses the trailers of a commit description, like Git does: the lines of
+/// the last paragraph that look like "Key: value", as long as there's a
+/// paragraph before it. Other lines in that paragraph are skipped.
+fn parse_trailers(description: &str) -> Vec<(&str, &str)> {
+    let mut paragraphs = description
+        .trim_end()
+        .split("\n\n")
+        .filter(|paragraph| !paragraph.trim().is_empty())
+        .collect_vec();
+    if paragraphs.len() < 2 {
+        return vec![];
+    }
+    let last_paragraph: &str = paragraphs.pop().unwrap();
+    last_paragraph
+        .lines()
+        .filter_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            let value = value.trim();
+            let is_valid_key =
+                !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '-');
+            if is_valid_key && !value.is_empty() {
+                Some((key, value))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
-enum DiffFormat {
+pub enum DiffFormat {
     Summary,
+    Stat,
     Git,
     ColorWords,
 }
@@ -1457,6 +2491,8 @@ enum DiffFormat {
 fn diff_format_for(ui: &Ui, args: &DiffFormatArgs) -> DiffFormat {
     if args.summary {
         DiffFormat::Summary
+    } else if args.stat {
+        DiffFormat::Stat
     } else if args.git {
         DiffFormat::Git
     } else if args.color_words {
@@ -1464,6 +2500,7 @@ fn diff_format_for(ui: &Ui, args: &DiffFormatArgs) -> DiffFormat {
     } else {
         match ui.settings().config().get_string("diff.format") {
             Ok(value) if &value == "summary" => DiffFormat::Summary,
+            Ok(value) if &value == "stat" => DiffFormat::Stat,
             Ok(value) if &value == "git" => DiffFormat::Git,
             Ok(value) if &value == "color-words" => DiffFormat::ColorWords,
             _ => DiffFormat::ColorWords,
@@ -1481,6 +2518,9 @@ fn show_diff(
         DiffFormat::Summary => {
             show_diff_summary(formatter, workspace_command, tree_diff)?;
         }
+        DiffFormat::Stat => {
+            show_diff_stat(formatter, workspace_command, tree_diff)?;
+        }
         DiffFormat::Git => {
             show_git_diff(formatter, workspace_command, tree_diff)?;
         }
@@ -1933,13 +2973,105 @@ fn show_diff_summary(
     })
 }
 
-fn cmd_status(
-    ui: &mut Ui,
-    command: &CommandHelper,
-    _args: &StatusArgs,
+fn show_diff_stat(
+    formatter: &mut dyn Formatter,
+    workspace_command: &WorkspaceCommandHelper,
+    tree_diff: TreeDiffIterator,
 ) -> Result<(), CommandError> {
-    let workspace_command = command.workspace_helper(ui)?;
-    let repo = workspace_command.repo();
+    fn count_lines(text: &[u8]) -> usize {
+        text.split_inclusive(|b| *b == b'\n').count()
+    }
+    fn plural(count: usize) -> &'static str {
+        if count == 1 {
+            ""
+        } else {
+            "s"
+        }
+    }
+
+    let repo = workspace_command.repo();
+    // (path, insertions and deletions, or None for a binary file)
+    let mut files = vec![];
+    for (path, diff) in tree_diff {
+        let (left, right) = match &diff {
+            tree::Diff::Modified(left, right) => (
+                diff_content(repo, &path, left)?,
+                diff_content(repo, &path, right)?,
+            ),
+            tree::Diff::Added(right) => (vec![], diff_content(repo, &path, right)?),
+            tree::Diff::Removed(left) => (diff_content(repo, &path, left)?, vec![]),
+        };
+        let counts = if is_binary(&left) || is_binary(&right) {
+            None
+        } else {
+            let mut insertions = 0;
+            let mut deletions = 0;
+            let line_diff = Diff::for_tokenizer(&[&left, &right], &diff::find_line_ranges);
+            for hunk in line_diff.hunks() {
+                if let DiffHunk::Different(contents) = hunk {
+                    deletions += count_lines(contents[0]);
+                    insertions += count_lines(contents[1]);
+                }
+            }
+            Some((insertions, deletions))
+        };
+        files.push((workspace_command.format_file_path(&path), counts));
+    }
+    if files.is_empty() {
+        return Ok(());
+    }
+
+    let path_width = files
+        .iter()
+        .map(|(path, _)| path.chars().count())
+        .max()
+        .unwrap_or(0);
+    let mut total_insertions = 0;
+    let mut total_deletions = 0;
+    formatter.with_label("diff", |formatter| {
+        for (path, counts) in &files {
+            write!(formatter, "{path:<path_width$} | ")?;
+            match counts {
+                Some((insertions, deletions)) => {
+                    total_insertions += insertions;
+                    total_deletions += deletions;
+                    formatter
+                        .with_label("added", |formatter| write!(formatter, "+{insertions}"))?;
+                    formatter.write_str("/")?;
+                    formatter
+                        .with_label("removed", |formatter| write!(formatter, "-{deletions}"))?;
+                    formatter.write_str("\n")?;
+                }
+                None => {
+                    formatter.write_str("Bin\n")?;
+                }
+            }
+        }
+        let mut summary = format!("{} file{} changed", files.len(), plural(files.len()));
+        if total_insertions > 0 {
+            summary.push_str(&format!(
+                ", {total_insertions} insertion{}(+)",
+                plural(total_insertions)
+            ));
+        }
+        if total_deletions > 0 {
+            summary.push_str(&format!(
+                ", {total_deletions} deletion{}(-)",
+                plural(total_deletions)
+            ));
+        }
+        writeln!(formatter, "{summary}")
+    })?;
+    Ok(())
+}
+
+fn cmd_status(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    _args: &StatusArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper(ui)?;
+    let repo = workspace_command.repo();
     let maybe_checkout_id = repo
         .view()
         .get_wc_commit_id(&workspace_command.workspace_id());
@@ -2080,12 +3212,56 @@ fn log_template(settings: &UserSettings) -> String {
 fn cmd_log(ui: &mut Ui, command: &CommandHelper, args: &LogArgs) -> Result<(), CommandError> {
     let workspace_command = command.workspace_helper(ui)?;
 
-    let default_revset = ui.settings().default_revset();
-    let revset_expression =
-        workspace_command.parse_revset(args.revisions.as_deref().unwrap_or(&default_revset))?;
+    // Validate the configured default up front so a broken setting is reported
+    // even when it isn't the revset being shown.
+    let default_revset_expression = workspace_command.parse_default_revset()?;
+    let (revset_expression, divergence) = if let Some(revision) = &args.since_divergence {
+        let divergence = find_divergence(&workspace_command, revision)?;
+        (divergence.revset_expression(), Some(divergence))
+    } else if let Some(base) = &args.since {
+        let base_expression = workspace_command.parse_revset(base)?;
+        (unmerged_revset_expression(&base_expression), None)
+    } else {
+        let expression = match &args.revisions {
+            Some(revisions) => workspace_command.parse_revset(revisions)?,
+            None => default_revset_expression,
+        };
+        (expression, None)
+    };
+    let revset_expression = match &args.oldest_ancestor {
+        Some(boundary) => {
+            revset_expression.minus(&workspace_command.parse_revset(boundary)?.ancestors())
+        }
+        None => revset_expression,
+    };
+    let now = chrono::Local::now();
+    let parse_date_arg = |text: &str| {
+        revset::parse_date(text, &now).ok_or_else(|| {
+            user_error_with_hint(
+                format!("Invalid date \"{text}\""),
+                "Pass e.g. \"2022-01-31\", \"2022-01-31T12:00:00+01:00\", \"yesterday\", or \"3 \
+                 days ago\"",
+            )
+        })
+    };
+    let mut revset_expression = revset_expression;
+    if let Some(after) = &args.after {
+        revset_expression = revset_expression.intersection(&RevsetExpression::filter(
+            RevsetFilterPredicate::AuthoredAfter(parse_date_arg(after)?),
+        ));
+    }
+    if let Some(before) = &args.before {
+        revset_expression = revset_expression.intersection(&RevsetExpression::filter(
+            RevsetFilterPredicate::AuthoredBefore(parse_date_arg(before)?),
+        ));
+    }
+    if let Some(pattern) = &args.author {
+        revset_expression = revset_expression.intersection(&RevsetExpression::filter(
+            RevsetFilterPredicate::AuthorRegex(RevsetRegex::new(author_pattern_regex(pattern)?)),
+        ));
+    }
     let repo = workspace_command.repo();
     let workspace_id = workspace_command.workspace_id();
-    let checkout_id = repo.view().get_wc_commit_id(&workspace_id);
     let matcher = workspace_command.matcher_from_values(&args.paths)?;
     let revset = workspace_command.evaluate_revset(&revset_expression)?;
     let revset = if !args.paths.is_empty() {
@@ -2094,139 +3270,1181 @@ fn cmd_log(ui: &mut Ui, command: &CommandHelper, args: &LogArgs) -> Result<(), C
         revset
     };
 
-    let store = repo.store();
-    let diff_format = (args.patch || args.diff_format.git || args.diff_format.summary)
-        .then(|| diff_format_for(ui, &args.diff_format));
+    let diff_format =
+        (args.patch || args.diff_format.git || args.diff_format.summary || args.diff_format.stat)
+            .then(|| diff_format_for(ui, &args.diff_format));
 
-    let template_string = match &args.template {
-        Some(value) => value.to_string(),
-        None => log_template(ui.settings()),
+    let mut template_strings = if args.template.is_empty() {
+        vec![log_template(ui.settings())]
+    } else {
+        args.template.clone()
     };
-    let template = crate::template_parser::parse_commit_template(
-        repo.as_repo_ref(),
-        &workspace_id,
-        &template_string,
-    );
+    if args.show_empty_marker {
+        template_strings.insert(0, r#"if(empty, label("empty", "(empty) "))"#.to_string());
+    }
+    if args.annotate_change_count {
+        template_strings.push(r#"diff "\n""#.to_string());
+    }
+    if args.parents_summary {
+        template_strings.push(r#"if(merge, "(parents: " parents.short() ")\n")"#.to_string());
+    }
+    let template: Box<dyn Template<Commit> + '_> = Box::new(ListTemplate(
+        template_strings
+            .iter()
+            .map(|template_string| {
+                crate::template_parser::parse_commit_template(
+                    repo.as_repo_ref(),
+                    &workspace_id,
+                    template_string,
+                )
+            })
+            .collect(),
+    ));
 
-    {
-        ui.request_pager();
-        let mut formatter = ui.stdout_formatter();
-        let mut formatter = formatter.as_mut();
-        formatter.add_label("log")?;
+    let mut marks = vec![];
+    for mark in &args.mark {
+        let (revision_str, symbol) = mark.rsplit_once('=').ok_or_else(|| {
+            user_error(format!(
+                "Invalid mark \"{mark}\" (expected a revset and a symbol, like \"@=*\")"
+            ))
+        })?;
+        if symbol.chars().count() != 1 {
+            return Err(user_error(format!(
+                "Invalid mark \"{mark}\" (the symbol must be a single character)"
+            )));
+        }
+        let commit_ids: HashSet<CommitId> = workspace_command
+            .evaluate_revset(&workspace_command.parse_revset(revision_str)?)?
+            .iter()
+            .commit_ids()
+            .collect();
+        marks.push((commit_ids, symbol.to_string()));
+    }
+    let highlighted = if args.highlight_path.is_empty() {
+        HashSet::new()
+    } else {
+        let highlight_matcher = workspace_command.matcher_from_values(&args.highlight_path)?;
+        revset::filter_by_diff(
+            repo.as_repo_ref(),
+            highlight_matcher.as_ref(),
+            workspace_command.evaluate_revset(&revset_expression)?,
+        )
+        .iter()
+        .commit_ids()
+        .collect()
+    };
+    let mut collapsed: HashSet<CommitId> = match &args.collapse {
+        Some(revision_str) => workspace_command
+            .evaluate_revset(&workspace_command.parse_revset(revision_str)?)?
+            .iter()
+            .commit_ids()
+            .collect(),
+        None => HashSet::new(),
+    };
+    let obsolete: HashSet<CommitId> = if args.collapse_obsolete {
+        let expression = revset_expression.minus(&RevsetExpression::visible_heads().ancestors());
+        workspace_command
+            .evaluate_revset(&expression)?
+            .iter()
+            .commit_ids()
+            .collect()
+    } else {
+        HashSet::new()
+    };
+    collapsed.extend(obsolete.iter().cloned());
+    let relative_to = match &args.relative_to {
+        Some(revision_str) => {
+            let expression = workspace_command.parse_revset(revision_str)?;
+            let evaluate = |expression: &Rc<RevsetExpression>| -> Result<_, CommandError> {
+                Ok(workspace_command
+                    .evaluate_revset(expression)?
+                    .iter()
+                    .commit_ids()
+                    .collect())
+            };
+            Some(RelativeTarget {
+                commits: evaluate(&expression)?,
+                ancestors: evaluate(&expression.ancestors())?,
+            })
+        }
+        None => None,
+    };
+    let signature_statuses = if args.show_signature_status {
+        let verify_command = match ui
+            .settings()
+            .config()
+            .get::<FullCommandArgs>("signing.verify-command")
+        {
+            Ok(verify_command) => Some(verify_command),
+            Err(config::ConfigError::NotFound(_)) => None,
+            Err(err) => return Err(err.into()),
+        };
+        let git_repo = repo.store().git_repo();
+        let mut statuses = HashMap::new();
+        if let Some(git_repo) = &git_repo {
+            for index_entry in revset.iter().take(args.limit.unwrap_or(usize::MAX)) {
+                let commit_id = index_entry.commit_id();
+                if let Some(status) =
+                    signature_status(git_repo, verify_command.as_ref(), &commit_id)?
+                {
+                    statuses.insert(commit_id, status);
+                }
+            }
+        }
+        Some(statuses)
+    } else {
+        None
+    };
 
-        if !args.no_graph {
-            let mut graph = AsciiGraphDrawer::new(&mut formatter);
-            let iter: Box<dyn Iterator<Item = (IndexEntry, Vec<RevsetGraphEdge>)>> =
-                if args.reversed {
-                    Box::new(revset.iter().graph().reversed())
+    ui.request_pager();
+    let patch_writer = diff_format.map(|diff_format| {
+        let ui: &Ui = ui;
+        let workspace_command = &workspace_command;
+        let matcher = matcher.as_ref();
+        let diff_tool = args.tool.as_deref();
+        Box::new(move |formatter: &mut dyn Formatter, commit: &Commit| {
+            show_log_patch(
+                ui,
+                formatter,
+                workspace_command,
+                commit,
+                matcher,
+                diff_format,
+                diff_tool,
+            )
+        }) as Box<PatchWriter<'_>>
+    });
+    let options = LogOptions {
+        no_graph: args.no_graph,
+        reversed: args.reversed,
+        limit: args.limit,
+        gutter_width: args
+            .gutter_width
+            .unwrap_or_else(|| ui.settings().graph_gutter_width()),
+        legend: args.legend,
+        node_template: args
+            .node_template
+            .clone()
+            .or_else(|| ui.settings().graph_node_template()),
+        marks,
+        hide_empty_working_copy: args.hide_working_copy_if_empty
+            || ui.settings().log_hide_empty_working_copy(),
+        edge_style: args.edge_style,
+        graph_style: match args.graph_style {
+            Some(style) => style,
+            None => graph_style_setting(ui.settings())?,
+        },
+        topo_groups: args.topo_groups,
+        collapsed,
+        obsolete,
+        relative_to,
+        signature_statuses,
+        color_by_author: args.color_by_author,
+        highlighted,
+        dim_root: args.dim_root || ui.settings().log_dim_root(),
+        no_color_working_copy: args.no_color_working_copy
+            || !ui.settings().log_color_working_copy(),
+        author_column: args.author_column,
+        group_by_author: args.group_by_author,
+        color: ui.color(),
+        patch_writer,
+    };
+    let warnings = if args.format == LogFormat::Json {
+        let mut stdout = ui.stdout_formatter();
+        write_log_json(stdout.as_mut(), repo, revset.as_ref(), &options)?;
+        vec![]
+    } else {
+        let mut stdout = ui.stdout_formatter();
+        if let Some(divergence) = &divergence {
+            stdout.with_label("divergent", |formatter| {
+                formatter.write_str(&format!(
+                    "Change {} has {} versions, which split at commit {}\n",
+                    &divergence.change_id.hex()[..12],
+                    divergence.versions.len(),
+                    divergence
+                        .fork_points
+                        .iter()
+                        .map(short_commit_hash)
+                        .join(", ")
+                ))
+            })?;
+        }
+        write_log(
+            repo,
+            &workspace_id,
+            ui.settings(),
+            &mut stdout,
+            revset.as_ref(),
+            template.as_ref(),
+            &options,
+        )?
+    };
+    // The patch writer borrows `ui`
+    drop(options);
+    for warning in warnings {
+        ui.write_warn(format!("warning: {warning}\n"))?;
+    }
+
+    // Check to see if the user might have specified a path when they intended
+    // to specify a revset.
+    if let (None, [only_path], true) = (
+        &args.revisions,
+        args.paths.as_slice(),
+        ui.settings().warn_path_revset(),
+    ) {
+        if only_path == "." && workspace_command.parse_file_path(only_path)?.is_root() {
+            // For users of e.g. Mercurial, where `.` indicates the current commit.
+            ui.write_warn(&format!(
+                "warning: The argument {only_path:?} is being interpreted as a path, but this is \
+                 often not useful because all non-empty commits touch '.'.  If you meant to show \
+                 the working copy commit, pass -r '@' instead.\n"
+            ))?;
+        } else if let (true, Some(jj_revision)) =
+            (revset.is_empty(), git_revision_to_revset(only_path))
+        {
+            ui.write_warn(&format!(
+                "warning: The argument {only_path:?} is being interpreted as a path, but it looks \
+                 like a Git revision. In jj, pass -r {jj_revision:?} instead.\n"
+            ))?;
+        } else if revset.is_empty()
+            && revset::parse(only_path, &RevsetAliasesMap::new(), None).is_ok()
+        {
+            ui.write_warn(&format!(
+                "warning: The argument {only_path:?} is being interpreted as a path. To specify a \
+                 revset, pass -r {only_path:?} instead.\n"
+            ))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Translates Git revision spellings relative to the current commit, like
+/// `HEAD~2` or `@^`, to the jj revset for the same commit, like `@--` (or
+/// `@~N` for more than a few generations).
+fn git_revision_to_revset(revision: &str) -> Option<String> {
+    let mut rest = match revision.strip_prefix("HEAD") {
+        Some(rest) => rest,
+        // A plain `@` is already a jj revision
+        None => revision.strip_prefix('@').filter(|rest| !rest.is_empty())?,
+    };
+    let mut generations: u32 = 0;
+    while let Some(operator) = rest.chars().next() {
+        rest = &rest[operator.len_utf8()..];
+        let num_digits = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let (digits, tail) = rest.split_at(num_digits);
+        // Counts that don't fit in a `u32` can't be written in jj either
+        let count: u32 = if digits.is_empty() {
+            1
+        } else {
+            digits.parse().ok()?
+        };
+        match operator {
+            '~' => generations = generations.checked_add(count)?,
+            // `^2` and up pick other parents of a merge, which have no
+            // equivalent
+            '^' if count == 1 => generations = generations.checked_add(1)?,
+            _ => return None,
+        }
+        rest = tail;
+    }
+    if generations <= 3 {
+        Some(format!("@{}", "-".repeat(generations as usize)))
+    } else {
+        Some(format!("@~{generations}"))
+    }
+}
+
+/// The versions of a divergent change and the commits they split from.
+struct Divergence {
+    change_id: ChangeId,
+    versions: Vec<CommitId>,
+    fork_points: Vec<CommitId>,
+}
+
+impl Divergence {
+    /// The versions and their ancestors back to the fork points.
+    fn revset_expression(&self) -> Rc<RevsetExpression> {
+        RevsetExpression::commits(self.fork_points.clone())
+            .dag_range_to(&RevsetExpression::commits(self.versions.clone()))
+    }
+}
+
+/// The commits that aren't ancestors of `base`, plus the ancestors of `base`
+/// they forked off from, so the graph shows where they diverged.
+fn unmerged_revset_expression(base: &Rc<RevsetExpression>) -> Rc<RevsetExpression> {
+    let unmerged = base.range(&RevsetExpression::visible_heads());
+    let fork_points = unmerged.parents().intersection(&base.ancestors());
+    unmerged.union(&fork_points)
+}
+
+fn find_divergence(
+    workspace_command: &WorkspaceCommandHelper,
+    revision: &str,
+) -> Result<Divergence, CommandError> {
+    let repo = workspace_command.repo();
+    let expression = workspace_command.parse_revset(revision)?;
+    let revset = workspace_command.evaluate_revset(&expression)?;
+    let change_id = revset
+        .iter()
+        .next()
+        .ok_or_else(|| {
+            user_error(format!(
+                "Revset \"{revision}\" didn't resolve to any revisions"
+            ))
+        })?
+        .change_id();
+    // TODO: Use an index from change id to commit ids once there is one.
+    let all_commits = RevsetExpression::all().evaluate(repo.as_repo_ref(), None)?;
+    let versions = all_commits
+        .iter()
+        .filter(|index_entry| index_entry.change_id() == change_id)
+        .map(|index_entry| index_entry.commit_id())
+        .collect_vec();
+    if versions.len() < 2 {
+        return Err(user_error(format!(
+            "Change {} is not divergent",
+            &change_id.hex()[..12]
+        )));
+    }
+    let common_ancestors = versions
+        .iter()
+        .map(|commit_id| RevsetExpression::commit(commit_id.clone()).ancestors())
+        .reduce(|expression1, expression2| expression1.intersection(&expression2))
+        .unwrap();
+    let fork_points = workspace_command
+        .evaluate_revset(&common_ancestors.heads())?
+        .iter()
+        .commit_ids()
+        .collect_vec();
+    Ok(Divergence {
+        change_id,
+        versions,
+        fork_points,
+    })
+}
+
+/// The graph style set by `ui.graph.style`, falling back to the default if it
+/// is not set.
+fn graph_style_setting(settings: &UserSettings) -> Result<GraphStyle, CommandError> {
+    let style = match settings.graph_style() {
+        Some(style) => style,
+        None => return Ok(GraphStyle::default()),
+    };
+    clap::ValueEnum::from_str(&style, false).map_err(|_| {
+        let names = <GraphStyle as clap::ValueEnum>::value_variants()
+            .iter()
+            .filter_map(clap::ValueEnum::to_possible_value)
+            .map(|value| value.get_name().to_owned())
+            .join(", ");
+        user_error(format!(
+            "Config option \"ui.graph.style\" must be one of {names}, not {style:?}"
+        ))
+    })
+}
+
+/// The symbols the graph's nodes and the ends of elided edges are drawn with.
+struct GraphSymbols {
+    working_copy: String,
+    commit: String,
+    elided: String,
+}
+
+/// Reads the graph symbols from the `ui.graph.*-symbol` (or `*-char`)
+/// settings. Each must be a single character that takes up one column, or the
+/// graph's columns would no longer line up.
+fn graph_symbols(settings: &UserSettings) -> Result<GraphSymbols, CommandError> {
+    let check = |name: &str, symbol: String| {
+        let mut chars = symbol.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None)
+                if !c.is_control()
+                    && !c.is_whitespace()
+                    && textwrap::core::display_width(&symbol) == 1 =>
+            {
+                Ok(symbol)
+            }
+            _ => {
+                let symbol_key = format!("ui.graph.{name}-symbol");
+                let key = if settings.config().get_string(&symbol_key).is_ok() {
+                    symbol_key
                 } else {
-                    Box::new(revset.iter().graph())
+                    format!("ui.graph.{name}-char")
                 };
-            for (index_entry, edges) in iter {
-                let mut graphlog_edges = vec![];
-                // TODO: Should we update RevsetGraphIterator to yield this flag instead of all
-                // the missing edges since we don't care about where they point here
-                // anyway?
-                let mut has_missing = false;
-                for edge in edges {
-                    match edge.edge_type {
-                        RevsetGraphEdgeType::Missing => {
-                            has_missing = true;
-                        }
-                        RevsetGraphEdgeType::Direct => graphlog_edges.push(Edge::Present {
+                Err(user_error(format!(
+                    "Config option \"{key}\" must be a single character that is one column wide, \
+                     not {symbol:?}"
+                )))
+            }
+        }
+    };
+    Ok(GraphSymbols {
+        working_copy: check("working-copy", settings.graph_working_copy_symbol())?,
+        commit: check("commit", settings.graph_commit_symbol())?,
+        elided: check("elided", settings.graph_elided_symbol())?,
+    })
+}
+
+/// Writes the diff of a revision, which `write_log()` shows below it.
+pub type PatchWriter<'a> = dyn Fn(&mut dyn Formatter, &Commit) -> Result<(), CommandError> + 'a;
+
+/// Options controlling how `write_log()` renders revisions.
+#[derive(Default)]
+pub struct LogOptions<'a> {
+    /// Don't draw the graph, show a flat list of revisions
+    pub no_graph: bool,
+    /// Show revisions in the opposite order (older revisions first)
+    pub reversed: bool,
+    /// Show at most this many revisions, counted before reversing them
+    pub limit: Option<usize>,
+    /// Extra columns of padding between the graph and the text of each
+    /// revision
+    pub gutter_width: usize,
+    /// Print a key explaining the graph symbols before the graph
+    pub legend: bool,
+    /// Render the graph node of each revision using this template instead of
+    /// the fixed symbols
+    pub node_template: Option<String>,
+    /// Use these symbols instead for the nodes of these commits. Later entries
+    /// take precedence over earlier ones.
+    pub marks: Vec<(HashSet<CommitId>, String)>,
+    /// Leave out the working-copy commit if it's empty and undescribed, and
+    /// mark its parent as the working copy instead (only with the graph)
+    pub hide_empty_working_copy: bool,
+    /// How to draw the edges of the graph
+    pub edge_style: EdgeStyle,
+    /// Which characters to draw the graph with
+    pub graph_style: GraphStyle,
+    /// Show each branch's revisions contiguously (only with the graph)
+    pub topo_groups: bool,
+    /// Collapse connected runs of these commits into summary nodes (only with
+    /// the graph)
+    pub collapsed: HashSet<CommitId>,
+    /// Hidden commits in `collapsed`. Consecutive ones with the same parents
+    /// are collapsed together, and their summary nodes say they're obsolete.
+    pub obsolete: HashSet<CommitId>,
+    /// Prefix each revision with its position relative to these commits (only
+    /// with the graph)
+    pub relative_to: Option<RelativeTarget>,
+    /// Prefix each revision with the status of its signature, if it has one
+    /// (only with the graph)
+    pub signature_statuses: Option<HashMap<CommitId, SignatureStatus>>,
+    /// Color the graph nodes by author
+    pub color_by_author: bool,
+    /// Highlight these commits (only with the graph)
+    pub highlighted: HashSet<CommitId>,
+    /// Draw the root commit's row dimmed (only with the graph)
+    pub dim_root: bool,
+    /// Don't use the working-copy colors for the working-copy commit's row
+    /// (only with the graph)
+    pub no_color_working_copy: bool,
+    /// Show the author of each revision in a column this wide (only with the
+    /// graph)
+    pub author_column: Option<usize>,
+    /// Group revisions by author (only without the graph)
+    pub group_by_author: bool,
+    /// Color the output with the colors from the settings
+    pub color: bool,
+    /// Show the diff of each revision, written by this function
+    pub patch_writer: Option<Box<PatchWriter<'a>>>,
+}
+
+/// Compiles the argument of `jj log --author`: a case-insensitive substring, or
+/// a regular expression if it starts with "regex:".
+fn author_pattern_regex(pattern: &str) -> Result<regex::Regex, CommandError> {
+    let result = match pattern.strip_prefix("regex:") {
+        Some(regex) => regex::Regex::new(regex),
+        None => regex::Regex::new(&format!("(?i){}", regex::escape(pattern))),
+    };
+    result.map_err(|err| user_error(format!("Invalid author pattern \"{pattern}\": {err}")))
+}
+
+/// Formats `signature` as "Name <email>" in exactly `width` characters, padded
+/// with spaces or cut off with "…".
+fn author_column_text(signature: &Signature, width: usize) -> String {
+    let text = if signature.email.is_empty() {
+        signature.name.clone()
+    } else {
+        format!("{} <{}>", signature.name, signature.email)
+    };
+    let num_chars = text.chars().count();
+    if num_chars <= width {
+        format!("{text}{}", " ".repeat(width - num_chars))
+    } else if width == 0 {
+        String::new()
+    } else {
+        let mut truncated: String = text.chars().take(width - 1).collect();
+        truncated.push('…');
+        truncated
+    }
+}
+
+/// The commits that `jj log --relative-to` describes the other revisions
+/// relative to.
+#[derive(Clone, Debug, Default)]
+pub struct RelativeTarget {
+    /// The commits in the given revset
+    pub commits: HashSet<CommitId>,
+    /// The ancestors of `commits`, including themselves
+    pub ancestors: HashSet<CommitId>,
+}
+
+impl RelativeTarget {
+    /// Whether the commit is "on", "behind", or "ahead" of the target.
+    pub fn position_of(&self, commit_id: &CommitId) -> &'static str {
+        if self.commits.contains(commit_id) {
+            "on"
+        } else if self.ancestors.contains(commit_id) {
+            "behind"
+        } else {
+            "ahead"
+        }
+    }
+}
+
+/// The result of verifying a commit's signature
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SignatureStatus {
+    Good,
+    Bad,
+    /// There's no command to verify the signature with, or it failed to run
+    Unknown,
+}
+
+impl SignatureStatus {
+    fn marker(self) -> &'static str {
+        match self {
+            SignatureStatus::Good => "G",
+            SignatureStatus::Bad => "B",
+            SignatureStatus::Unknown => "U",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            SignatureStatus::Good => "good",
+            SignatureStatus::Bad => "bad",
+            SignatureStatus::Unknown => "unknown",
+        }
+    }
+}
+
+/// Verifies the signature of the Git commit `commit_id`, if it has one, by
+/// running `verify_command` with the paths to files containing the signature
+/// and the signed data. The signature is good if it exits with 0.
+fn signature_status(
+    git_repo: &git2::Repository,
+    verify_command: Option<&FullCommandArgs>,
+    commit_id: &CommitId,
+) -> Result<Option<SignatureStatus>, CommandError> {
+    let oid = git2::Oid::from_bytes(commit_id.as_bytes()).unwrap();
+    let (signature, signed_data) = match git_repo.extract_signature(&oid, None) {
+        Ok(result) => result,
+        // Not signed, or not a Git commit (like the root commit)
+        Err(_) => return Ok(None),
+    };
+    let verify_command = match verify_command {
+        Some(verify_command) => verify_command,
+        None => return Ok(Some(SignatureStatus::Unknown)),
+    };
+    let temp_dir = tempfile::Builder::new().prefix("jj-verify-").tempdir()?;
+    let signature_path = temp_dir.path().join("signature");
+    let signed_data_path = temp_dir.path().join("signed_data");
+    std::fs::write(&signature_path, &*signature)?;
+    std::fs::write(&signed_data_path, &*signed_data)?;
+    let status = verify_command
+        .to_command()
+        .arg(&signature_path)
+        .arg(&signed_data_path)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status();
+    Ok(Some(match status {
+        Ok(status) if status.success() => SignatureStatus::Good,
+        Ok(status) if status.code().is_some() => SignatureStatus::Bad,
+        _ => SignatureStatus::Unknown,
+    }))
+}
+
+/// Renders the revisions in `revset` the way `jj log` does, with the
+/// working-copy commit of `workspace_id` marked.
+///
+/// The output is written to `writer`. Returns the warnings to show the user,
+/// e.g. about node template results that couldn't be used.
+pub fn write_log(
+    repo: &Arc<ReadonlyRepo>,
+    workspace_id: &WorkspaceId,
+    settings: &UserSettings,
+    writer: &mut dyn Write,
+    revset: &dyn Revset,
+    template: &dyn Template<Commit>,
+    options: &LogOptions,
+) -> Result<Vec<String>, CommandError> {
+    let store = repo.store();
+    let formatter_factory = FormatterFactory::prepare(settings, options.color);
+    let mut warnings = vec![];
+    let mut checkout_id = repo.view().get_wc_commit_id(workspace_id).cloned();
+    let mut hidden_commit_id = None;
+    if options.hide_empty_working_copy && !options.no_graph {
+        if let Some(wc_commit_id) = &checkout_id {
+            let wc_commit = store.get_commit(wc_commit_id)?;
+            if let Some(parent_id) = parent_of_empty_head(repo, revset, &wc_commit) {
+                hidden_commit_id = checkout_id.replace(parent_id);
+            }
+        }
+    }
+
+    let mut formatter = formatter_factory.new_formatter(writer);
+    let mut formatter = formatter.as_mut();
+    formatter.add_label("log")?;
+
+    if !options.no_graph {
+        let GraphSymbols {
+            working_copy: working_copy_symbol,
+            commit: commit_symbol,
+            elided: elided_symbol,
+        } = graph_symbols(settings)?;
+        if options.legend {
+            formatter.with_label("legend", |formatter| {
+                formatter.write_str(&format!(
+                    "Legend: {working_copy_symbol} working copy, {commit_symbol} commit, \
+                     {elided_symbol} elided history\n"
+                ))
+            })?;
+        }
+        let diff_word_wrap = settings.diff_word_wrap();
+        let node_template = options.node_template.as_ref().map(|template_string| {
+            crate::template_parser::parse_commit_template(
+                repo.as_repo_ref(),
+                workspace_id,
+                template_string,
+            )
+        });
+        // Bad node template results are only reported for the first commit
+        let mut warned_about_node_template = false;
+        let mut graph = AsciiGraphDrawer::new(&mut formatter)
+            .with_gutter_width(options.gutter_width)
+            .with_style(options.graph_style)
+            .with_elided_symbol(&elided_symbol);
+        let hidden_position = hidden_commit_id
+            .as_ref()
+            .and_then(|commit_id| repo.index().commit_id_to_pos(commit_id));
+        let limit = options.limit.unwrap_or(usize::MAX);
+        // The limit applies before reversing, so the newest commits are shown
+        let forward_iter = revset
+            .iter()
+            .graph()
+            .filter(|(index_entry, _)| Some(index_entry.position()) != hidden_position)
+            .take(limit);
+        let forward_iter: Box<dyn Iterator<Item = (IndexEntry, Vec<RevsetGraphEdge>)>> =
+            if options.topo_groups {
+                Box::new(TopoGroupedRevsetGraphIterator::new(forward_iter))
+            } else {
+                Box::new(forward_iter)
+            };
+        let iter: Box<dyn Iterator<Item = (IndexEntry, Vec<RevsetGraphEdge>)>> = if options.reversed
+        {
+            Box::new(ReverseRevsetGraphIterator::new(forward_iter))
+        } else {
+            forward_iter
+        };
+        let (iter, collapsed_groups) = if options.collapsed.is_empty() {
+            (iter, CollapsedGroups::default())
+        } else {
+            let rows = iter.collect_vec();
+            let groups = CollapsedGroups::new(&rows, &options.collapsed, &options.obsolete);
+            let iter: Box<dyn Iterator<Item = (IndexEntry, Vec<RevsetGraphEdge>)>> =
+                Box::new(rows.into_iter());
+            (iter, groups)
+        };
+        let mut collapsed_edges = vec![];
+        // The oldest revision within the limit, if the limit cut off any
+        let last_position_before_limit = options.limit.and_then(|limit| {
+            let mut entries = revset
+                .iter()
+                .filter(|index_entry| Some(index_entry.position()) != hidden_position)
+                .skip(limit.checked_sub(1)?);
+            let last_entry = entries.next()?;
+            entries.next().map(|_| last_entry.position())
+        });
+        for (index_entry, edges) in iter {
+            // Ancestors that are only cut off by the limit aren't marked as elided
+            let is_last_before_limit =
+                !options.reversed && Some(index_entry.position()) == last_position_before_limit;
+            let commit_id = index_entry.commit_id();
+            let commit = store.get_commit(&commit_id)?;
+            let mut graphlog_edges = vec![];
+            // TODO: Should we update RevsetGraphIterator to yield this flag instead of all
+            // the missing edges since we don't care about where they point here
+            // anyway?
+            let mut has_missing = false;
+            let group_key = collapsed_groups.key(index_entry.position());
+            for edge in edges {
+                // Only the parent of the hidden commit can have an edge to it,
+                // when the graph is reversed
+                if Some(edge.target) == hidden_position {
+                    continue;
+                }
+                // Edges to revisions that the limit cut off would point at rows
+                // that aren't printed
+                if edge.edge_type != RevsetGraphEdgeType::Missing
+                    && last_position_before_limit.map_or(false, |last| edge.target < last)
+                {
+                    continue;
+                }
+                let target = collapsed_groups.key(edge.target).unwrap_or(edge.target);
+                // Edges within a collapsed group aren't drawn
+                if group_key.is_some() && group_key == Some(target) {
+                    continue;
+                }
+                match edge.edge_type {
+                    RevsetGraphEdgeType::Missing => {
+                        has_missing = true;
+                    }
+                    RevsetGraphEdgeType::Direct => {
+                        let rebased = options.edge_style == EdgeStyle::Rebase && {
+                            let target_id = repo.index().entry_by_pos(edge.target).commit_id();
+                            // Reversed edges point from parents to children
+                            if options.reversed {
+                                is_rebased_onto(&store.get_commit(&target_id)?, &commit_id)
+                            } else {
+                                is_rebased_onto(&commit, &target_id)
+                            }
+                        };
+                        graphlog_edges.push(Edge::Present {
                             direct: true,
-                            target: edge.target,
-                        }),
-                        RevsetGraphEdgeType::Indirect => graphlog_edges.push(Edge::Present {
-                            direct: false,
-                            target: edge.target,
-                        }),
+                            rebased,
+                            target,
+                        })
                     }
+                    RevsetGraphEdgeType::Indirect => graphlog_edges.push(Edge::Present {
+                        direct: false,
+                        rebased: false,
+                        target,
+                    }),
                 }
-                if has_missing {
-                    graphlog_edges.push(Edge::Missing);
+            }
+            if has_missing && !is_last_before_limit {
+                graphlog_edges.push(Edge::Missing);
+            }
+            if let Some(group_key) = group_key {
+                for edge in graphlog_edges {
+                    if !collapsed_edges.contains(&edge) {
+                        collapsed_edges.push(edge);
+                    }
                 }
+                let (count, last_position) = collapsed_groups.groups[&group_key];
+                let kind = if collapsed_groups.obsolete_groups.contains(&group_key) {
+                    "obsolete commit"
+                } else {
+                    "commit"
+                };
+                if index_entry.position() != last_position {
+                    continue;
+                }
+                let mut edges = std::mem::take(&mut collapsed_edges);
+                // The missing edge is drawn after the others
+                edges.sort_by_key(|edge| *edge == Edge::Missing);
                 let mut buffer = vec![];
-                let commit_id = index_entry.commit_id();
-                let commit = store.get_commit(&commit_id)?;
-                let is_checkout = Some(&commit_id) == checkout_id;
                 {
-                    let mut formatter = ui.new_formatter(&mut buffer);
-                    if is_checkout {
-                        formatter.with_label("working_copy", |formatter| {
-                            template.format(&commit, formatter)
-                        })?;
+                    let mut formatter = formatter_factory.new_formatter(&mut buffer);
+                    formatter.with_label("collapsed", |formatter| {
+                        formatter.write_str(&format!(
+                            "({count} {kind}{})",
+                            if count == 1 { "" } else { "s" }
+                        ))
+                    })?;
+                }
+                buffer.push(b'\n');
+                graph.add_node(&group_key, &edges, b"+", &buffer)?;
+                continue;
+            }
+            let mut buffer = vec![];
+            let is_checkout = Some(&commit_id) == checkout_id.as_ref();
+            let is_dimmed = options.dim_root && &commit_id == store.root_commit_id();
+            let is_highlighted = options.highlighted.contains(&commit_id);
+            {
+                let mut formatter = formatter_factory.new_formatter(&mut buffer);
+                if is_highlighted {
+                    formatter.add_label("highlighted")?;
+                }
+                if let Some(width) = options.author_column {
+                    formatter.with_label("author", |formatter| {
+                        formatter.write_str(&author_column_text(commit.author(), width))
+                    })?;
+                    formatter.write_str(" ")?;
+                }
+                if let Some(signature_statuses) = &options.signature_statuses {
+                    match signature_statuses.get(&commit_id) {
+                        Some(status) => {
+                            formatter.with_label("signature", |formatter| {
+                                formatter.with_label(status.label(), |formatter| {
+                                    formatter.write_str(status.marker())
+                                })
+                            })?;
+                            formatter.write_str(" ")?;
+                        }
+                        None => formatter.write_str("  ")?,
+                    }
+                }
+                if let Some(relative_to) = &options.relative_to {
+                    let position = relative_to.position_of(&commit_id);
+                    formatter.with_label("relative", |formatter| {
+                        formatter
+                            .with_label(position, |formatter| write!(formatter, "[{position}]"))
+                    })?;
+                    formatter.write_str(" ")?;
+                }
+                if is_dimmed {
+                    // Drop the template's own colors so the whole row is dim
+                    let mut text = vec![];
+                    template.format(&commit, &mut PlainTextFormatter::new(&mut text))?;
+                    // Leave the newline out, so the color is reset before it
+                    let text = text.strip_suffix(b"\n").unwrap_or(&text);
+                    formatter.with_label("dimmed", |formatter| formatter.write_all(text))?;
+                } else if is_checkout && !options.no_color_working_copy {
+                    formatter.with_label("working_copy", |formatter| {
+                        template.format(&commit, formatter)
+                    })?;
+                } else {
+                    template.format(&commit, formatter.as_mut())?;
+                }
+                if is_highlighted {
+                    formatter.remove_label()?;
+                }
+            }
+            if !buffer.ends_with(b"\n") {
+                buffer.push(b'\n');
+            }
+            if let Some(patch_writer) = &options.patch_writer {
+                let mut patch = vec![];
+                {
+                    let mut formatter = formatter_factory.new_formatter(&mut patch);
+                    patch_writer(formatter.as_mut(), &commit)?;
+                }
+                // Wrap the diff here rather than letting the terminal do it so
+                // continuation lines get the graph edges drawn next to them.
+                // The width includes the graph.
+                let wrap_width = if diff_word_wrap == 0 {
+                    0
+                } else {
+                    let text_offset = graph.text_offset(&index_entry.position(), &graphlog_edges);
+                    diff_word_wrap.saturating_sub(text_offset).max(1)
+                };
+                buffer.extend(wrap_text(&patch, wrap_width));
+            }
+            let default_node_symbol = if is_checkout {
+                &working_copy_symbol
+            } else {
+                &commit_symbol
+            };
+            let node_symbol = match &node_template {
+                Some(node_template) => {
+                    let mut symbol = vec![];
+                    node_template.format(&commit, &mut PlainTextFormatter::new(&mut symbol))?;
+                    let symbol = String::from_utf8_lossy(&symbol).into_owned();
+                    if symbol.chars().count() == 1 {
+                        symbol
                     } else {
-                        template.format(&commit, formatter.as_mut())?;
+                        if !warned_about_node_template {
+                            warnings.push(format!(
+                                "Node template produced {symbol:?} for commit {}, which is not a \
+                                 single character; using {default_node_symbol:?} instead",
+                                short_commit_hash(&commit_id)
+                            ));
+                            warned_about_node_template = true;
+                        }
+                        default_node_symbol.clone()
                     }
                 }
-                if !buffer.ends_with(b"\n") {
-                    buffer.push(b'\n');
+                None => default_node_symbol.clone(),
+            };
+            let node_symbol = match options
+                .marks
+                .iter()
+                .rev()
+                .find(|(commit_ids, _)| commit_ids.contains(&commit_id))
+            {
+                Some((_, symbol)) => symbol.clone(),
+                None => node_symbol,
+            };
+            let node_label = if is_dimmed {
+                Some("dimmed".to_string())
+            } else if is_highlighted {
+                Some("highlighted".to_string())
+            } else if options.color_by_author {
+                Some(author_color_label(&commit.author().email))
+            } else {
+                None
+            };
+            let node_symbol = if let Some(node_label) = node_label {
+                let mut colored_symbol = vec![];
+                {
+                    let mut formatter = formatter_factory.new_formatter(&mut colored_symbol);
+                    formatter
+                        .with_label(&node_label, |formatter| formatter.write_str(&node_symbol))?;
                 }
-                if let Some(diff_format) = diff_format {
-                    let mut formatter = ui.new_formatter(&mut buffer);
-                    show_patch(
-                        formatter.as_mut(),
-                        &workspace_command,
-                        &commit,
-                        matcher.as_ref(),
-                        diff_format,
-                    )?;
+                colored_symbol
+            } else {
+                node_symbol.into_bytes()
+            };
+            graph.add_node(
+                &index_entry.position(),
+                &graphlog_edges,
+                &node_symbol,
+                &buffer,
+            )?;
+        }
+    } else if options.group_by_author {
+        let mut commits: Vec<Commit> = revset
+            .iter()
+            .take(options.limit.unwrap_or(usize::MAX))
+            .map(|index_entry| store.get_commit(&index_entry.commit_id()))
+            .try_collect()?;
+        // Newest first within each author, like the rest of the log
+        commits.sort_by(|commit1, commit2| {
+            let (author1, author2) = (commit1.author(), commit2.author());
+            let timestamp_order = if options.reversed {
+                author1
+                    .timestamp
+                    .timestamp
+                    .cmp(&author2.timestamp.timestamp)
+            } else {
+                author2
+                    .timestamp
+                    .timestamp
+                    .cmp(&author1.timestamp.timestamp)
+            };
+            (&author1.name, &author1.email)
+                .cmp(&(&author2.name, &author2.email))
+                .then(timestamp_order)
+        });
+        let groups = commits
+            .into_iter()
+            .group_by(|commit| (commit.author().name.clone(), commit.author().email.clone()));
+        for (i, ((name, email), commits)) in groups.into_iter().enumerate() {
+            if i > 0 {
+                formatter.write_str("\n")?;
+            }
+            formatter.with_label("author", |formatter| {
+                formatter.write_str(&format!("{name} <{email}>\n"))
+            })?;
+            for commit in commits {
+                template.format(&commit, formatter)?;
+                if let Some(patch_writer) = &options.patch_writer {
+                    patch_writer(&mut *formatter, &commit)?;
                 }
-                let node_symbol = if is_checkout { b"@" } else { b"o" };
-                graph.add_node(
-                    &index_entry.position(),
-                    &graphlog_edges,
-                    node_symbol,
-                    &buffer,
-                )?;
             }
+        }
+    } else {
+        let limit = options.limit.unwrap_or(usize::MAX);
+        let iter: Box<dyn Iterator<Item = IndexEntry>> = if options.reversed {
+            Box::new(revset.iter().take(limit).collect_vec().into_iter().rev())
         } else {
-            let iter: Box<dyn Iterator<Item = IndexEntry>> = if args.reversed {
-                Box::new(revset.iter().reversed())
-            } else {
-                Box::new(revset.iter())
+            Box::new(revset.iter().take(limit))
+        };
+        for index_entry in iter {
+            let commit = store.get_commit(&index_entry.commit_id())?;
+            template.format(&commit, formatter)?;
+            if let Some(patch_writer) = &options.patch_writer {
+                patch_writer(&mut *formatter, &commit)?;
+            }
+        }
+    }
+
+    formatter.remove_label()?;
+    Ok(warnings)
+}
+
+/// The runs of consecutive graph rows that `jj log --collapse` draws as a
+/// single node. Each group is identified by the position of its first row.
+#[derive(Default)]
+struct CollapsedGroups {
+    /// The group of each collapsed row
+    keys: HashMap<IndexPosition, IndexPosition>,
+    /// The number of rows in each group, and the position of its last row
+    groups: HashMap<IndexPosition, (usize, IndexPosition)>,
+    /// The groups that only have obsolete rows
+    obsolete_groups: HashSet<IndexPosition>,
+}
+
+impl CollapsedGroups {
+    /// Groups the rows whose commits are in `collapsed`. A row joins the group
+    /// of the row before it if an edge of that group leads to it, so each
+    /// group is connected and nothing else is drawn in the middle of it. An
+    /// `obsolete` row also joins the group of an obsolete row before it with
+    /// the same edges, since they're usually predecessors of the same commit.
+    fn new(
+        rows: &[(IndexEntry, Vec<RevsetGraphEdge>)],
+        collapsed: &HashSet<CommitId>,
+        obsolete: &HashSet<CommitId>,
+    ) -> Self {
+        let mut result = CollapsedGroups::default();
+        let mut current_group: Option<(IndexPosition, HashSet<IndexPosition>)> = None;
+        // The edges of the row before, if it's obsolete
+        let mut obsolete_edges: Option<&[RevsetGraphEdge]> = None;
+        for (index_entry, edges) in rows {
+            let position = index_entry.position();
+            let commit_id = index_entry.commit_id();
+            if !collapsed.contains(&commit_id) {
+                current_group = None;
+                obsolete_edges = None;
+                continue;
+            }
+            let is_obsolete = obsolete.contains(&commit_id);
+            let joins_group = match &current_group {
+                Some((_, targets)) => {
+                    targets.contains(&position)
+                        || (is_obsolete && obsolete_edges == Some(edges.as_slice()))
+                }
+                None => false,
             };
-            for index_entry in iter {
-                let commit = store.get_commit(&index_entry.commit_id())?;
-                template.format(&commit, formatter)?;
-                if let Some(diff_format) = diff_format {
-                    show_patch(
-                        formatter,
-                        &workspace_command,
-                        &commit,
-                        matcher.as_ref(),
-                        diff_format,
-                    )?;
+            if !joins_group {
+                current_group = Some((position, HashSet::new()));
+                if is_obsolete {
+                    result.obsolete_groups.insert(position);
                 }
             }
+            obsolete_edges = is_obsolete.then(|| edges.as_slice());
+            let (key, targets) = current_group.as_mut().unwrap();
+            let key = *key;
+            if !is_obsolete {
+                result.obsolete_groups.remove(&key);
+            }
+            targets.extend(edges.iter().map(|edge| edge.target));
+            result.keys.insert(position, key);
+            let group = result.groups.entry(key).or_insert((0, position));
+            *group = (group.0 + 1, position);
         }
+        result
     }
 
-    // Check to see if the user might have specified a path when they intended
-    // to specify a revset.
-    if let (None, [only_path]) = (&args.revisions, args.paths.as_slice()) {
-        if only_path == "." && workspace_command.parse_file_path(only_path)?.is_root() {
-            // For users of e.g. Mercurial, where `.` indicates the current commit.
-            ui.write_warn(&format!(
-                "warning: The argument {only_path:?} is being interpreted as a path, but this is \
-                 often not useful because all non-empty commits touch '.'.  If you meant to show \
-                 the working copy commit, pass -r '@' instead.\n"
-            ))?;
-        } else if revset.is_empty()
-            && revset::parse(only_path, &RevsetAliasesMap::new(), None).is_ok()
-        {
-            ui.write_warn(&format!(
-                "warning: The argument {only_path:?} is being interpreted as a path. To specify a \
-                 revset, pass -r {only_path:?} instead.\n"
-            ))?;
+    fn key(&self, position: IndexPosition) -> Option<IndexPosition> {
+        self.keys.get(&position).copied()
+    }
+}
+
+#[derive(serde::Serialize)]
+struct JsonSignature<'a> {
+    name: &'a str,
+    email: &'a str,
+    /// RFC 3339, or null if the timestamp is out of range
+    timestamp: Option<String>,
+}
+
+impl<'a> JsonSignature<'a> {
+    fn new(signature: &'a Signature) -> Self {
+        JsonSignature {
+            name: &signature.name,
+            email: &signature.email,
+            timestamp: timestamp_to_datetime(&signature.timestamp)
+                .map(|datetime| datetime.to_rfc3339_opts(SecondsFormat::Millis, false)),
         }
     }
+}
+
+#[derive(serde::Serialize)]
+struct JsonCommit<'a> {
+    commit_id: String,
+    change_id: String,
+    description: &'a str,
+    author: JsonSignature<'a>,
+    committer: JsonSignature<'a>,
+    parents: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    diff: Option<String>,
+}
 
+/// Writes the revisions as a JSON array, one object per revision on its own
+/// line so the output can be streamed into tools like `jq`. Only the ordering,
+/// limit and diff options apply; there's no graph.
+pub fn write_log_json(
+    formatter: &mut dyn Formatter,
+    repo: &Arc<ReadonlyRepo>,
+    revset: &dyn Revset,
+    options: &LogOptions,
+) -> Result<(), CommandError> {
+    let store = repo.store();
+    let limit = options.limit.unwrap_or(usize::MAX);
+    let iter: Box<dyn Iterator<Item = IndexEntry>> = if options.reversed {
+        Box::new(revset.iter().take(limit).collect_vec().into_iter().rev())
+    } else {
+        Box::new(revset.iter().take(limit))
+    };
+    formatter.write_str("[")?;
+    let mut is_empty = true;
+    for index_entry in iter {
+        let commit = store.get_commit(&index_entry.commit_id())?;
+        let diff = match &options.patch_writer {
+            Some(patch_writer) => {
+                let mut patch = vec![];
+                patch_writer(&mut PlainTextFormatter::new(&mut patch), &commit)?;
+                Some(String::from_utf8_lossy(&patch).into_owned())
+            }
+            None => None,
+        };
+        let json_commit = JsonCommit {
+            commit_id: commit.id().hex(),
+            change_id: commit.change_id().hex(),
+            description: commit.description(),
+            author: JsonSignature::new(commit.author()),
+            committer: JsonSignature::new(commit.committer()),
+            parents: commit.parent_ids().iter().map(CommitId::hex).collect(),
+            diff,
+        };
+        formatter.write_str(if is_empty { "\n" } else { ",\n" })?;
+        formatter.write_str(&serde_json::to_string(&json_commit).unwrap())?;
+        is_empty = false;
+    }
+    formatter.write_str(if is_empty { "]\n" } else { "\n]\n" })?;
     Ok(())
 }
 
+/// The label that `jj log --color-by-author` uses for the node of a commit by
+/// `author_email`. The template method `hash()` picks the same index for the
+/// email, so templates can color other parts of the output to match.
+fn author_color_label(author_email: &str) -> String {
+    let index = stable_hash(author_email) % crate::formatter::AUTHOR_COLORS.len() as u64;
+    format!("author_color_{index}")
+}
+
+/// Whether `commit` was rebased onto its parent `parent_id`, i.e. none of the
+/// commits it was rewritten from had that parent. A commit that wasn't
+/// rewritten doesn't count as rebased.
+fn is_rebased_onto(commit: &Commit, parent_id: &CommitId) -> bool {
+    let predecessors = commit.predecessors();
+    !predecessors.is_empty()
+        && predecessors
+            .iter()
+            .all(|predecessor| !predecessor.parent_ids().contains(parent_id))
+}
+
+/// Returns the parent of `commit` if `commit` is an undescribed head without
+/// changes and its parent is in `revset`, so `commit` can be left out of a log
+/// without hiding where it is.
+fn parent_of_empty_head(
+    repo: &ReadonlyRepo,
+    revset: &dyn Revset,
+    commit: &Commit,
+) -> Option<CommitId> {
+    if !commit.description().is_empty()
+        || !commit.is_empty()
+        || !repo.view().heads().contains(commit.id())
+    {
+        return None;
+    }
+    let parent_id = &commit.parent_ids()[0];
+    revset
+        .iter()
+        .any(|index_entry| &index_entry.commit_id() == parent_id)
+        .then(|| parent_id.clone())
+}
+
 fn show_patch(
+    ui: &Ui,
     formatter: &mut dyn Formatter,
     workspace_command: &WorkspaceCommandHelper,
     commit: &Commit,
@@ -2236,10 +4454,97 @@ fn show_patch(
     let parents = commit.parents();
     let from_tree = merge_commit_trees(workspace_command.repo().as_repo_ref(), &parents);
     let to_tree = commit.tree();
+    if format != DiffFormat::Summary && format != DiffFormat::Stat {
+        if let Ok(command) = ui
+            .settings()
+            .config()
+            .get::<FullCommandArgs>("diff.external-command")
+        {
+            let mut patch = vec![];
+            show_git_diff(
+                &mut PlainTextFormatter::new(&mut patch),
+                workspace_command,
+                from_tree.diff(&to_tree, matcher),
+            )?;
+            match run_external_diff_command(&command, patch) {
+                Ok(output) => {
+                    formatter.write_all(&output)?;
+                    return Ok(());
+                }
+                Err(err) => {
+                    ui.stderr_formatter().with_label("warning", |formatter| {
+                        formatter.write_str(&format!(
+                            "warning: Failed to run external diff command '{command}': {err}; \
+                             showing the built-in diff instead\n"
+                        ))
+                    })?;
+                }
+            }
+        }
+    }
     let diff_iterator = from_tree.diff(&to_tree, matcher);
     show_diff(formatter, workspace_command, diff_iterator, format)
 }
 
+/// Shows the patch of `commit` in `diff_format`, or with the external
+/// `diff_tool` if given.
+fn show_log_patch(
+    ui: &Ui,
+    formatter: &mut dyn Formatter,
+    workspace_command: &WorkspaceCommandHelper,
+    commit: &Commit,
+    matcher: &dyn Matcher,
+    diff_format: DiffFormat,
+    diff_tool: Option<&str>,
+) -> Result<(), CommandError> {
+    if let Some(tool_name) = diff_tool {
+        let parents = commit.parents();
+        let from_tree = merge_commit_trees(workspace_command.repo().as_repo_ref(), &parents);
+        let output = crate::diff_edit::run_diff_tool(
+            ui.settings(),
+            tool_name,
+            &from_tree,
+            &commit.tree(),
+            matcher,
+        )
+        .map_err(|err| user_error(format!("Failed to show diff with '{tool_name}': {err}")))?;
+        formatter.write_all(&output)?;
+        Ok(())
+    } else {
+        show_patch(
+            ui,
+            formatter,
+            workspace_command,
+            commit,
+            matcher,
+            diff_format,
+        )
+    }
+}
+
+/// Pipes a unified diff through `command` and returns what it printed.
+fn run_external_diff_command(command: &FullCommandArgs, patch: Vec<u8>) -> io::Result<Vec<u8>> {
+    let mut child = command
+        .to_command()
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()?;
+    let mut stdin = child.stdin.take().unwrap();
+    // Write from another thread so a command that prints output before it
+    // has read all of its input can't deadlock with us
+    let writer = std::thread::spawn(move || stdin.write_all(&patch));
+    let output = child.wait_with_output()?;
+    // The command may legitimately exit without reading all of its input
+    writer.join().unwrap().ok();
+    if !output.status.success() {
+        return Err(io::Error::new(
+            io::ErrorKind::Other,
+            format!("exited with {}", output.status),
+        ));
+    }
+    Ok(output.stdout)
+}
+
 fn cmd_obslog(ui: &mut Ui, command: &CommandHelper, args: &ObslogArgs) -> Result<(), CommandError> {
     let workspace_command = command.workspace_helper(ui)?;
 
@@ -2250,8 +4555,9 @@ fn cmd_obslog(ui: &mut Ui, command: &CommandHelper, args: &ObslogArgs) -> Result
         .view()
         .get_wc_commit_id(&workspace_id);
 
-    let diff_format = (args.patch || args.diff_format.git || args.diff_format.summary)
-        .then(|| diff_format_for(ui, &args.diff_format));
+    let diff_format =
+        (args.patch || args.diff_format.git || args.diff_format.summary || args.diff_format.stat)
+            .then(|| diff_format_for(ui, &args.diff_format));
 
     let template_string = match &args.template {
         Some(value) => value.to_string(),
@@ -2274,7 +4580,10 @@ fn cmd_obslog(ui: &mut Ui, command: &CommandHelper, args: &ObslogArgs) -> Result
         Box::new(|commit: &Commit| commit.predecessors()),
     );
     if !args.no_graph {
-        let mut graph = AsciiGraphDrawer::new(&mut formatter);
+        let symbols = graph_symbols(ui.settings())?;
+        let mut graph = AsciiGraphDrawer::new(&mut formatter)
+            .with_style(graph_style_setting(ui.settings())?)
+            .with_elided_symbol(&symbols.elided);
         for commit in commits {
             let mut edges = vec![];
             for predecessor in &commit.predecessors() {
@@ -2298,11 +4607,11 @@ fn cmd_obslog(ui: &mut Ui, command: &CommandHelper, args: &ObslogArgs) -> Result
                 )?;
             }
             let node_symbol = if Some(commit.id()) == wc_commit_id {
-                b"@"
+                &symbols.working_copy
             } else {
-                b"o"
+                &symbols.commit
             };
-            graph.add_node(commit.id(), &edges, node_symbol, &buffer)?;
+            graph.add_node(commit.id(), &edges, node_symbol.as_bytes(), &buffer)?;
         }
     } else {
         for commit in commits {
@@ -3560,6 +5869,279 @@ fn list_branches(
     Ok(())
 }
 
+fn cmd_config(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    subcommand: &ConfigSubcommand,
+) -> Result<(), CommandError> {
+    match subcommand {
+        ConfigSubcommand::List(sub_args) => cmd_config_list(ui, command, sub_args),
+        ConfigSubcommand::Get(sub_args) => cmd_config_get(ui, command, sub_args),
+        ConfigSubcommand::Set(sub_args) => cmd_config_set(ui, command, sub_args),
+        ConfigSubcommand::Reset(sub_args) => cmd_config_reset(ui, command, sub_args),
+        ConfigSubcommand::Validate(sub_args) => cmd_config_validate(ui, command, sub_args),
+        ConfigSubcommand::Export(sub_args) => cmd_config_export(ui, command, sub_args),
+        ConfigSubcommand::Where(sub_args) => cmd_config_where(ui, command, sub_args),
+    }
+}
+
+/// Includes the current repo's config in `ui`'s settings, if there is a repo.
+/// Loads the config of the current repo, if there is one, and returns the path
+/// to its config file.
+fn load_repo_config_if_any(
+    ui: &mut Ui,
+    command: &CommandHelper,
+) -> Result<Option<PathBuf>, CommandError> {
+    match command.load_workspace(ui) {
+        Ok(workspace) => Ok(Some(workspace.repo_path().join("config.toml"))),
+        // Outside a repo, only the user's config applies
+        Err(CommandError::UserError { .. }) => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+fn load_config_layers(
+    command: &CommandHelper,
+    repo_config_path: Option<&Path>,
+) -> Result<crate::config::ConfigLayers, CommandError> {
+    crate::cli_util::load_config_layers(command.global_args(), repo_config_path)
+}
+
+/// Returns true if `key` is set by something other than jj's built-in
+/// defaults, even if it's set to the same value.
+fn is_config_set_explicitly(layers: &crate::config::ConfigLayers, key: &str) -> bool {
+    !matches!(
+        layers.find_source(key),
+        None | Some(crate::config::ConfigSource::Default)
+    )
+}
+
+fn cmd_config_list(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &ConfigListArgs,
+) -> Result<(), CommandError> {
+    let repo_config_path = load_repo_config_if_any(ui, command)?;
+    let layers = load_config_layers(command, repo_config_path.as_deref())?;
+    let values = crate::config::flatten_config(ui.settings().config())
+        .map_err(|err| CommandError::ConfigError(err.to_string()))?;
+    let filter = args
+        .filter
+        .as_ref()
+        .map(|glob| crate::config::compile_config_key_glob(glob));
+    ui.request_pager();
+    let mut formatter = ui.stdout_formatter();
+    for (key, value) in values {
+        if let Some(name) = &args.name {
+            if key != *name && !key.starts_with(&format!("{name}.")) {
+                continue;
+            }
+        }
+        if let Some(filter) = &filter {
+            if !filter.is_match(&key) {
+                continue;
+            }
+        }
+        if args.only_explicit && !is_config_set_explicitly(&layers, &key) {
+            continue;
+        }
+        let mut line = format!("{key} = {}", crate::config::format_config_value(&value));
+        if args.show_origin {
+            if let Some(source) = layers.find_source(&key) {
+                line.push_str(&format!("  # {source}"));
+            }
+        }
+        formatter.write_str(&format!("{line}\n"))?;
+    }
+    Ok(())
+}
+
+fn cmd_config_get(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &ConfigGetArgs,
+) -> Result<(), CommandError> {
+    let repo_config_path = load_repo_config_if_any(ui, command)?;
+    let value = match ui.settings().config().get::<config::Value>(&args.name) {
+        Ok(value) => value,
+        Err(config::ConfigError::NotFound(_)) => {
+            if let Some(default) = &args.default {
+                ui.stdout_formatter().write_str(&format!("{default}\n"))?;
+                return Ok(());
+            }
+            return Err(user_error(format!(
+                "Config option \"{}\" is not set",
+                args.name
+            )));
+        }
+        Err(err) => return Err(err.into()),
+    };
+    if args.only_explicit
+        && !is_config_set_explicitly(
+            &load_config_layers(command, repo_config_path.as_deref())?,
+            &args.name,
+        )
+    {
+        if let Some(default) = &args.default {
+            ui.stdout_formatter().write_str(&format!("{default}\n"))?;
+            return Ok(());
+        }
+        return Err(user_error(format!(
+            "Config option \"{}\" only has its built-in default value",
+            args.name
+        )));
+    }
+    let format_value = |value: &config::Value| match &value.kind {
+        // Print strings without quotes so the output is easy to use in scripts
+        config::ValueKind::String(value) => value.clone(),
+        _ => crate::config::format_config_value(value),
+    };
+    let mut formatter = ui.stdout_formatter();
+    match &value.kind {
+        config::ValueKind::Array(values) if args.all => {
+            for value in values {
+                formatter.write_str(&format!("{}\n", format_value(value)))?;
+            }
+        }
+        _ => formatter.write_str(&format!("{}\n", format_value(&value)))?,
+    }
+    Ok(())
+}
+
+fn cmd_config_set(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &ConfigSetArgs,
+) -> Result<(), CommandError> {
+    let path = match args.scope {
+        ConfigScope::User => crate::config::user_config_path_for_writing()
+            .map_err(|err| CommandError::ConfigError(err.to_string()))?,
+        ConfigScope::Repo => command.load_workspace(ui)?.repo_path().join("config.toml"),
+    };
+    let old_value = crate::config::write_config_value(&path, &args.name, &args.value, args.dry_run)
+        .map_err(|err| CommandError::ConfigError(err.to_string()))?;
+    if args.dry_run {
+        let new_value = crate::config::parse_value_for_writing(&args.value);
+        match old_value {
+            Some(old_value) => ui.write(&format!(
+                "Would change {} in {} from {} to {}\n",
+                args.name,
+                path.display(),
+                old_value,
+                new_value.to_string().trim()
+            ))?,
+            None => ui.write(&format!(
+                "Would set {} in {} to {}\n",
+                args.name,
+                path.display(),
+                new_value.to_string().trim()
+            ))?,
+        }
+    }
+    Ok(())
+}
+
+fn cmd_config_reset(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &ConfigResetArgs,
+) -> Result<(), CommandError> {
+    let path = match args.scope {
+        ConfigScope::User => crate::config::user_config_path_for_writing()
+            .map_err(|err| CommandError::ConfigError(err.to_string()))?,
+        ConfigScope::Repo => command.load_workspace(ui)?.repo_path().join("config.toml"),
+    };
+    let removed = crate::config::remove_config_value(&path, &args.name, args.dry_run)
+        .map_err(|err| CommandError::ConfigError(err.to_string()))?;
+    if !removed {
+        return Err(user_error(format!(
+            "Config option \"{}\" is not set in {}",
+            args.name,
+            path.display()
+        )));
+    }
+    if args.dry_run {
+        ui.write(&format!(
+            "Would remove {} from {}\n",
+            args.name,
+            path.display()
+        ))?;
+        return Ok(());
+    }
+    // Another layer may still set the option, e.g. the user's config after
+    // resetting it in the repo's
+    let repo_config_path = load_repo_config_if_any(ui, command)?;
+    let layers = load_config_layers(command, repo_config_path.as_deref())?;
+    let value = layers
+        .merged()
+        .map_err(|err| CommandError::ConfigError(err.to_string()))?
+        .get::<config::Value>(&args.name)
+        .ok();
+    match (value, layers.find_source(&args.name)) {
+        (Some(value), Some(crate::config::ConfigSource::Default)) => ui.write(&format!(
+            "Reset {} to its default value {}\n",
+            args.name,
+            crate::config::format_config_value(&value)
+        ))?,
+        (Some(value), Some(source)) => ui.write(&format!(
+            "Reset {} to {} from {source}\n",
+            args.name,
+            crate::config::format_config_value(&value)
+        ))?,
+        _ => ui.write(&format!(
+            "Removed {}, which has no built-in default value\n",
+            args.name
+        ))?,
+    }
+    Ok(())
+}
+
+fn cmd_config_export(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &ConfigExportArgs,
+) -> Result<(), CommandError> {
+    load_repo_config_if_any(ui, command)?;
+    let toml = crate::config::export_config(ui.settings().config(), &args.redact)
+        .map_err(|err| CommandError::ConfigError(err.to_string()))?;
+    ui.request_pager();
+    ui.stdout_formatter().write_str(&toml)?;
+    Ok(())
+}
+
+fn cmd_config_where(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &ConfigWhereArgs,
+) -> Result<(), CommandError> {
+    let repo_config_path = load_repo_config_if_any(ui, command)?;
+    let layers = load_config_layers(command, repo_config_path.as_deref())?;
+    match layers.find_source(&args.name) {
+        Some(source) => {
+            ui.stdout_formatter().write_str(&format!("{source}\n"))?;
+            Ok(())
+        }
+        None => Err(user_error(format!(
+            "Config option \"{}\" is not set",
+            args.name
+        ))),
+    }
+}
+
+fn cmd_config_validate(
+    ui: &mut Ui,
+    _command: &CommandHelper,
+    args: &ConfigValidateArgs,
+) -> Result<(), CommandError> {
+    let path = ui.cwd().join(&args.path);
+    let unknown_keys = crate::config::validate_config_file(&path)
+        .map_err(|err| CommandError::ConfigError(err.to_string()))?;
+    for key in unknown_keys {
+        ui.write_warn(format!("warning: Unknown config key \"{key}\"\n"))?;
+    }
+    Ok(())
+}
+
 fn cmd_debug(
     ui: &mut Ui,
     command: &CommandHelper,
@@ -3647,22 +6229,30 @@ fn cmd_debug(
 
 // TODO: Move this somewhere where it can be reused by
 // `template_parser::SignatureTimestamp`.
-fn format_timestamp(timestamp: &Timestamp) -> String {
+fn timestamp_to_datetime(timestamp: &Timestamp) -> Option<DateTime<FixedOffset>> {
     let utc = match Utc.timestamp_opt(
         timestamp.timestamp.0.div_euclid(1000),
         (timestamp.timestamp.0.rem_euclid(1000)) as u32 * 1000000,
     ) {
         LocalResult::None => {
-            return "<out-of-range date>".to_string();
+            return None;
         }
         LocalResult::Single(x) => x,
         LocalResult::Ambiguous(y, _z) => y,
     };
-    let datetime = utc.with_timezone(
-        &FixedOffset::east_opt(timestamp.tz_offset * 60)
-            .unwrap_or_else(|| FixedOffset::east_opt(0).unwrap()),
-    );
-    datetime.format("%Y-%m-%d %H:%M:%S.%3f %:z").to_string()
+    Some(
+        utc.with_timezone(
+            &FixedOffset::east_opt(timestamp.tz_offset * 60)
+                .unwrap_or_else(|| FixedOffset::east_opt(0).unwrap()),
+        ),
+    )
+}
+
+fn format_timestamp(timestamp: &Timestamp) -> String {
+    match timestamp_to_datetime(timestamp) {
+        Some(datetime) => datetime.format("%Y-%m-%d %H:%M:%S.%3f %:z").to_string(),
+        None => "<out-of-range date>".to_string(),
+    }
 }
 
 fn cmd_op_log(
@@ -3709,7 +6299,8 @@ fn cmd_op_log(
     }
     let template = OpTemplate;
 
-    let mut graph = AsciiGraphDrawer::new(&mut formatter);
+    let mut graph =
+        AsciiGraphDrawer::new(&mut formatter).with_style(graph_style_setting(ui.settings())?);
     for op in topo_order_reverse(
         vec![head_op],
         Box::new(|op: &Operation| op.id().clone()),
@@ -4702,6 +7293,7 @@ pub fn run_command(
         Commands::Backout(sub_args) => cmd_backout(ui, command_helper, sub_args),
         Commands::Resolve(sub_args) => cmd_resolve(ui, command_helper, sub_args),
         Commands::Branch(sub_args) => cmd_branch(ui, command_helper, sub_args),
+        Commands::Config(sub_args) => cmd_config(ui, command_helper, sub_args),
         Commands::Undo(sub_args) => cmd_op_undo(ui, command_helper, sub_args),
         Commands::Operation(sub_args) => cmd_operation(ui, command_helper, sub_args),
         Commands::Workspace(sub_args) => cmd_workspace(ui, command_helper, sub_args),
@@ -4713,10 +7305,90 @@ pub fn run_command(
 
 #[cfg(test)]
 mod tests {
+    use jujutsu_lib::backend::MillisSinceEpoch;
+
     use super::*;
 
     #[test]
     fn verify_app() {
         default_app().debug_assert();
     }
+
+    #[test]
+    fn test_git_revision_to_revset() {
+        assert_eq!(git_revision_to_revset("HEAD").as_deref(), Some("@"));
+        assert_eq!(git_revision_to_revset("HEAD^").as_deref(), Some("@-"));
+        assert_eq!(git_revision_to_revset("HEAD~1").as_deref(), Some("@-"));
+        assert_eq!(git_revision_to_revset("HEAD~").as_deref(), Some("@-"));
+        assert_eq!(git_revision_to_revset("HEAD^1~2").as_deref(), Some("@---"));
+        assert_eq!(git_revision_to_revset("@^").as_deref(), Some("@-"));
+        assert_eq!(git_revision_to_revset("@~2").as_deref(), Some("@--"));
+        assert_eq!(git_revision_to_revset("HEAD~4").as_deref(), Some("@~4"));
+        assert_eq!(git_revision_to_revset("HEAD~2~3").as_deref(), Some("@~5"));
+        // Too many generations to write as a revset
+        assert_eq!(git_revision_to_revset("HEAD~99999999999"), None);
+        assert_eq!(git_revision_to_revset("HEAD~4294967295~1"), None);
+        // Not Git revisions, or nothing to translate
+        assert_eq!(git_revision_to_revset("@"), None);
+        assert_eq!(git_revision_to_revset("HEAD^2"), None);
+        assert_eq!(git_revision_to_revset("HEADS"), None);
+        assert_eq!(git_revision_to_revset("@-"), None);
+        assert_eq!(git_revision_to_revset("main~1"), None);
+    }
+
+    #[test]
+    fn test_author_column_text() {
+        let signature = Signature {
+            name: "Some One".to_string(),
+            email: "some.one@example.com".to_string(),
+            timestamp: Timestamp {
+                timestamp: MillisSinceEpoch(0),
+                tz_offset: 0,
+            },
+        };
+        assert_eq!(
+            author_column_text(&signature, 32),
+            "Some One <some.one@example.com> "
+        );
+        assert_eq!(
+            author_column_text(&signature, 31),
+            "Some One <some.one@example.com>"
+        );
+        assert_eq!(author_column_text(&signature, 10), "Some One …");
+        assert_eq!(author_column_text(&signature, 1), "…");
+        assert_eq!(author_column_text(&signature, 0), "");
+    }
+
+    #[test]
+    fn test_author_pattern_regex() {
+        let regex = author_pattern_regex("Some.One").unwrap();
+        assert!(regex.is_match("some.one@example.com"));
+        assert!(regex.is_match("SOME.ONE"));
+        // The "." isn't a wildcard
+        assert!(!regex.is_match("Some One"));
+
+        let regex = author_pattern_regex("regex:^Some.One$").unwrap();
+        assert!(regex.is_match("Some One"));
+        assert!(!regex.is_match("some one"));
+        assert!(!regex.is_match("Some One Else"));
+
+        assert!(author_pattern_regex("regex:(").is_err());
+    }
+
+    #[test]
+    fn test_parse_trailers() {
+        assert!(parse_trailers("").is_empty());
+        // A single paragraph is just the subject
+        assert!(parse_trailers("Fixes: nothing\n").is_empty());
+        assert_eq!(
+            parse_trailers(
+                "subject\n\nbody: not a trailer\n\nSigned-off-by: A <a@example.com>\nnot a \
+                 trailer\nBad Key: value\nEmpty:\nReviewed-by: B <b@example.com>\n"
+            ),
+            vec![
+                ("Signed-off-by", "A <a@example.com>"),
+                ("Reviewed-by", "B <b@example.com>"),
+            ]
+        );
+    }
 }
@@ -13,10 +13,11 @@
 // limitations under the License.
 
 use std::borrow::Cow;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::{env, fmt};
 
+use itertools::Itertools;
 use jujutsu_lib::settings::UserSettings;
 use thiserror::Error;
 
@@ -26,13 +27,25 @@ pub enum ConfigError {
     ConfigReadError(#[from] config::ConfigError),
     #[error("Both {0} and {1} exist. Please consolidate your configs in one of them.")]
     AmbiguousSource(PathBuf, PathBuf),
+    #[error("Cannot determine the user config directory")]
+    NoUserConfigDir,
+    #[error("Failed to update config file {0}: {1}")]
+    ConfigWriteError(PathBuf, String),
+    #[error("Unknown profile \"{name}\" ({available})")]
+    UnknownProfile { name: String, available: String },
+    #[error("Config option \"{key}\" refers to undefined environment variable \"{name}\"")]
+    UndefinedEnvVar { key: String, name: String },
+    #[error("Config option \"{key}\" has a \"${{\" without a closing \"}}\"")]
+    UnterminatedEnvVar { key: String },
 }
 
-fn config_path() -> Result<Option<PathBuf>, ConfigError> {
-    if let Ok(config_path) = env::var("JJ_CONFIG") {
-        // TODO: We should probably support colon-separated (std::env::split_paths)
-        // paths here
-        Ok(Some(PathBuf::from(config_path)))
+/// Returns the user's config files and directories, in increasing order of
+/// precedence. Paths listed in `JJ_CONFIG` may not exist.
+fn config_paths() -> Result<Vec<PathBuf>, ConfigError> {
+    if let Some(config_paths) = env::var_os("JJ_CONFIG") {
+        Ok(env::split_paths(&config_paths)
+            .filter(|path| !path.as_os_str().is_empty())
+            .collect())
     } else {
         // TODO: Should we drop the final `/config.toml` and read all files in the
         // directory?
@@ -47,29 +60,36 @@ fn config_path() -> Result<Option<PathBuf>, ConfigError> {
                 xdg_config_path.clone(),
                 home_config_path.clone(),
             )),
-            _ => Ok(platform_specific_config_path.or(home_config_path)),
+            _ => Ok(platform_specific_config_path
+                .or(home_config_path)
+                .into_iter()
+                .collect()),
         }
     }
 }
 
-/// Environment variables that should be overridden by config values
-fn env_base() -> config::Config {
-    let mut builder = config::Config::builder();
+/// Environment variables that should be overridden by config values, as
+/// (variable, key, value) triples
+fn env_base_values() -> Vec<(&'static str, &'static str, String)> {
+    let mut values = vec![];
     if env::var("NO_COLOR").is_ok() {
         // "User-level configuration files and per-instance command-line arguments
         // should override $NO_COLOR." https://no-color.org/
-        builder = builder.set_override("ui.color", "never").unwrap();
+        values.push(("NO_COLOR", "ui.color", "never".to_string()));
     }
     if let Ok(value) = env::var("PAGER") {
-        builder = builder.set_override("ui.pager", value).unwrap();
+        values.push(("PAGER", "ui.pager", value));
     }
     if let Ok(value) = env::var("VISUAL") {
-        builder = builder.set_override("ui.editor", value).unwrap();
+        values.push(("VISUAL", "ui.editor", value));
     } else if let Ok(value) = env::var("EDITOR") {
-        builder = builder.set_override("ui.editor", value).unwrap();
+        values.push(("EDITOR", "ui.editor", value));
     }
+    values
+}
 
-    builder.build().unwrap()
+fn env_base() -> config::Config {
+    config_from_env_values(env_base_values())
 }
 
 fn default_mergetool_config() -> config::Config {
@@ -94,66 +114,727 @@ fn default_mergetool_config() -> config::Config {
         .unwrap()
 }
 
-/// Environment variables that override config values
-fn env_overrides() -> config::Config {
+/// Environment variables that override config values, including the repo's
+/// config, and the keys they set
+const ENV_OVERRIDE_VARS: &[(&str, &str)] = &[
+    ("JJ_USER", "user.name"),
+    ("JJ_EMAIL", "user.email"),
+    ("JJ_TIMESTAMP", "user.timestamp"),
+    ("JJ_OP_TIMESTAMP", "operation.timestamp"),
+    ("JJ_OP_HOSTNAME", "operation.hostname"),
+    ("JJ_OP_USERNAME", "operation.username"),
+    ("JJ_LOG_DEFAULT_ARGS", "ui.log-default-args"),
+    ("JJ_DEFAULT_REVSET", "ui.default-revset"),
+    ("JJ_EDITOR", "ui.editor"),
+];
+
+fn env_override_values() -> Vec<(&'static str, &'static str, String)> {
+    ENV_OVERRIDE_VARS
+        .iter()
+        .filter_map(|(var, key)| env::var(var).ok().map(|value| (*var, *key, value)))
+        .collect()
+}
+
+/// Environment variables that override config values, including the repo's
+/// config
+pub fn env_overrides() -> config::Config {
+    config_from_env_values(env_override_values())
+}
+
+fn config_from_env_values(values: Vec<(&'static str, &'static str, String)>) -> config::Config {
     let mut builder = config::Config::builder();
-    if let Ok(value) = env::var("JJ_USER") {
-        builder = builder.set_override("user.name", value).unwrap();
-    }
-    if let Ok(value) = env::var("JJ_EMAIL") {
-        builder = builder.set_override("user.email", value).unwrap();
-    }
-    if let Ok(value) = env::var("JJ_TIMESTAMP") {
-        builder = builder.set_override("user.timestamp", value).unwrap();
+    for (_, key, value) in values {
+        builder = builder.set_override(key, value).unwrap();
     }
-    if let Ok(value) = env::var("JJ_OP_TIMESTAMP") {
-        builder = builder.set_override("operation.timestamp", value).unwrap();
-    }
-    if let Ok(value) = env::var("JJ_OP_HOSTNAME") {
-        builder = builder.set_override("operation.hostname", value).unwrap();
-    }
-    if let Ok(value) = env::var("JJ_OP_USERNAME") {
-        builder = builder.set_override("operation.username", value).unwrap();
+    builder.build().unwrap()
+}
+
+/// Adds the `*.toml` files in `dir` and its subdirectories to `files`.
+/// Unreadable directories are skipped.
+fn collect_toml_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    if let Ok(read_dir) = dir.read_dir() {
+        for dir_entry in read_dir.flatten() {
+            let path = dir_entry.path();
+            if path.is_dir() {
+                collect_toml_files(&path, files);
+            } else if path.is_file() && path.extension().map_or(false, |ext| ext == "toml") {
+                files.push(path);
+            }
+        }
     }
-    if let Ok(value) = env::var("JJ_EDITOR") {
-        builder = builder.set_override("ui.editor", value).unwrap();
+}
+
+/// Returns the user's config files, in increasing order of precedence. The
+/// `*.toml` files in a config directory are included in sorted order.
+fn user_config_files() -> Result<Vec<PathBuf>, ConfigError> {
+    let mut files = vec![];
+    for config_path in config_paths()? {
+        if config_path.is_dir() {
+            let mut dir_files = vec![];
+            collect_toml_files(&config_path, &mut dir_files);
+            dir_files.sort();
+            files.extend(dir_files);
+        } else {
+            files.push(config_path);
+        }
     }
-    builder.build().unwrap()
+    Ok(files)
 }
 
-pub fn read_config() -> Result<UserSettings, ConfigError> {
+fn config_file_source(path: &Path) -> impl config::Source + Send + Sync + 'static {
+    // TODO: Accept other formats?
+    config::File::from(path)
+        .required(false)
+        .format(config::FileFormat::Toml)
+}
+
+/// Reads the built-in defaults, the environment variables that config files
+/// override, and the user's config files.
+fn read_base_config() -> Result<config::Config, ConfigError> {
     let mut config_builder = config::Config::builder()
         .add_source(default_mergetool_config())
         .add_source(env_base());
+    for file in user_config_files()? {
+        config_builder = config_builder.add_source(config_file_source(&file));
+    }
+    Ok(config_builder.build()?)
+}
 
-    if let Some(config_path) = config_path()? {
-        let mut files = vec![];
-        if config_path.is_dir() {
-            if let Ok(read_dir) = config_path.read_dir() {
-                // TODO: Walk the directory recursively?
-                for dir_entry in read_dir.flatten() {
-                    let path = dir_entry.path();
-                    if path.is_file() {
-                        files.push(path);
-                    }
+/// Reads the user's config, using the profile named by the `JJ_PROFILE`
+/// environment variable, if any.
+pub fn read_config() -> Result<UserSettings, ConfigError> {
+    read_config_with_profile(env_profile().as_deref())
+}
+
+/// Returns the profile named by the `JJ_PROFILE` environment variable, if any.
+pub fn env_profile() -> Option<String> {
+    env::var("JJ_PROFILE").ok().filter(|name| !name.is_empty())
+}
+
+/// Reads the user's config. If `profile` is set, the `[profiles.<profile>]`
+/// table is layered over the config files, but below settings from
+/// environment variables like `JJ_EMAIL`.
+pub fn read_config_with_profile(profile: Option<&str>) -> Result<UserSettings, ConfigError> {
+    let base_config = read_base_config()?;
+    let mut config_builder = config::Config::builder().add_source(base_config.clone());
+    if let Some(name) = profile {
+        config_builder = config_builder.add_source(profile_config(&base_config, name)?);
+    }
+    let config = config_builder.add_source(env_overrides()).build()?;
+    let config = interpolate_env_vars(config, |name| env::var(name).ok())?;
+    Ok(UserSettings::from_config(config))
+}
+
+/// Replaces `${VAR}` in the string values of `config` (including the ones in
+/// arrays) with the value of the environment variable `VAR`, as returned by
+/// `lookup`. `$$` is replaced with a single `$`.
+fn interpolate_env_vars(
+    config: config::Config,
+    lookup: impl Fn(&str) -> Option<String>,
+) -> Result<config::Config, ConfigError> {
+    let mut table = config.collect()?;
+    for (name, value) in table.iter_mut() {
+        if let Some(new_value) = interpolate_env_vars_in_value(name, value, &lookup)? {
+            *value = new_value;
+        }
+    }
+    // The values are replaced in the table rather than set by their dotted
+    // names, which can't express keys like `revset-aliases.'user(x)'`
+    Ok(config::Config::builder()
+        .add_source(TableSource(table))
+        .build()?)
+}
+
+/// Returns the new value if `value` (named `key`) is or contains a string that
+/// changes.
+fn interpolate_env_vars_in_value(
+    key: &str,
+    value: &config::Value,
+    lookup: &impl Fn(&str) -> Option<String>,
+) -> Result<Option<config::Value>, ConfigError> {
+    let new_kind = match &value.kind {
+        config::ValueKind::String(text) if text.contains('$') => {
+            let new_text = interpolate_env_vars_in_str(key, text, lookup)?;
+            (&new_text != text).then(|| config::ValueKind::String(new_text))
+        }
+        config::ValueKind::Array(values) => {
+            let new_values: Vec<_> = values
+                .iter()
+                .map(|value| interpolate_env_vars_in_value(key, value, lookup))
+                .try_collect()?;
+            (!new_values.iter().all(Option::is_none)).then(|| {
+                let values = values
+                    .iter()
+                    .zip(new_values)
+                    .map(|(value, new_value)| new_value.unwrap_or_else(|| value.clone()))
+                    .collect_vec();
+                config::ValueKind::Array(values)
+            })
+        }
+        config::ValueKind::Table(table) => {
+            let mut new_table = table.clone();
+            let mut changed = false;
+            for (name, value) in new_table.iter_mut() {
+                let key = format!("{key}.{name}");
+                if let Some(new_value) = interpolate_env_vars_in_value(&key, value, lookup)? {
+                    *value = new_value;
+                    changed = true;
                 }
             }
-            files.sort();
+            changed.then(|| config::ValueKind::Table(new_table))
+        }
+        _ => None,
+    };
+    Ok(new_kind.map(|kind| config::Value::new(None, kind)))
+}
+
+/// A config source with the values of an already collected table.
+#[derive(Clone, Debug)]
+struct TableSource(config::Map<String, config::Value>);
+
+impl config::Source for TableSource {
+    fn clone_into_box(&self) -> Box<dyn config::Source + Send + Sync> {
+        Box::new(self.clone())
+    }
+
+    fn collect(&self) -> Result<config::Map<String, config::Value>, config::ConfigError> {
+        Ok(self.0.clone())
+    }
+}
+
+fn interpolate_env_vars_in_str(
+    key: &str,
+    text: &str,
+    lookup: impl Fn(&str) -> Option<String>,
+) -> Result<String, ConfigError> {
+    let mut result = String::new();
+    let mut rest = text;
+    while let Some(dollar) = rest.find('$') {
+        result.push_str(&rest[..dollar]);
+        rest = &rest[dollar..];
+        if let Some(after) = rest.strip_prefix("$$") {
+            result.push('$');
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix("${") {
+            let end = after
+                .find('}')
+                .ok_or_else(|| ConfigError::UnterminatedEnvVar {
+                    key: key.to_string(),
+                })?;
+            let name = &after[..end];
+            let value = lookup(name).ok_or_else(|| ConfigError::UndefinedEnvVar {
+                key: key.to_string(),
+                name: name.to_string(),
+            })?;
+            result.push_str(&value);
+            rest = &after[end + 1..];
         } else {
-            files.push(config_path);
+            // A lone `$`, like in the `$left` arguments of merge tools
+            result.push('$');
+            rest = &rest[1..];
         }
-        for file in files {
-            // TODO: Accept other formats and/or accept only certain file extensions?
-            config_builder = config_builder.add_source(
-                config::File::from(file)
-                    .required(false)
-                    .format(config::FileFormat::Toml),
-            );
+    }
+    result.push_str(rest);
+    Ok(result)
+}
+
+/// Returns the settings in the `[profiles.<name>]` table of `config`, as if
+/// they were set at the top level.
+fn profile_config(config: &config::Config, name: &str) -> Result<config::Config, ConfigError> {
+    let profiles = match config.get_table("profiles") {
+        Ok(profiles) => profiles,
+        Err(config::ConfigError::NotFound(_)) => Default::default(),
+        Err(err) => return Err(err.into()),
+    };
+    let profile = match profiles.get(name) {
+        Some(profile) => profile.clone().into_table()?,
+        None => {
+            let available = if profiles.is_empty() {
+                "no profiles are defined".to_string()
+            } else {
+                format!(
+                    "available profiles: {}",
+                    profiles.keys().sorted().join(", ")
+                )
+            };
+            return Err(ConfigError::UnknownProfile {
+                name: name.to_string(),
+                available,
+            });
         }
     };
+    let mut values = vec![];
+    collect_config_values("", profile, &mut values);
+    let mut builder = config::Config::builder();
+    for (key, value) in values {
+        builder = builder.set_override(key, value)?;
+    }
+    Ok(builder.build()?)
+}
 
-    let config = config_builder.add_source(env_overrides()).build()?;
-    Ok(UserSettings::from_config(config))
+/// Where the effective value of a config option is set.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ConfigSource {
+    /// The `--color` argument, which sets `ui.color`
+    ColorArg,
+    /// A `--config-toml` argument
+    CommandLine,
+    /// An environment variable
+    Env(&'static str),
+    /// A config file (the user's or the repo's)
+    File(PathBuf),
+    /// The `[profiles.<name>]` table of the user's config
+    Profile(String),
+    /// jj's built-in defaults
+    Default,
+}
+
+impl fmt::Display for ConfigSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigSource::ColorArg => write!(f, "--color"),
+            ConfigSource::CommandLine => write!(f, "--config-toml"),
+            ConfigSource::Env(var) => write!(f, "${var}"),
+            ConfigSource::File(path) => write!(f, "{}", path.display()),
+            ConfigSource::Profile(name) => write!(f, "profile {name}"),
+            ConfigSource::Default => write!(f, "built-in default"),
+        }
+    }
+}
+
+/// The layers of the config, each loaded on its own so the one that sets a key
+/// can be found, from the highest precedence to the lowest.
+pub struct ConfigLayers {
+    layers: Vec<(ConfigSource, config::Config)>,
+}
+
+impl ConfigLayers {
+    /// Loads the layers the same way the config is layered when running a
+    /// command: the built-in defaults, environment variables like `$EDITOR`,
+    /// the user's config files, the `profile`, the repo's config file at
+    /// `repo_config_path`, environment variables like `$JJ_EDITOR`, and
+    /// `toml_strs` from `--config-toml`.
+    pub fn load(
+        profile: Option<&str>,
+        repo_config_path: Option<&Path>,
+        toml_strs: &[String],
+    ) -> Result<Self, ConfigError> {
+        fn env_layers(
+            values: Vec<(&'static str, &'static str, String)>,
+        ) -> impl Iterator<Item = (ConfigSource, config::Config)> {
+            values.into_iter().rev().map(|value| {
+                (
+                    ConfigSource::Env(value.0),
+                    config_from_env_values(vec![value]),
+                )
+            })
+        }
+        fn file_layer(path: &Path) -> Result<(ConfigSource, config::Config), ConfigError> {
+            let config = config::Config::builder()
+                .add_source(config_file_source(path))
+                .build()?;
+            Ok((ConfigSource::File(path.to_path_buf()), config))
+        }
+
+        let mut layers = vec![];
+        let mut command_line_builder = config::Config::builder();
+        for toml_str in toml_strs {
+            command_line_builder = command_line_builder
+                .add_source(config::File::from_str(toml_str, config::FileFormat::Toml));
+        }
+        layers.push((ConfigSource::CommandLine, command_line_builder.build()?));
+        layers.extend(env_layers(env_override_values()));
+        if let Some(path) = repo_config_path {
+            layers.push(file_layer(path)?);
+        }
+        if let Some(name) = profile {
+            let profile_config = profile_config(&read_base_config()?, name)?;
+            layers.push((ConfigSource::Profile(name.to_string()), profile_config));
+        }
+        for file in user_config_files()?.iter().rev() {
+            layers.push(file_layer(file)?);
+        }
+        layers.extend(env_layers(env_base_values()));
+        layers.push((ConfigSource::Default, default_mergetool_config()));
+        Ok(ConfigLayers { layers })
+    }
+
+    /// Adds a layer from `toml_str` that takes precedence over all the others.
+    pub fn push_override(
+        &mut self,
+        source: ConfigSource,
+        toml_str: &str,
+    ) -> Result<(), ConfigError> {
+        let config = config::Config::builder()
+            .add_source(config::File::from_str(toml_str, config::FileFormat::Toml))
+            .build()?;
+        self.layers.insert(0, (source, config));
+        Ok(())
+    }
+
+    /// Merges the layers into the config that's in effect.
+    pub fn merged(&self) -> Result<config::Config, ConfigError> {
+        let mut builder = config::Config::builder();
+        for (_, config) in self.layers.iter().rev() {
+            builder = builder.add_source(config.clone());
+        }
+        interpolate_env_vars(builder.build()?, |name| env::var(name).ok())
+    }
+
+    /// Finds the layer with the highest precedence that sets `key`.
+    pub fn find_source(&self, key: &str) -> Option<&ConfigSource> {
+        self.layers
+            .iter()
+            .find(|(_, config)| config.get::<config::Value>(key).is_ok())
+            .map(|(source, _)| source)
+    }
+}
+
+/// Returns the file that user-level settings should be written to.
+///
+/// If there are several user config paths, the last one (which takes
+/// precedence) is used. If it's a directory, the settings go in a
+/// `config.toml` file in it.
+pub fn user_config_path_for_writing() -> Result<PathBuf, ConfigError> {
+    match config_paths()?.pop() {
+        Some(path) if path.is_dir() => Ok(path.join("config.toml")),
+        Some(path) => Ok(path),
+        None => dirs::config_dir()
+            .map(|config_dir| config_dir.join("jj").join("config.toml"))
+            .ok_or(ConfigError::NoUserConfigDir),
+    }
+}
+
+/// Parses `value` as a TOML value if possible (e.g. `true` or `["a", "b"]`),
+/// and uses it as a string otherwise, like `jj config set` does.
+pub fn parse_value_for_writing(value: &str) -> toml_edit::Value {
+    value
+        .parse::<toml_edit::Value>()
+        .unwrap_or_else(|_| toml_edit::Value::from(value))
+}
+
+/// Sets `key` to `value` in the TOML file at `path`, creating the file if it
+/// doesn't exist. `value` is parsed as a TOML value if possible (e.g. `true`
+/// or `["a", "b"]`), and used as a string otherwise.
+///
+/// Only the value of `key` changes; comments, key order, and formatting in the
+/// rest of the file are preserved. With `dry_run`, the file is left untouched.
+///
+/// Returns the previous value of `key` in the file, formatted as TOML.
+pub fn write_config_value(
+    path: &Path,
+    key: &str,
+    value: &str,
+    dry_run: bool,
+) -> Result<Option<String>, ConfigError> {
+    let write_error = |message: String| ConfigError::ConfigWriteError(path.to_owned(), message);
+    // Edit the document in place so comments and formatting survive
+    let mut doc = if path.exists() {
+        let text = std::fs::read_to_string(path).map_err(|err| write_error(err.to_string()))?;
+        text.parse::<toml_edit::Document>()
+            .map_err(|err| write_error(err.to_string()))?
+    } else {
+        toml_edit::Document::new()
+    };
+    let mut value = parse_value_for_writing(value);
+
+    let (table_keys, leaf_key) = match key.rsplit_once('.') {
+        Some((table_keys, leaf_key)) => (table_keys.split('.').collect_vec(), leaf_key),
+        None => (vec![], key),
+    };
+    let mut table: &mut dyn toml_edit::TableLike = doc.as_table_mut();
+    for (i, table_key) in table_keys.iter().enumerate() {
+        if !table.contains_key(table_key) {
+            let mut new_table = toml_edit::Table::new();
+            // Don't write a header for a table that only contains other tables
+            new_table.set_implicit(true);
+            table.insert(table_key, toml_edit::Item::Table(new_table));
+        }
+        let item = table.get_mut(table_key).unwrap();
+        table = item.as_table_like_mut().ok_or_else(|| {
+            write_error(format!(
+                r#""{}" is not a table"#,
+                table_keys[..=i].join(".")
+            ))
+        })?;
+    }
+    let old_value = match table.get_mut(leaf_key) {
+        Some(item) => {
+            let old_value = match item.as_value() {
+                Some(old_value) => {
+                    // Replace just the value, keeping the comments around it
+                    *value.decor_mut() = old_value.decor().clone();
+                    let mut old_value = old_value.clone();
+                    *old_value.decor_mut() = toml_edit::Decor::default();
+                    old_value.to_string()
+                }
+                None => item.to_string().trim().to_string(),
+            };
+            *item = toml_edit::Item::Value(value);
+            Some(old_value)
+        }
+        None => {
+            table.insert(leaf_key, toml_edit::Item::Value(value));
+            None
+        }
+    };
+
+    if !dry_run {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|err| write_error(err.to_string()))?;
+        }
+        std::fs::write(path, doc.to_string()).map_err(|err| write_error(err.to_string()))?;
+    }
+    Ok(old_value)
+}
+
+/// Removes `key` from the TOML file at `path`, so that a value from a
+/// lower-priority config (or the built-in default) applies again. Tables left
+/// empty by the removal are removed too. Returns false if the file doesn't set
+/// `key`.
+///
+/// Like `write_config_value()`, this preserves comments and formatting in the
+/// rest of the file, and leaves the file untouched with `dry_run`.
+pub fn remove_config_value(path: &Path, key: &str, dry_run: bool) -> Result<bool, ConfigError> {
+    let write_error = |message: String| ConfigError::ConfigWriteError(path.to_owned(), message);
+    if !path.exists() {
+        return Ok(false);
+    }
+    let text = std::fs::read_to_string(path).map_err(|err| write_error(err.to_string()))?;
+    let mut doc = text
+        .parse::<toml_edit::Document>()
+        .map_err(|err| write_error(err.to_string()))?;
+
+    fn remove_from_table(table: &mut dyn toml_edit::TableLike, keys: &[&str]) -> bool {
+        match keys {
+            [] => false,
+            [leaf_key] => table.remove(leaf_key).is_some(),
+            [table_key, rest @ ..] => {
+                let removed = match table
+                    .get_mut(table_key)
+                    .and_then(|item| item.as_table_like_mut())
+                {
+                    Some(child) => remove_from_table(child, rest),
+                    None => false,
+                };
+                let is_empty = table
+                    .get(table_key)
+                    .and_then(|item| item.as_table_like())
+                    .map_or(false, |child| child.is_empty());
+                if removed && is_empty {
+                    table.remove(table_key);
+                }
+                removed
+            }
+        }
+    }
+    if !remove_from_table(doc.as_table_mut(), &key.split('.').collect_vec()) {
+        return Ok(false);
+    }
+    if !dry_run {
+        std::fs::write(path, doc.to_string()).map_err(|err| write_error(err.to_string()))?;
+    }
+    Ok(true)
+}
+
+/// Config keys that jj knows about. A key ending in `*` matches every key in
+/// that table, for tables whose keys are chosen by the user.
+const KNOWN_CONFIG_KEYS: &[&str] = &[
+    "alias.*",
+    "colors.*",
+    "diff.external-command",
+    "diff.format",
+    "merge-tools.*",
+    "operation.hostname",
+    "operation.timestamp",
+    "operation.username",
+    "profiles.*",
+    "push.branch-prefix",
+    "revset-aliases.*",
+    "signing.verify-command",
+    "template.commit_summary",
+    "template.log.graph",
+    "ui.allow-init-native",
+    "ui.color",
+    "ui.default-revset",
+    "ui.diff-editor",
+    "ui.diff.word-wrap",
+    "ui.dim-root",
+    "ui.editor",
+    "ui.graph.commit-char",
+    "ui.graph.commit-symbol",
+    "ui.graph.elided-char",
+    "ui.graph.elided-symbol",
+    "ui.graph.gutter-width",
+    "ui.graph.node-template",
+    "ui.graph.style",
+    "ui.graph.working-copy-char",
+    "ui.graph.working-copy-symbol",
+    "ui.log-color-working-copy",
+    "ui.log-default-args",
+    "ui.log-hide-empty-working-copy",
+    "ui.merge-editor",
+    "ui.pager",
+    "ui.print-max-bytes",
+    "ui.progress-indicator",
+    "ui.relative-timestamps",
+    "ui.warn-path-revset",
+    "user.email",
+    "user.name",
+    "user.timestamp",
+];
+
+fn is_known_config_key(key: &str) -> bool {
+    KNOWN_CONFIG_KEYS
+        .iter()
+        .any(|known_key| match known_key.strip_suffix('*') {
+            Some(prefix) => key.starts_with(prefix),
+            None => key == *known_key,
+        })
+}
+
+/// Compiles a glob pattern matching whole config keys. `*` matches any
+/// sequence of characters (including `.`), and `?` matches any single
+/// character.
+pub fn compile_config_key_glob(glob: &str) -> regex::Regex {
+    let mut pattern = "^".to_string();
+    for c in glob.chars() {
+        match c {
+            '*' => pattern.push_str(".*"),
+            '?' => pattern.push('.'),
+            c => pattern.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    pattern.push('$');
+    regex::Regex::new(&pattern).unwrap()
+}
+
+fn collect_config_values(
+    prefix: &str,
+    table: impl IntoIterator<Item = (String, config::Value)>,
+    values: &mut Vec<(String, config::Value)>,
+) {
+    for (name, value) in table {
+        let key = if prefix.is_empty() {
+            name
+        } else {
+            format!("{prefix}.{name}")
+        };
+        match value.kind {
+            config::ValueKind::Table(table) => collect_config_values(&key, table, values),
+            _ => values.push((key, value)),
+        }
+    }
+}
+
+/// Returns the leaf values in `config` keyed by their dotted names, sorted by
+/// key.
+pub fn flatten_config(
+    config: &config::Config,
+) -> Result<Vec<(String, config::Value)>, ConfigError> {
+    let mut values = vec![];
+    collect_config_values("", config.clone().collect()?, &mut values);
+    values.sort_by(|(key1, _), (key2, _)| key1.cmp(key2));
+    Ok(values)
+}
+
+/// Serializes `config` as a single TOML document. The values of the keys in
+/// `redacted` that are set are replaced with `"<redacted>"`.
+pub fn export_config(config: &config::Config, redacted: &[String]) -> Result<String, ConfigError> {
+    let mut builder = config::Config::builder().add_source(config.clone());
+    for key in redacted {
+        if config.get::<config::Value>(key).is_ok() {
+            builder = builder.set_override(key.as_str(), "<redacted>")?;
+        }
+    }
+    let table = builder.build()?.collect()?;
+    Ok(toml_edit::Document::from(config_table_to_toml(table)).to_string())
+}
+
+fn config_table_to_toml(table: config::Map<String, config::Value>) -> toml_edit::Table {
+    let mut result = toml_edit::Table::new();
+    // Only write a header for tables that have values of their own
+    result.set_implicit(true);
+    let mut entries = table.into_iter().collect_vec();
+    entries.sort_by(|(key1, _), (key2, _)| key1.cmp(key2));
+    for (key, value) in entries {
+        let item = match value.kind {
+            config::ValueKind::Table(table) => toml_edit::Item::Table(config_table_to_toml(table)),
+            _ => toml_edit::value(config_value_to_toml(value)),
+        };
+        result.insert(&key, item);
+    }
+    result
+}
+
+fn config_value_to_toml(value: config::Value) -> toml_edit::Value {
+    match value.kind {
+        config::ValueKind::Nil => "".into(),
+        config::ValueKind::Boolean(value) => value.into(),
+        config::ValueKind::I64(value) => value.into(),
+        config::ValueKind::I128(value) => {
+            i64::try_from(value).map_or_else(|_| value.to_string().into(), Into::into)
+        }
+        config::ValueKind::U64(value) => {
+            i64::try_from(value).map_or_else(|_| value.to_string().into(), Into::into)
+        }
+        config::ValueKind::U128(value) => {
+            i64::try_from(value).map_or_else(|_| value.to_string().into(), Into::into)
+        }
+        config::ValueKind::Float(value) => value.into(),
+        config::ValueKind::String(value) => value.into(),
+        config::ValueKind::Array(values) => {
+            toml_edit::Value::Array(values.into_iter().map(config_value_to_toml).collect())
+        }
+        config::ValueKind::Table(table) => {
+            let mut entries = table.into_iter().collect_vec();
+            entries.sort_by(|(key1, _), (key2, _)| key1.cmp(key2));
+            toml_edit::Value::InlineTable(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key, config_value_to_toml(value)))
+                    .collect(),
+            )
+        }
+    }
+}
+
+/// Returns true if `key` is set to `value` in jj's built-in defaults.
+///
+/// A key the user has explicitly set to the same value as the default can't
+/// be told apart from one they didn't set, so it's considered a default too.
+pub fn is_default_config_value(key: &str, value: &config::Value) -> bool {
+    match default_mergetool_config().get::<config::Value>(key) {
+        Ok(default_value) => default_value.kind == value.kind,
+        Err(_) => false,
+    }
+}
+
+/// Formats a config value the way it would be written in a TOML file.
+pub fn format_config_value(value: &config::Value) -> String {
+    config_value_to_toml(value.clone())
+        .to_string()
+        .trim()
+        .to_string()
+}
+
+/// Parses the config file at `path` the same way `read_config()` does, but
+/// without merging it into the user's settings.
+///
+/// Returns the keys set in the file that jj doesn't know about.
+pub fn validate_config_file(path: &Path) -> Result<Vec<String>, ConfigError> {
+    let config = config::Config::builder()
+        .add_source(
+            config::File::from(path)
+                .required(true)
+                .format(config::FileFormat::Toml),
+        )
+        .build()?;
+    Ok(flatten_config(&config)?
+        .into_iter()
+        .map(|(key, _)| key)
+        .filter(|key| !is_known_config_key(key))
+        .collect())
 }
 
 /// Command name and arguments specified by config.
@@ -170,8 +851,15 @@ impl FullCommandArgs {
     /// The list is not empty, but each element may be an empty string.
     pub fn args(&self) -> Cow<[String]> {
         match self {
-            // Handle things like `EDITOR=emacs -nw` (TODO: parse shell escapes)
-            FullCommandArgs::String(s) => s.split(' ').map(|s| s.to_owned()).collect(),
+            // Handle things like `EDITOR=emacs -nw`
+            FullCommandArgs::String(s) => {
+                let words = split_shell_words(s);
+                if words.is_empty() {
+                    Cow::Owned(vec!["".to_owned()])
+                } else {
+                    Cow::Owned(words)
+                }
+            }
             FullCommandArgs::Vec(a) => Cow::Borrowed(&a.0),
         }
     }
@@ -195,12 +883,88 @@ impl fmt::Display for FullCommandArgs {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             FullCommandArgs::String(s) => write!(f, "{s}"),
-            // TODO: format with shell escapes
-            FullCommandArgs::Vec(a) => write!(f, "{}", a.0.join(" ")),
+            FullCommandArgs::Vec(a) => {
+                write!(f, "{}", a.0.iter().map(|arg| shell_quote(arg)).join(" "))
+            }
         }
     }
 }
 
+/// Splits `s` into words the way a POSIX shell would, without expanding
+/// anything: words are separated by whitespace, single quotes preserve
+/// everything up to the next single quote, and a backslash escapes the next
+/// character. Unlike in a shell, a backslash outside quotes only escapes
+/// whitespace, quotes, and backslashes, so Windows paths work unquoted. Inside
+/// double quotes, it escapes `"`, `\`, `$`, `` ` ``, and newlines. An
+/// unterminated quote extends to the end of `s`.
+pub fn split_shell_words(s: &str) -> Vec<String> {
+    let mut words = vec![];
+    let mut word: Option<String> = None;
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {
+                words.extend(word.take());
+            }
+            '\'' => {
+                let word = word.get_or_insert_with(String::new);
+                for c in chars.by_ref() {
+                    if c == '\'' {
+                        break;
+                    }
+                    word.push(c);
+                }
+            }
+            '"' => {
+                let word = word.get_or_insert_with(String::new);
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => break,
+                        '\\' => match chars.next() {
+                            Some(c @ ('"' | '\\' | '$' | '`')) => word.push(c),
+                            Some('\n') => {}
+                            Some(c) => {
+                                word.push('\\');
+                                word.push(c);
+                            }
+                            None => word.push('\\'),
+                        },
+                        c => word.push(c),
+                    }
+                }
+            }
+            '\\' => {
+                let word = word.get_or_insert_with(String::new);
+                match chars.clone().next() {
+                    Some('\n') => {
+                        chars.next();
+                    }
+                    Some(c) if c.is_whitespace() || "'\"\\".contains(c) => {
+                        chars.next();
+                        word.push(c);
+                    }
+                    // Keep Windows paths like `C:\Tools\editor.exe` intact
+                    _ => word.push('\\'),
+                }
+            }
+            c => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    words.extend(word);
+    words
+}
+
+/// Quotes `arg` so that `split_shell_words()` (and a shell) would read it back
+/// as a single word.
+pub fn shell_quote(arg: &str) -> Cow<str> {
+    let is_safe = |c: char| c.is_ascii_alphanumeric() || "-_./=:,+@%^".contains(c);
+    if !arg.is_empty() && arg.chars().all(is_safe) {
+        Cow::Borrowed(arg)
+    } else {
+        Cow::Owned(format!("'{}'", arg.replace('\'', r"'\''")))
+    }
+}
+
 /// Wrapper to reject an array without command name.
 // Based on https://github.com/serde-rs/serde/issues/939
 #[derive(Clone, Debug, Eq, Hash, PartialEq, serde::Deserialize)]
@@ -255,5 +1019,204 @@ mod tests {
         let args: FullCommandArgs = config.get("string").unwrap();
         assert_eq!(args, FullCommandArgs::String("emacs -nw".to_owned()));
         assert_eq!(args.args(), ["emacs", "-nw"].as_ref());
+
+        let args = FullCommandArgs::from(r#""my editor/with space" --title 'a "b"' c\ d"#);
+        assert_eq!(
+            args.args(),
+            ["my editor/with space", "--title", r#"a "b""#, "c d"].as_ref()
+        );
+    }
+
+    #[test]
+    fn test_interpolate_env_vars_in_str() {
+        let lookup = |name: &str| match name {
+            "HOME" => Some("/home/user".to_string()),
+            "EMPTY" => Some(String::new()),
+            _ => None,
+        };
+        let interpolate = |text: &str| interpolate_env_vars_in_str("ui.editor", text, lookup);
+        assert_eq!(interpolate("vim").unwrap(), "vim");
+        assert_eq!(
+            interpolate("${HOME}/bin/edit").unwrap(),
+            "/home/user/bin/edit"
+        );
+        assert_eq!(
+            interpolate("${HOME}${EMPTY}${HOME}").unwrap(),
+            "/home/user/home/user"
+        );
+        // `$$` is a literal `$`, and so is a `$` without `{`
+        assert_eq!(interpolate("$${HOME}").unwrap(), "${HOME}");
+        assert_eq!(interpolate("$$$$").unwrap(), "$$");
+        assert_eq!(interpolate("$left $").unwrap(), "$left $");
+        assert_eq!(
+            interpolate("${UNDEFINED}").unwrap_err().to_string(),
+            r#"Config option "ui.editor" refers to undefined environment variable "UNDEFINED""#
+        );
+        assert_eq!(
+            interpolate("${HOME").unwrap_err().to_string(),
+            r#"Config option "ui.editor" has a "${" without a closing "}""#
+        );
+    }
+
+    #[test]
+    fn test_interpolate_env_vars() {
+        let config = config::Config::builder()
+            .add_source(config::File::from_str(
+                r#"
+                ui.editor = "${HOME}/edit"
+                ui.pager = "less"
+                merge-tools.tool.merge-args = ["$left", "${HOME}/$output"]
+                merge-tools.other.merge-args = ["$left", "$right"]
+                "#,
+                config::FileFormat::Toml,
+            ))
+            .build()
+            .unwrap();
+        let config = interpolate_env_vars(config, |name| {
+            (name == "HOME").then(|| "/home/user".to_string())
+        })
+        .unwrap();
+        assert_eq!(config.get_string("ui.editor").unwrap(), "/home/user/edit");
+        assert_eq!(config.get_string("ui.pager").unwrap(), "less");
+        assert_eq!(
+            config
+                .get::<Vec<String>>("merge-tools.tool.merge-args")
+                .unwrap(),
+            ["$left", "/home/user/$output"]
+        );
+        assert_eq!(
+            config
+                .get::<Vec<String>>("merge-tools.other.merge-args")
+                .unwrap(),
+            ["$left", "$right"]
+        );
+    }
+
+    #[test]
+    fn test_interpolate_env_vars_quoted_keys() {
+        let config = config::Config::builder()
+            .add_source(config::File::from_str(
+                r#"
+                [revset-aliases]
+                'user(x)' = 'author(x) | committer(x)'
+                'mine' = 'user("${EMAIL}")'
+                [merge-tools."my.tool"]
+                program = "${HOME}/bin/tool"
+                "#,
+                config::FileFormat::Toml,
+            ))
+            .build()
+            .unwrap();
+        let config = interpolate_env_vars(config, |name| match name {
+            "HOME" => Some("/home/user".to_string()),
+            "EMAIL" => Some("user@example.com".to_string()),
+            _ => None,
+        })
+        .unwrap();
+        let aliases = config.get_table("revset-aliases").unwrap();
+        assert_eq!(
+            aliases["user(x)"].clone().into_string().unwrap(),
+            "author(x) | committer(x)"
+        );
+        assert_eq!(
+            aliases["mine"].clone().into_string().unwrap(),
+            r#"user("user@example.com")"#
+        );
+        let tools = config.get_table("merge-tools").unwrap();
+        let tool = tools["my.tool"].clone().into_table().unwrap();
+        assert_eq!(
+            tool["program"].clone().into_string().unwrap(),
+            "/home/user/bin/tool"
+        );
+    }
+
+    #[test]
+    fn test_split_shell_words() {
+        assert_eq!(split_shell_words(""), Vec::<String>::new());
+        assert_eq!(
+            split_shell_words("  code  --wait\t-g "),
+            ["code", "--wait", "-g"]
+        );
+        // Quotes can be in the middle of a word, and can produce empty words
+        assert_eq!(split_shell_words(r#"a'b c'd "" ''"#), ["ab cd", "", ""]);
+        // Backslashes escape only whitespace, quotes, and backslashes outside
+        // quotes, nothing inside single quotes, and only some characters inside
+        // double quotes
+        assert_eq!(
+            split_shell_words(r#"\'\" '\n' "\"\\\$\n""#),
+            [r#"'""#, r"\n", r#""\$\n"#]
+        );
+        assert_eq!(
+            split_shell_words(r"C:\Tools\editor.exe --wait"),
+            [r"C:\Tools\editor.exe", "--wait"]
+        );
+        // An unterminated quote extends to the end
+        assert_eq!(split_shell_words("a 'b c"), ["a", "b c"]);
+        assert_eq!(split_shell_words(r#"a "b c"#), ["a", "b c"]);
+    }
+
+    #[test]
+    fn test_command_args_display() {
+        let args = ["my editor", "--title", "it's", "", "-n"].map(|s| s.to_owned());
+        let display = FullCommandArgs::Vec(NonEmptyCommandArgsVec(args.to_vec())).to_string();
+        assert_eq!(display, r#"'my editor' --title 'it'\''s' '' -n"#);
+        // The output can be used as a string again
+        assert_eq!(FullCommandArgs::from(&display).args(), args.as_ref());
+    }
+
+    #[test]
+    fn test_format_config_value() {
+        let config = config::Config::builder()
+            .add_source(config::File::from_str(
+                r#"
+                string = "a \"quoted\" string"
+                escape = "\u001b[31m"
+                int = 42
+                bool = true
+                array = ["a", "b"]
+                table = { b = 2, a = 1 }
+                "#,
+                config::FileFormat::Toml,
+            ))
+            .build()
+            .unwrap();
+        let format = |key| format_config_value(&config.get::<config::Value>(key).unwrap());
+        // Strings are quoted and escaped so the output is valid TOML again
+        let parse = |text: String| text.parse::<toml_edit::Value>().unwrap();
+        assert_eq!(
+            parse(format("string")).as_str(),
+            Some(r#"a "quoted" string"#)
+        );
+        assert_eq!(parse(format("escape")).as_str(), Some("\u{1b}[31m"));
+        assert!(!format("escape").contains('\u{1b}'));
+        assert_eq!(format("int"), "42");
+        assert_eq!(format("bool"), "true");
+        assert_eq!(format("array"), r#"["a", "b"]"#);
+        assert_eq!(format("table"), "{ a = 1, b = 2 }");
+    }
+
+    #[test]
+    fn test_compile_config_key_glob() {
+        let glob = compile_config_key_glob("merge-tools.*");
+        assert!(glob.is_match("merge-tools.meld.program"));
+        assert!(!glob.is_match("merge-tools"));
+        assert!(!glob.is_match("ui.merge-editor"));
+        let glob = compile_config_key_glob("ui.?olor");
+        assert!(glob.is_match("ui.color"));
+        assert!(!glob.is_match("ui.colour"));
+        // Other regex metacharacters match literally
+        let glob = compile_config_key_glob("alias.a+b");
+        assert!(glob.is_match("alias.a+b"));
+        assert!(!glob.is_match("alias.aab"));
+    }
+
+    #[test]
+    fn test_is_known_config_key() {
+        assert!(is_known_config_key("ui.color"));
+        assert!(is_known_config_key("merge-tools.meld.merge-args"));
+        assert!(is_known_config_key("alias.l"));
+        assert!(!is_known_config_key("ui.colour"));
+        assert!(!is_known_config_key("ui"));
+        assert!(!is_known_config_key("aliases.l"));
     }
 }
@@ -27,7 +27,7 @@ use jujutsu_lib::conflicts::{
     update_conflict_from_content,
 };
 use jujutsu_lib::gitignore::GitIgnoreFile;
-use jujutsu_lib::matchers::EverythingMatcher;
+use jujutsu_lib::matchers::{EverythingMatcher, Matcher};
 use jujutsu_lib::repo_path::RepoPath;
 use jujutsu_lib::settings::UserSettings;
 use jujutsu_lib::store::Store;
@@ -186,7 +186,7 @@ pub fn run_mergetool(
     };
 
     let editor = get_merge_tool_from_settings(ui)?;
-    let initial_output_content: Vec<u8> = if editor.merge_tool_edits_conflict_markers {
+    let initial_output_content: Vec<u8> = if editor.edits_conflict_markers() {
         let mut materialized_conflict = vec![];
         materialize_merge_result(&content, &mut materialized_conflict)
             .expect("Writing to an in-memory buffer should never fail");
@@ -253,7 +253,7 @@ pub fn run_mergetool(
     }
 
     let mut new_tree_value: Option<TreeValue> = None;
-    if editor.merge_tool_edits_conflict_markers {
+    if editor.edits_conflict_markers() {
         if let Some(new_conflict_id) = update_conflict_from_content(
             tree.store(),
             repo_path,
@@ -379,6 +379,69 @@ pub fn edit_diff(
     Ok(right_tree_state.current_tree_id().clone())
 }
 
+/// Runs the diff tool `tool_name` on `left_tree` and `right_tree`, restricted
+/// to the paths matching `matcher`, and returns what it printed.
+pub fn run_diff_tool(
+    settings: &UserSettings,
+    tool_name: &str,
+    left_tree: &Tree,
+    right_tree: &Tree,
+    matcher: &dyn Matcher,
+) -> Result<Vec<u8>, DiffEditError> {
+    let store = left_tree.store();
+    let changed_files = left_tree
+        .diff(right_tree, matcher)
+        .map(|(path, _value)| path)
+        .collect_vec();
+
+    let temp_dir = tempfile::Builder::new()
+        .prefix("jj-diff-tool-")
+        .tempdir()
+        .map_err(ExternalToolError::SetUpDirError)?;
+    let mut paths: HashMap<&str, PathBuf> = HashMap::new();
+    for (side, tree) in [("left", left_tree), ("right", right_tree)] {
+        let wc_dir = temp_dir.path().join(side);
+        let state_dir = temp_dir.path().join(format!("{side}_state"));
+        check_out(
+            store.clone(),
+            wc_dir.clone(),
+            state_dir,
+            tree,
+            changed_files.clone(),
+        )?;
+        set_readonly_recursively(&wc_dir).map_err(ExternalToolError::SetUpDirError)?;
+        paths.insert(side, wc_dir);
+    }
+
+    let tool = get_tool_config(settings, tool_name).map_err(ExternalToolError::ConfigError)?;
+    let diff_args = if tool.diff_args.is_empty() {
+        vec!["$left".to_string(), "$right".to_string()]
+    } else {
+        tool.diff_args
+    };
+    let args: Vec<PathBuf> = interpolate_mergetool_filename_patterns(&diff_args, &paths);
+    let args_str = args
+        .iter()
+        .map(|p| p.to_string_lossy().into_owned())
+        .collect_vec();
+    let output = Command::new(&tool.program)
+        .args(&args)
+        .output()
+        .map_err(|e| ExternalToolError::FailedToExecute {
+            tool_binary: tool.program.clone(),
+            args: args_str.clone(),
+            source: e,
+        })?;
+    if !output.status.success() {
+        return Err(DiffEditError::from(ExternalToolError::ToolAborted {
+            tool_binary: tool.program,
+            args: args_str,
+            exit_status: output.status,
+        }));
+    }
+    Ok(output.stdout)
+}
+
 /// Merge/diff tool loaded from the settings.
 #[derive(Clone, Debug, serde::Deserialize)]
 #[serde(rename_all = "kebab-case")]
@@ -390,6 +453,12 @@ struct MergeTool {
     /// Arguments to pass to the program when editing diffs.
     #[serde(default)]
     pub edit_args: Vec<String>,
+    /// Arguments to pass to the program when showing diffs (e.g. `jj log -p
+    /// --tool`). `$left` and `$right` are replaced with the paths to
+    /// directories containing the two sides. Defaults to `["$left",
+    /// "$right"]`.
+    #[serde(default)]
+    pub diff_args: Vec<String>,
     /// Arguments to pass to the program when resolving 3-way conflicts.
     /// `$left`, `$right`, `$base`, and `$output` are replaced with
     /// paths to the corresponding files.
@@ -403,11 +472,12 @@ struct MergeTool {
     /// contents of the conflict, with JJ's conflict markers. After the
     /// merge tool is done, any remaining conflict markers in the
     /// file parsed and taken to mean that the conflict was only partially
-    /// resolved.
+    /// resolved. If unset, `merge-tools.merge-tool-edits-conflict-markers`
+    /// applies instead.
     // TODO: Instead of a boolean, this could denote the flavor of conflict markers to put in
     // the file (`jj` or `diff3` for example).
     #[serde(default)]
-    pub merge_tool_edits_conflict_markers: bool,
+    pub merge_tool_edits_conflict_markers: Option<bool>,
 }
 
 impl MergeTool {
@@ -415,12 +485,21 @@ impl MergeTool {
         MergeTool {
             program: program.to_owned(),
             edit_args: vec![],
+            diff_args: vec![],
             merge_args: vec![],
-            merge_tool_edits_conflict_markers: false,
+            merge_tool_edits_conflict_markers: None,
         }
     }
+
+    pub fn edits_conflict_markers(&self) -> bool {
+        self.merge_tool_edits_conflict_markers.unwrap_or(false)
+    }
 }
 
+/// The key under `[merge-tools]` with the value of
+/// `merge-tool-edits-conflict-markers` for tools that don't set it themselves.
+const DEFAULT_EDITS_CONFLICT_MARKERS_KEY: &str = "merge-tool-edits-conflict-markers";
+
 /// Loads merge tool options from `[merge-tools.<name>]`. The given name is used
 /// as an executable name if no configuration found for that name.
 fn get_tool_config(settings: &UserSettings, name: &str) -> Result<MergeTool, ConfigError> {
@@ -430,7 +509,7 @@ fn get_tool_config(settings: &UserSettings, name: &str) -> Result<MergeTool, Con
         Err(ConfigError::NotFound(_)) => return Ok(MergeTool::with_program(name)),
         Err(err) => return Err(err),
     };
-    if let Some(v) = tools_table.get(name) {
+    let mut result = if let Some(v) = tools_table.get(name) {
         let mut result: MergeTool = v
             .clone()
             .try_deserialize()
@@ -440,10 +519,59 @@ fn get_tool_config(settings: &UserSettings, name: &str) -> Result<MergeTool, Con
         if result.program.is_empty() {
             result.program.clone_from(&name.to_string());
         };
-        Ok(result)
+        check_substitution_tokens(
+            &format!("{TABLE_KEY}.{name}.merge-args"),
+            &result.merge_args,
+            &["left", "base", "right", "output"],
+        )?;
+        check_substitution_tokens(
+            &format!("{TABLE_KEY}.{name}.diff-args"),
+            &result.diff_args,
+            &["left", "right"],
+        )?;
+        result
     } else {
-        Ok(MergeTool::with_program(name))
+        MergeTool::with_program(name)
+    };
+    if result.merge_tool_edits_conflict_markers.is_none() {
+        if let Some(value) = tools_table.get(DEFAULT_EDITS_CONFLICT_MARKERS_KEY) {
+            let value = value.clone().into_bool().map_err(|e| {
+                ConfigError::Message(format!(
+                    "{TABLE_KEY}.{DEFAULT_EDITS_CONFLICT_MARKERS_KEY}: {e}"
+                ))
+            })?;
+            result.merge_tool_edits_conflict_markers = Some(value);
+        }
+    }
+    Ok(result)
+}
+
+/// Checks that every argument in `args` that looks like a `$name` substitution
+/// names one of `known_tokens`, so a typo doesn't end up being passed to the
+/// tool verbatim.
+fn check_substitution_tokens(
+    key: &str,
+    args: &[String],
+    known_tokens: &[&str],
+) -> Result<(), ConfigError> {
+    for arg in args {
+        let token = match arg.strip_prefix('$') {
+            Some(token) => token,
+            None => continue,
+        };
+        let looks_like_token =
+            !token.is_empty() && token.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if looks_like_token && !known_tokens.contains(&token) {
+            return Err(ConfigError::Message(format!(
+                "{key}: unknown substitution \"{arg}\" (expected one of {})",
+                known_tokens
+                    .iter()
+                    .map(|token| format!("${token}"))
+                    .join(", ")
+            )));
+        }
     }
+    Ok(())
 }
 
 fn get_diff_editor_from_settings(ui: &mut Ui) -> Result<MergeTool, ExternalToolError> {
@@ -451,17 +579,43 @@ fn get_diff_editor_from_settings(ui: &mut Ui) -> Result<MergeTool, ExternalToolE
     Ok(get_tool_config(ui.settings(), &editor_name)?)
 }
 
+/// Returns a warning if `tool` is given an `$output` file to write to but
+/// doesn't say whether it edits the conflict markers in that file, which is
+/// easy to get wrong when configuring a new tool. The built-in tools are known
+/// to be set up correctly.
+fn conflict_markers_warning(tool_name: &str, tool: &MergeTool, is_builtin: bool) -> Option<String> {
+    if is_builtin
+        || tool.merge_tool_edits_conflict_markers.is_some()
+        || !tool.merge_args.iter().any(|arg| arg == "$output")
+    {
+        return None;
+    }
+    Some(format!(
+        "warning: merge-tools.{tool_name}.merge-args passes \"$output\", but \
+         merge-tools.{tool_name}.merge-tool-edits-conflict-markers is not set, so the output file \
+         will start out empty. Set it to true if the tool edits the conflict markers in the file, \
+         or to false to silence this warning.\n"
+    ))
+}
+
 fn get_merge_tool_from_settings(ui: &mut Ui) -> Result<MergeTool, ConflictResolveError> {
     let editor_name = editor_name_from_settings(ui, "merge")?;
     let editor =
         get_tool_config(ui.settings(), &editor_name).map_err(ExternalToolError::ConfigError)?;
     if editor.merge_args.is_empty() {
-        Err(ConflictResolveError::MergeArgsNotConfigured {
+        return Err(ConflictResolveError::MergeArgsNotConfigured {
             tool_name: editor_name,
-        })
-    } else {
-        Ok(editor)
+        });
+    }
+    let merge_args_key = format!("merge-tools.{editor_name}.merge-args");
+    let is_builtin = match ui.settings().config().get::<config::Value>(&merge_args_key) {
+        Ok(value) => crate::config::is_default_config_value(&merge_args_key, &value),
+        Err(_) => false,
+    };
+    if let Some(warning) = conflict_markers_warning(&editor_name, &editor, is_builtin) {
+        ui.write_warn(warning)?;
     }
+    Ok(editor)
 }
 
 /// Finds the appropriate tool for diff editing or merges
@@ -487,3 +641,115 @@ fn editor_name_from_settings(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn to_args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    fn settings_from_toml(text: &str) -> UserSettings {
+        let config = config::Config::builder()
+            .add_source(config::File::from_str(text, config::FileFormat::Toml))
+            .build()
+            .unwrap();
+        UserSettings::from_config(config)
+    }
+
+    #[test]
+    fn test_edits_conflict_markers_inference() {
+        // Defaults to false
+        let settings = settings_from_toml(
+            r#"
+            merge-tools.tool.merge-args = ["$output"]
+            "#,
+        );
+        let tool = get_tool_config(&settings, "tool").unwrap();
+        assert_eq!(tool.merge_tool_edits_conflict_markers, None);
+        assert!(!tool.edits_conflict_markers());
+
+        // The global default applies to tools that don't set it, including
+        // ones that aren't configured at all
+        let settings = settings_from_toml(
+            r#"
+            merge-tools.merge-tool-edits-conflict-markers = true
+            merge-tools.tool.merge-args = ["$output"]
+            merge-tools.other.merge-args = ["$output"]
+            merge-tools.other.merge-tool-edits-conflict-markers = false
+            "#,
+        );
+        assert!(get_tool_config(&settings, "tool")
+            .unwrap()
+            .edits_conflict_markers());
+        assert!(get_tool_config(&settings, "unconfigured")
+            .unwrap()
+            .edits_conflict_markers());
+        // The tool's own setting wins
+        assert!(!get_tool_config(&settings, "other")
+            .unwrap()
+            .edits_conflict_markers());
+
+        let settings = settings_from_toml(
+            r#"
+            merge-tools.merge-tool-edits-conflict-markers = "yes please"
+            "#,
+        );
+        assert!(get_tool_config(&settings, "tool").is_err());
+    }
+
+    #[test]
+    fn test_conflict_markers_warning() {
+        let mut tool = MergeTool::with_program("tool");
+        tool.merge_args = to_args(&["$left", "$base", "$right", "-o", "$output"]);
+        insta::assert_snapshot!(conflict_markers_warning("tool", &tool, false).unwrap(), @r###"
+        warning: merge-tools.tool.merge-args passes "$output", but merge-tools.tool.merge-tool-edits-conflict-markers is not set, so the output file will start out empty. Set it to true if the tool edits the conflict markers in the file, or to false to silence this warning.
+        "###);
+        // Not for the built-in tools
+        assert_eq!(conflict_markers_warning("tool", &tool, true), None);
+        // Not once the setting is given either way
+        tool.merge_tool_edits_conflict_markers = Some(false);
+        assert_eq!(conflict_markers_warning("tool", &tool, false), None);
+        tool.merge_tool_edits_conflict_markers = Some(true);
+        assert_eq!(conflict_markers_warning("tool", &tool, false), None);
+        // Not if the tool doesn't write to an output file
+        let mut tool = MergeTool::with_program("tool");
+        tool.merge_args = to_args(&["$left", "$right"]);
+        assert_eq!(conflict_markers_warning("tool", &tool, false), None);
+    }
+
+    #[test]
+    fn test_check_substitution_tokens_valid() {
+        let known = ["left", "base", "right", "output"];
+        let args = to_args(&["$base", "$left", "$right", "-o", "$output", "--auto"]);
+        assert!(check_substitution_tokens("merge-tools.kdiff3.merge-args", &args, &known).is_ok());
+        // Arguments that don't look like a substitution are passed as-is
+        let args = to_args(&["$", "$left/file", "--opt=$right", "$left"]);
+        assert!(check_substitution_tokens("merge-tools.tool.merge-args", &args, &known).is_ok());
+        assert!(check_substitution_tokens("merge-tools.tool.merge-args", &[], &known).is_ok());
+    }
+
+    #[test]
+    fn test_check_substitution_tokens_invalid() {
+        let known = ["left", "base", "right", "output"];
+        let args = to_args(&["$lef", "$base", "$right", "-o", "$output"]);
+        let err =
+            check_substitution_tokens("merge-tools.tool.merge-args", &args, &known).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "merge-tools.tool.merge-args: unknown substitution \"$lef\" (expected one of $left, \
+             $base, $right, $output)"
+        );
+        // $base and $output aren't available when showing a diff
+        let args = to_args(&["$left", "$right", "$output"]);
+        let err =
+            check_substitution_tokens("merge-tools.tool.diff-args", &args, &["left", "right"])
+                .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "merge-tools.tool.diff-args: unknown substitution \"$output\" (expected one of $left, \
+             $right)"
+        );
+    }
+}
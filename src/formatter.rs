@@ -133,6 +133,10 @@ pub struct ColorFormatter<W> {
     current_color: Vec<u8>,
 }
 
+/// The colors that `jj log --color-by-author` picks from, as the defaults for
+/// the labels `author_color_0`, `author_color_1`, etc.
+pub const AUTHOR_COLORS: &[&str] = &["red", "green", "yellow", "blue", "magenta", "cyan"];
+
 fn config_colors(user_settings: &UserSettings) -> HashMap<String, String> {
     let mut result = HashMap::new();
     result.insert(String::from("error"), String::from("red"));
@@ -153,6 +157,13 @@ fn config_colors(user_settings: &UserSettings) -> HashMap<String, String> {
     result.insert(String::from("git_head"), String::from("magenta"));
     result.insert(String::from("divergent"), String::from("red"));
     result.insert(String::from("conflict"), String::from("red"));
+    result.insert(String::from("empty"), String::from("green"));
+    result.insert(String::from("relative ahead"), String::from("green"));
+    result.insert(String::from("relative behind"), String::from("yellow"));
+    result.insert(String::from("relative on"), String::from("blue"));
+    result.insert(String::from("signature good"), String::from("green"));
+    result.insert(String::from("signature bad"), String::from("red"));
+    result.insert(String::from("signature unknown"), String::from("yellow"));
 
     // TODO: This near-duplication of the lines above is unfortunate. Should we
     // allow adding and clearing the "bright" bit somehow? Or should we instead
@@ -225,6 +236,13 @@ fn config_colors(user_settings: &UserSettings) -> HashMap<String, String> {
     result.insert(String::from("diff added"), String::from("green"));
     result.insert(String::from("diff modified"), String::from("cyan"));
 
+    result.insert(String::from("dimmed"), String::from("bright black"));
+    result.insert(String::from("highlighted"), String::from("bright yellow"));
+
+    for (i, color) in AUTHOR_COLORS.iter().enumerate() {
+        result.insert(format!("author_color_{i}"), color.to_string());
+    }
+
     result.insert(String::from("op-log id"), String::from("blue"));
     result.insert(String::from("op-log user"), String::from("yellow"));
     result.insert(String::from("op-log time"), String::from("cyan"));
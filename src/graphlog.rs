@@ -19,7 +19,13 @@ use std::io::Write;
 #[derive(Debug, Clone, PartialEq, Eq)]
 // An edge to another node in the graph
 pub enum Edge<T> {
-    Present { target: T, direct: bool },
+    /// `rebased` marks a direct edge to a parent that the node was rebased
+    /// onto, which is drawn dashed
+    Present {
+        target: T,
+        direct: bool,
+        rebased: bool,
+    },
     Missing,
 }
 
@@ -32,6 +38,7 @@ impl<T> Edge<T> {
         Edge::Present {
             target: id,
             direct: true,
+            rebased: false,
         }
     }
 
@@ -39,14 +46,126 @@ impl<T> Edge<T> {
         Edge::Present {
             target: id,
             direct: false,
+            rebased: false,
         }
     }
+
+    pub fn rebased(id: T) -> Self {
+        Edge::Present {
+            target: id,
+            direct: true,
+            rebased: true,
+        }
+    }
+}
+
+/// The characters the edges of the graph are drawn with
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GraphStyle {
+    /// Plain ASCII characters (`|`, `/`, `\`), which work in any terminal
+    Ascii,
+    /// Unicode box-drawing characters, with rounded corners (`╮`)
+    Curved,
+    /// Unicode box-drawing characters, with square corners (`┐`)
+    Square,
+}
+
+impl Default for GraphStyle {
+    fn default() -> Self {
+        GraphStyle::Ascii
+    }
 }
 
+impl GraphStyle {
+    fn glyphs(self) -> &'static Glyphs {
+        match self {
+            GraphStyle::Ascii => &ASCII_GLYPHS,
+            GraphStyle::Curved => &CURVED_GLYPHS,
+            GraphStyle::Square => &SQUARE_GLYPHS,
+        }
+    }
+}
+
+struct Glyphs {
+    direct: &'static str,
+    indirect: &'static str,
+    rebased: &'static str,
+    fork: &'static str,
+    join: &'static str,
+    horizontal: &'static str,
+    /// Connects edges that are merged more than one column to the left
+    underscore: &'static str,
+    /// Ends the horizontal line to the right of a merge with many parents
+    octopus_end: &'static str,
+    /// Used instead of `fork` and `join` where a single edge branches off or
+    /// joins the edge right next to it, if the style has corners
+    corners: Option<Corners>,
+}
+
+struct Corners {
+    /// Where an edge branches off from or joins a vertical edge
+    branch: &'static str,
+    /// Where an edge branches off to the right and goes down
+    fork: &'static str,
+    /// Where an edge from above joins an edge to the left
+    join: &'static str,
+    /// Where an edge from above moves to the column to the left
+    start: &'static str,
+}
+
+const ASCII_GLYPHS: Glyphs = Glyphs {
+    direct: "|",
+    indirect: ":",
+    rebased: "'",
+    fork: "\\",
+    join: "/",
+    horizontal: "-",
+    underscore: "_",
+    octopus_end: ".",
+    corners: None,
+};
+
+const CURVED_GLYPHS: Glyphs = Glyphs {
+    direct: "│",
+    indirect: "┆",
+    rebased: "╎",
+    fork: "╲",
+    join: "╱",
+    horizontal: "─",
+    underscore: "─",
+    octopus_end: "╮",
+    corners: Some(Corners {
+        branch: "├",
+        fork: "╮",
+        join: "╯",
+        start: "╭",
+    }),
+};
+
+const SQUARE_GLYPHS: Glyphs = Glyphs {
+    direct: "│",
+    indirect: "┆",
+    rebased: "╎",
+    fork: "╲",
+    join: "╱",
+    horizontal: "─",
+    underscore: "─",
+    octopus_end: "┐",
+    corners: Some(Corners {
+        branch: "├",
+        fork: "┐",
+        join: "┘",
+        start: "┌",
+    }),
+};
+
 pub struct AsciiGraphDrawer<'writer, K> {
     writer: &'writer mut dyn Write,
     edges: Vec<Edge<K>>,
     pending_text: Vec<Vec<u8>>,
+    gutter_width: usize,
+    glyphs: &'static Glyphs,
+    elided_symbol: Vec<u8>,
 }
 
 impl<'writer, K> AsciiGraphDrawer<'writer, K>
@@ -58,9 +177,40 @@ where
             writer,
             edges: Default::default(),
             pending_text: Default::default(),
+            gutter_width: 0,
+            glyphs: GraphStyle::default().glyphs(),
+            elided_symbol: b"~".to_vec(),
         }
     }
 
+    /// Sets the number of extra columns of padding between the graph and the
+    /// text of each node.
+    pub fn with_gutter_width(mut self, gutter_width: usize) -> Self {
+        self.gutter_width = gutter_width;
+        self
+    }
+
+    /// Sets the symbol that ends edges to nodes that aren't drawn. It should
+    /// be a single character.
+    pub fn with_elided_symbol(mut self, symbol: &str) -> Self {
+        self.elided_symbol = symbol.as_bytes().to_vec();
+        self
+    }
+
+    /// Sets the characters the edges are drawn with.
+    pub fn with_style(mut self, style: GraphStyle) -> Self {
+        self.glyphs = style.glyphs();
+        self
+    }
+
+    /// The number of columns that the graph and the gutter take up in front
+    /// of the text of a node that would be added with `edges`, on any of the
+    /// node's rows.
+    pub fn text_offset(&self, id: &K, edges: &[Edge<K>]) -> usize {
+        let other_edges = self.edges.len() - usize::from(self.index_by_target(id).is_some());
+        2 * (other_edges + edges.len().max(1)) + self.gutter_width
+    }
+
     pub fn add_node(
         &mut self,
         id: &K,
@@ -76,6 +226,7 @@ where
             self.pending_text.pop().unwrap();
         }
         self.pending_text.reverse();
+        let glyphs = self.glyphs;
 
         // Check if an existing edge should be terminated by the new node. If there
         // is, draw the new node in the same column. Otherwise, insert it at the right.
@@ -88,13 +239,13 @@ where
             if edges.len() > 2 && edge_index < self.edges.len() - 1 {
                 for i in 2..edges.len() {
                     for edge in self.edges.iter().take(edge_index + 1) {
-                        AsciiGraphDrawer::straight_edge(&mut self.writer, edge)?;
+                        AsciiGraphDrawer::straight_edge(&mut self.writer, glyphs, edge)?;
                     }
                     for _ in 0..i - 2 {
                         self.writer.write_all(b"  ")?;
                     }
                     for _ in edge_index + 1..self.edges.len() {
-                        self.writer.write_all(b" \\")?;
+                        write!(self.writer, " {}", glyphs.fork)?;
                     }
                     self.writer.write_all(b"\n")?;
                 }
@@ -108,21 +259,21 @@ where
 
         // Draw the edges to the left of the new node
         for edge in self.edges.iter().take(edge_index) {
-            AsciiGraphDrawer::straight_edge(&mut self.writer, edge)?;
+            AsciiGraphDrawer::straight_edge(&mut self.writer, glyphs, edge)?;
         }
         // Draw the new node
         self.writer.write_all(node_symbol)?;
         // If it's a merge of many nodes, draw a vertical line to the right
         for _ in 3..edges.len() {
-            self.writer.write_all(b"--")?;
+            write!(self.writer, "{0}{0}", glyphs.horizontal)?;
         }
         if edges.len() > 2 {
-            self.writer.write_all(b"-.")?;
+            write!(self.writer, "{}{}", glyphs.horizontal, glyphs.octopus_end)?;
         }
         self.writer.write_all(b" ")?;
         // Draw the edges to the right of the new node
         for edge in self.edges.iter().skip(edge_index) {
-            AsciiGraphDrawer::straight_edge(&mut self.writer, edge)?;
+            AsciiGraphDrawer::straight_edge(&mut self.writer, glyphs, edge)?;
         }
         if edges.len() > 1 {
             self.writer.write_all(b"  ")?;
@@ -138,11 +289,28 @@ where
         // If it's a merge commit, insert a row of '\'.
         if edges.len() >= 2 {
             for edge in self.edges.iter().take(edge_index) {
-                AsciiGraphDrawer::straight_edge(&mut self.writer, edge)?;
+                AsciiGraphDrawer::straight_edge(&mut self.writer, glyphs, edge)?;
             }
-            AsciiGraphDrawer::straight_edge_no_space(&mut self.writer, &self.edges[edge_index])?;
-            for _ in edge_index + 1..self.edges.len() {
-                self.writer.write_all(b"\\ ")?;
+            match &glyphs.corners {
+                // A single edge branching off to the right gets a corner
+                Some(corners) if edge_index + 2 == self.edges.len() => {
+                    AsciiGraphDrawer::branch_edge(
+                        &mut self.writer,
+                        glyphs,
+                        &self.edges[edge_index],
+                    )?;
+                    write!(self.writer, "{}{}", glyphs.horizontal, corners.fork)?;
+                }
+                _ => {
+                    AsciiGraphDrawer::straight_edge_no_space(
+                        &mut self.writer,
+                        glyphs,
+                        &self.edges[edge_index],
+                    )?;
+                    for _ in edge_index + 1..self.edges.len() {
+                        write!(self.writer, "{} ", glyphs.fork)?;
+                    }
+                }
             }
             self.writer.write_all(b" ")?;
             self.maybe_write_pending_text()?;
@@ -181,7 +349,7 @@ where
         // Emit any remaining lines of text.
         while !self.pending_text.is_empty() {
             for edge in &self.edges {
-                AsciiGraphDrawer::straight_edge(&mut self.writer, edge)?;
+                AsciiGraphDrawer::straight_edge(&mut self.writer, glyphs, edge)?;
             }
             for _ in self.edges.len()..pad_to_index {
                 self.writer.write_all(b"  ")?;
@@ -204,39 +372,73 @@ where
 
     /// Not an instance method so the caller doesn't need mutable access to the
     /// whole struct.
-    fn straight_edge(writer: &mut dyn Write, edge: &Edge<K>) -> io::Result<()> {
-        AsciiGraphDrawer::straight_edge_no_space(writer, edge)?;
+    fn straight_edge(writer: &mut dyn Write, glyphs: &Glyphs, edge: &Edge<K>) -> io::Result<()> {
+        AsciiGraphDrawer::straight_edge_no_space(writer, glyphs, edge)?;
         writer.write_all(b" ")
     }
 
     /// Not an instance method so the caller doesn't need mutable access to the
     /// whole struct.
-    fn straight_edge_no_space(writer: &mut dyn Write, edge: &Edge<K>) -> io::Result<()> {
-        match edge {
-            Edge::Present { direct: true, .. } => {
-                writer.write_all(b"|")?;
-            }
-            Edge::Present { direct: false, .. } => {
-                writer.write_all(b":")?;
-            }
-            Edge::Missing => {
-                writer.write_all(b"|")?;
-            }
+    fn straight_edge_no_space(
+        writer: &mut dyn Write,
+        glyphs: &Glyphs,
+        edge: &Edge<K>,
+    ) -> io::Result<()> {
+        let glyph = match edge {
+            Edge::Present {
+                direct: true,
+                rebased: true,
+                ..
+            } => glyphs.rebased,
+            Edge::Present { direct: true, .. } => glyphs.direct,
+            Edge::Present { direct: false, .. } => glyphs.indirect,
+            Edge::Missing => glyphs.direct,
+        };
+        writer.write_all(glyph.as_bytes())
+    }
+
+    /// Draws `edge` where another edge branches off from it or joins it on the
+    /// right. Only solid edges get the style's branch corner.
+    fn branch_edge(writer: &mut dyn Write, glyphs: &Glyphs, edge: &Edge<K>) -> io::Result<()> {
+        match (&glyphs.corners, edge) {
+            (
+                Some(corners),
+                Edge::Present {
+                    direct: true,
+                    rebased: false,
+                    ..
+                }
+                | Edge::Missing,
+            ) => writer.write_all(corners.branch.as_bytes()),
+            _ => AsciiGraphDrawer::straight_edge_no_space(writer, glyphs, edge),
         }
-        Ok(())
     }
 
     fn merge_edges(&mut self, source: usize, target: usize, pad_to_index: usize) -> io::Result<()> {
         assert!(target < source);
+        let glyphs = self.glyphs;
         self.edges.remove(source);
         for i in 0..target {
-            AsciiGraphDrawer::straight_edge(&mut self.writer, &self.edges[i])?;
+            AsciiGraphDrawer::straight_edge(&mut self.writer, glyphs, &self.edges[i])?;
         }
         if source == target + 1 {
             // If we're merging exactly one step to the left, draw a '/' to join the lines.
-            AsciiGraphDrawer::straight_edge_no_space(&mut self.writer, &self.edges[target])?;
-            for _ in source..self.edges.len() + 1 {
-                self.writer.write_all(b"/ ")?;
+            match &glyphs.corners {
+                // A single edge joining from the right gets a corner
+                Some(corners) if source == self.edges.len() => {
+                    AsciiGraphDrawer::branch_edge(&mut self.writer, glyphs, &self.edges[target])?;
+                    write!(self.writer, "{}{}", glyphs.horizontal, corners.join)?;
+                }
+                _ => {
+                    AsciiGraphDrawer::straight_edge_no_space(
+                        &mut self.writer,
+                        glyphs,
+                        &self.edges[target],
+                    )?;
+                    for _ in source..self.edges.len() + 1 {
+                        write!(self.writer, "{} ", glyphs.join)?;
+                    }
+                }
             }
             self.writer.write_all(b" ")?;
             for _ in self.edges.len() + 1..pad_to_index {
@@ -246,14 +448,18 @@ where
             // If we're merging more than one step to the left, we need two rows:
             // | |_|_|/
             // |/| | |
-            AsciiGraphDrawer::straight_edge(&mut self.writer, &self.edges[target])?;
+            AsciiGraphDrawer::straight_edge(&mut self.writer, glyphs, &self.edges[target])?;
             for i in target + 1..source - 1 {
-                AsciiGraphDrawer::straight_edge_no_space(&mut self.writer, &self.edges[i])?;
-                self.writer.write_all(b"_")?;
+                AsciiGraphDrawer::straight_edge_no_space(&mut self.writer, glyphs, &self.edges[i])?;
+                self.writer.write_all(glyphs.underscore.as_bytes())?;
             }
-            AsciiGraphDrawer::straight_edge_no_space(&mut self.writer, &self.edges[source - 1])?;
+            AsciiGraphDrawer::straight_edge_no_space(
+                &mut self.writer,
+                glyphs,
+                &self.edges[source - 1],
+            )?;
             for _ in source..self.edges.len() + 1 {
-                self.writer.write_all(b"/ ")?;
+                write!(self.writer, "{} ", glyphs.join)?;
             }
             self.writer.write_all(b" ")?;
             for _ in self.edges.len() + 1..pad_to_index {
@@ -262,12 +468,16 @@ where
             self.maybe_write_pending_text()?;
 
             for i in 0..target {
-                AsciiGraphDrawer::straight_edge(&mut self.writer, &self.edges[i])?;
+                AsciiGraphDrawer::straight_edge(&mut self.writer, glyphs, &self.edges[i])?;
             }
-            AsciiGraphDrawer::straight_edge_no_space(&mut self.writer, &self.edges[target])?;
-            self.writer.write_all(b"/")?;
+            AsciiGraphDrawer::straight_edge_no_space(
+                &mut self.writer,
+                glyphs,
+                &self.edges[target],
+            )?;
+            self.writer.write_all(glyphs.join.as_bytes())?;
             for i in target + 1..self.edges.len() {
-                AsciiGraphDrawer::straight_edge(&mut self.writer, &self.edges[i])?;
+                AsciiGraphDrawer::straight_edge(&mut self.writer, glyphs, &self.edges[i])?;
             }
             for _ in self.edges.len()..pad_to_index {
                 self.writer.write_all(b"  ")?;
@@ -279,13 +489,14 @@ where
     }
 
     fn close_missing_edge(&mut self, source: usize, pad_to_index: usize) -> io::Result<()> {
+        let glyphs = self.glyphs;
         self.edges.remove(source);
         for i in 0..source {
-            AsciiGraphDrawer::straight_edge(&mut self.writer, &self.edges[i])?;
+            AsciiGraphDrawer::straight_edge(&mut self.writer, glyphs, &self.edges[i])?;
         }
-        self.writer.write_all(b"~")?;
+        self.writer.write_all(&self.elided_symbol)?;
         for _ in source..self.edges.len() {
-            self.writer.write_all(b"/ ")?;
+            write!(self.writer, "{} ", glyphs.join)?;
         }
         self.writer.write_all(b" ")?;
         for _ in self.edges.len() + 1..pad_to_index {
@@ -295,12 +506,25 @@ where
     }
 
     fn close_edge(&mut self, source: usize, pad_to_index: usize) -> io::Result<()> {
+        let glyphs = self.glyphs;
         for i in 0..source {
-            AsciiGraphDrawer::straight_edge(&mut self.writer, &self.edges[i])?;
+            AsciiGraphDrawer::straight_edge(&mut self.writer, glyphs, &self.edges[i])?;
         }
-        self.writer.write_all(b" ")?;
-        for _ in source..self.edges.len() {
-            self.writer.write_all(b"/ ")?;
+        match &glyphs.corners {
+            // A single edge moving into the empty column gets corners
+            Some(corners) if source + 1 == self.edges.len() => {
+                write!(
+                    self.writer,
+                    "{}{}{}",
+                    corners.start, glyphs.horizontal, corners.join
+                )?;
+            }
+            _ => {
+                self.writer.write_all(b" ")?;
+                for _ in source..self.edges.len() {
+                    write!(self.writer, "{} ", glyphs.join)?;
+                }
+            }
         }
         self.writer.write_all(b" ")?;
         for _ in self.edges.len() + 1..pad_to_index {
@@ -311,12 +535,121 @@ where
 
     fn maybe_write_pending_text(&mut self) -> io::Result<()> {
         if let Some(text) = self.pending_text.pop() {
+            if !text.is_empty() {
+                self.writer.write_all(&b" ".repeat(self.gutter_width))?;
+            }
             self.writer.write_all(&text)?;
         }
         self.writer.write_all(b"\n")
     }
 }
 
+/// Wraps each line of `text` so that no line is wider than `width` columns.
+///
+/// Lines are broken after the last space that fits, which stays at the end of
+/// the line, or in the middle of a word if there is none. ANSI escape sequences
+/// take up no columns, and a color that is active at a break is reset before
+/// the line ends and restored on the continuation line, so the graph edges
+/// drawn around the text stay uncolored. A `width` of 0 disables wrapping.
+pub fn wrap_text(text: &[u8], width: usize) -> Vec<u8> {
+    if width == 0 {
+        return text.to_vec();
+    }
+    let mut output = Vec::with_capacity(text.len());
+    for (i, line) in text.split(|b| *b == b'\n').enumerate() {
+        if i > 0 {
+            output.push(b'\n');
+        }
+        wrap_line(line, width, &mut output);
+    }
+    output
+}
+
+/// A run of bytes in a line of text: either an escape sequence or a single
+/// (possibly multi-byte) character.
+struct TextItem<'a> {
+    bytes: &'a [u8],
+    is_escape: bool,
+}
+
+fn split_text_items(line: &[u8]) -> Vec<TextItem> {
+    let mut items = vec![];
+    let mut start = 0;
+    while start < line.len() {
+        let mut end = start + 1;
+        let is_escape = line[start] == 0x1b && line.get(end) == Some(&b'[');
+        if is_escape {
+            end += 1;
+            while end < line.len() && !(0x40..=0x7e).contains(&line[end]) {
+                end += 1;
+            }
+            end = (end + 1).min(line.len());
+        } else {
+            // Skip over UTF-8 continuation bytes
+            while end < line.len() && line[end] & 0xc0 == 0x80 {
+                end += 1;
+            }
+        }
+        items.push(TextItem {
+            bytes: &line[start..end],
+            is_escape,
+        });
+        start = end;
+    }
+    items
+}
+
+fn wrap_line(line: &[u8], width: usize, output: &mut Vec<u8>) {
+    let items = split_text_items(line);
+
+    // Find the items that start a continuation line
+    let mut breaks = vec![];
+    let mut column = 0;
+    let mut last_space = None;
+    for (i, item) in items.iter().enumerate() {
+        if item.is_escape {
+            continue;
+        }
+        if column == width {
+            let break_at = match last_space {
+                Some(space) => space + 1,
+                None => i,
+            };
+            breaks.push(break_at);
+            column = items[break_at..i]
+                .iter()
+                .filter(|item| !item.is_escape)
+                .count();
+            last_space = None;
+        }
+        if item.bytes == b" " {
+            last_space = Some(i);
+        }
+        column += 1;
+    }
+
+    let mut breaks = breaks.into_iter().peekable();
+    let mut active_color: &[u8] = b"";
+    for (i, item) in items.iter().enumerate() {
+        if breaks.peek() == Some(&i) {
+            breaks.next();
+            if !active_color.is_empty() {
+                output.extend_from_slice(b"\x1b[0m");
+            }
+            output.push(b'\n');
+            output.extend_from_slice(active_color);
+        }
+        if item.is_escape && item.bytes.ends_with(b"m") {
+            active_color = if item.bytes == b"\x1b[0m" {
+                b""
+            } else {
+                item.bytes
+            };
+        }
+        output.extend_from_slice(item.bytes);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -334,6 +667,199 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn gutter_width() -> io::Result<()> {
+        let mut buffer = vec![];
+        let mut graph = AsciiGraphDrawer::new(&mut buffer).with_gutter_width(3);
+        graph.add_node(&2, &[Edge::direct(1)], b"@", b"node 2\nmore text")?;
+        graph.add_node(&1, &[], b"o", b"node 1")?;
+
+        insta::assert_snapshot!(String::from_utf8_lossy(&buffer), @r###"
+        @    node 2
+        |    more text
+        o    node 1
+        "###);
+
+        Ok(())
+    }
+
+    fn draw_merge_and_chain_end(style: GraphStyle) -> io::Result<String> {
+        let mut buffer = vec![];
+        let mut graph = AsciiGraphDrawer::new(&mut buffer).with_style(style);
+        graph.add_node(&6, &[Edge::direct(4), Edge::direct(5)], b"@", b"node 6")?;
+        graph.add_node(&5, &[Edge::direct(3)], b"o", b"node 5")?;
+        graph.add_node(&4, &[Edge::direct(3)], b"o", b"node 4")?;
+        graph.add_node(&3, &[Edge::direct(1)], b"o", b"node 3")?;
+        graph.add_node(&2, &[Edge::indirect(1)], b"o", b"node 2")?;
+        graph.add_node(&1, &[], b"o", b"node 1")?;
+        Ok(String::from_utf8_lossy(&buffer).into_owned())
+    }
+
+    #[test]
+    fn ascii_style() -> io::Result<()> {
+        insta::assert_snapshot!(draw_merge_and_chain_end(GraphStyle::Ascii)?, @r###"
+        @   node 6
+        |\  
+        | o node 5
+        o | node 4
+        |/  
+        o node 3
+        | o node 2
+        |/  
+        o node 1
+        "###);
+
+        Ok(())
+    }
+
+    #[test]
+    fn curved_style() -> io::Result<()> {
+        insta::assert_snapshot!(draw_merge_and_chain_end(GraphStyle::Curved)?, @r###"
+        @   node 6
+        ├─╮ 
+        │ o node 5
+        o │ node 4
+        ├─╯ 
+        o node 3
+        │ o node 2
+        ├─╯ 
+        o node 1
+        "###);
+
+        Ok(())
+    }
+
+    #[test]
+    fn square_style() -> io::Result<()> {
+        insta::assert_snapshot!(draw_merge_and_chain_end(GraphStyle::Square)?, @r###"
+        @   node 6
+        ├─┐ 
+        │ o node 5
+        o │ node 4
+        ├─┘ 
+        o node 3
+        │ o node 2
+        ├─┘ 
+        o node 1
+        "###);
+
+        Ok(())
+    }
+
+    #[test]
+    fn curved_style_chain_end() -> io::Result<()> {
+        let mut buffer = vec![];
+        let mut graph = AsciiGraphDrawer::new(&mut buffer).with_style(GraphStyle::Curved);
+        graph.add_node(&4, &[Edge::direct(2)], b"o", b"node 4")?;
+        graph.add_node(&3, &[Edge::indirect(1)], b"o", b"node 3")?;
+        graph.add_node(&2, &[], b"o", b"node 2")?;
+        graph.add_node(&1, &[], b"o", b"node 1")?;
+
+        insta::assert_snapshot!(String::from_utf8_lossy(&buffer), @r###"
+        o node 4
+        │ o node 3
+        o ┆ node 2
+        ╭─╯ 
+        o node 1
+        "###);
+
+        Ok(())
+    }
+
+    #[test]
+    fn wrap_text_at_width() {
+        let wrap =
+            |text: &str, width| String::from_utf8(wrap_text(text.as_bytes(), width)).unwrap();
+        // Breaks after the last space that fits, which is kept
+        assert_eq!(wrap("one two three", 8), "one two \nthree");
+        assert_eq!(wrap("one two three", 7), "one \ntwo \nthree");
+        assert_eq!(wrap("one two three", 6), "one \ntwo \nthree");
+        // A space that doesn't fit starts the continuation line
+        assert_eq!(wrap("abcd efg", 4), "abcd\n efg");
+        // Breaks inside words that don't fit on a line
+        assert_eq!(wrap("abcdefghij", 4), "abcd\nefgh\nij");
+        // Lines that fit are left alone
+        assert_eq!(wrap("one\ntwo three\n", 9), "one\ntwo three\n");
+        // Multi-byte characters take up one column
+        assert_eq!(wrap("äöüäöü", 3), "äöü\näöü");
+        // 0 disables wrapping
+        assert_eq!(wrap("one two three", 0), "one two three");
+    }
+
+    #[test]
+    fn wrap_text_with_color() {
+        let wrap =
+            |text: &str, width| String::from_utf8(wrap_text(text.as_bytes(), width)).unwrap();
+        // Escape sequences take up no columns, and the color is restored on the
+        // continuation line
+        assert_eq!(
+            wrap("\x1b[32mabcdef\x1b[0m", 3),
+            "\x1b[32mabc\x1b[0m\n\x1b[32mdef\x1b[0m"
+        );
+        assert_eq!(wrap("ab\x1b[32mcd\x1b[0mef", 4), "ab\x1b[32mcd\x1b[0m\nef");
+    }
+
+    #[test]
+    fn text_offset() -> io::Result<()> {
+        let mut buffer = vec![];
+        let mut graph = AsciiGraphDrawer::new(&mut buffer);
+        assert_eq!(
+            graph.text_offset(&4, &[Edge::direct(2), Edge::direct(3)]),
+            4
+        );
+        graph.add_node(&4, &[Edge::direct(2), Edge::direct(3)], b"@", b"node 4")?;
+        // The edge to node 2 is drawn next to node 3
+        assert_eq!(graph.text_offset(&3, &[Edge::direct(1)]), 4);
+        graph.add_node(&3, &[Edge::direct(1)], b"o", b"node 3")?;
+        assert_eq!(graph.text_offset(&2, &[Edge::direct(1)]), 4);
+        graph.add_node(&2, &[Edge::direct(1)], b"o", b"node 2")?;
+        assert_eq!(graph.text_offset(&1, &[]), 2);
+
+        let mut buffer = vec![];
+        let graph = AsciiGraphDrawer::<u32>::new(&mut buffer).with_gutter_width(3);
+        assert_eq!(graph.text_offset(&1, &[]), 5);
+        Ok(())
+    }
+
+    #[test]
+    fn ascii_glyphs_are_ascii() {
+        let glyphs = &ASCII_GLYPHS;
+        assert!([
+            glyphs.direct,
+            glyphs.indirect,
+            glyphs.rebased,
+            glyphs.fork,
+            glyphs.join,
+            glyphs.horizontal,
+            glyphs.underscore,
+            glyphs.octopus_end,
+        ]
+        .iter()
+        .all(|glyph| glyph.is_ascii()));
+        assert!(glyphs.corners.is_none());
+    }
+
+    #[test]
+    fn rebased_edges() -> io::Result<()> {
+        let mut buffer = vec![];
+        let mut graph = AsciiGraphDrawer::new(&mut buffer);
+        graph.add_node(&4, &[Edge::rebased(2), Edge::direct(3)], b"@", b"node 4")?;
+        graph.add_node(&3, &[Edge::direct(1)], b"o", b"node 3")?;
+        graph.add_node(&2, &[Edge::rebased(1)], b"o", b"node 2")?;
+        graph.add_node(&1, &[], b"o", b"node 1")?;
+
+        insta::assert_snapshot!(String::from_utf8_lossy(&buffer), @r###"
+        @   node 4
+        '\  
+        ' o node 3
+        o | node 2
+        '/  
+        o node 1
+        "###);
+
+        Ok(())
+    }
+
     #[test]
     fn long_description() -> io::Result<()> {
         let mut buffer = vec![];
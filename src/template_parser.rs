@@ -12,8 +12,11 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::cmp::Ordering;
+
 use chrono::{DateTime, FixedOffset, LocalResult, TimeZone, Utc};
-use jujutsu_lib::backend::{CommitId, Signature, Timestamp};
+use itertools::Itertools;
+use jujutsu_lib::backend::{ChangeId, CommitId, Signature, Timestamp};
 use jujutsu_lib::commit::Commit;
 use jujutsu_lib::op_store::WorkspaceId;
 use jujutsu_lib::repo::RepoRef;
@@ -23,10 +26,12 @@ use pest_derive::Parser;
 
 use crate::formatter::PlainTextFormatter;
 use crate::templater::{
-    AuthorProperty, BranchProperty, ChangeIdProperty, CommitIdKeyword, CommitterProperty,
-    ConditionalTemplate, ConflictProperty, ConstantTemplateProperty, DescriptionProperty,
-    DivergentProperty, DynamicLabelTemplate, GitRefsProperty, IsGitHeadProperty,
-    IsWorkingCopyProperty, LabelTemplate, ListTemplate, LiteralTemplate, SignatureTimestamp,
+    stable_hash, AuthorProperty, BranchProperty, ChangeIdKeyword, ChangeIdShortest,
+    ChildrenCountProperty, CommitIdKeyword, CommitterProperty, ConditionalTemplate,
+    ConflictProperty, ConstantTemplateProperty, DescriptionProperty, DiffStats, DiffStatsProperty,
+    DivergentProperty, DynamicLabelTemplate, EmptyProperty, GitRefsProperty, IsGitHeadProperty,
+    IsWorkingCopyProperty, LabelTemplate, ListTemplate, LiteralTemplate, MergeProperty,
+    ParentsCountProperty, ParentsProperty, RawDescriptionProperty, SignatureTimestamp,
     StringPropertyTemplate, TagProperty, Template, TemplateFunction, TemplateProperty,
     WorkingCopiesProperty,
 };
@@ -55,11 +60,13 @@ fn parse_string_literal(pair: Pair<Rule>) -> String {
     result
 }
 
-struct StringShort;
+struct StringShort {
+    len: usize,
+}
 
 impl TemplateProperty<String, String> for StringShort {
     fn extract(&self, context: &String) -> String {
-        context.chars().take(12).collect()
+        context.chars().take(self.len).collect()
     }
 }
 
@@ -67,15 +74,108 @@ struct StringFirstLine;
 
 impl TemplateProperty<String, String> for StringFirstLine {
     fn extract(&self, context: &String) -> String {
-        context.lines().next().unwrap().to_string()
+        context.lines().next().unwrap_or_default().to_string()
+    }
+}
+
+/// Maps the string to a number in `[0, buckets)` that stays the same across
+/// runs, e.g. to pick a color for it.
+struct StringHash {
+    buckets: u64,
+}
+
+impl TemplateProperty<String, i64> for StringHash {
+    fn extract(&self, context: &String) -> i64 {
+        (stable_hash(context) % self.buckets) as i64
+    }
+}
+
+struct StringEmpty;
+
+impl TemplateProperty<String, bool> for StringEmpty {
+    fn extract(&self, context: &String) -> bool {
+        context.is_empty()
+    }
+}
+
+struct StringContains {
+    needle: String,
+}
+
+impl TemplateProperty<String, bool> for StringContains {
+    fn extract(&self, context: &String) -> bool {
+        context.contains(&self.needle)
+    }
+}
+
+struct StringEquals {
+    other: String,
+}
+
+impl TemplateProperty<String, bool> for StringEquals {
+    fn extract(&self, context: &String) -> bool {
+        *context == self.other
+    }
+}
+
+/// Checks that the integer compares to `other` as `ordering`.
+struct IntegerComparison {
+    other: i64,
+    ordering: Ordering,
+}
+
+impl TemplateProperty<i64, bool> for IntegerComparison {
+    fn extract(&self, context: &i64) -> bool {
+        context.cmp(&self.other) == self.ordering
     }
 }
 
-struct CommitIdShortest;
+struct CommitIdShort {
+    len: usize,
+}
+
+impl TemplateProperty<CommitId, String> for CommitIdShort {
+    fn extract(&self, context: &CommitId) -> String {
+        CommitIdKeyword::short_format(context.clone(), self.len)
+    }
+}
+
+struct CommitIdShortest<'a> {
+    repo: RepoRef<'a>,
+}
 
-impl TemplateProperty<CommitId, String> for CommitIdShortest {
+impl TemplateProperty<CommitId, String> for CommitIdShortest<'_> {
     fn extract(&self, context: &CommitId) -> String {
-        CommitIdKeyword::shortest_format(context.clone())
+        CommitIdKeyword::shortest_format(self.repo, context.clone())
+    }
+}
+
+struct ChangeIdHex;
+
+impl TemplateProperty<ChangeId, String> for ChangeIdHex {
+    fn extract(&self, context: &ChangeId) -> String {
+        ChangeIdKeyword::default_format(context.clone())
+    }
+}
+
+struct CommitIdsShort {
+    len: usize,
+}
+
+impl TemplateProperty<Vec<CommitId>, String> for CommitIdsShort {
+    fn extract(&self, context: &Vec<CommitId>) -> String {
+        context
+            .iter()
+            .map(|commit_id| CommitIdKeyword::short_format(commit_id.clone(), self.len))
+            .join(", ")
+    }
+}
+
+struct CommitIdsLen;
+
+impl TemplateProperty<Vec<CommitId>, i64> for CommitIdsLen {
+    fn extract(&self, context: &Vec<CommitId>) -> i64 {
+        context.len() as i64
     }
 }
 
@@ -120,20 +220,150 @@ struct RelativeTimestampString;
 impl TemplateProperty<Timestamp, String> for RelativeTimestampString {
     fn extract(&self, context: &Timestamp) -> String {
         datetime_from_timestamp(context)
-            .and_then(|datetime| {
-                let now = chrono::Local::now();
-
-                now.signed_duration_since(datetime).to_std().ok()
-            })
-            .map(|duration| {
-                let f = timeago::Formatter::new();
-                f.convert(duration)
-            })
+            .map(|datetime| format_time_ago(chrono::Local::now().signed_duration_since(datetime)))
             .unwrap_or_else(|| "<out-of-range date>".to_string())
     }
 }
 
+/// Describes something that happened `duration` ago in the largest whole unit,
+/// e.g. "3 minutes ago" or "yesterday". A negative duration (e.g. from clock
+/// skew between machines) is described as "in the future".
+fn format_time_ago(duration: chrono::Duration) -> String {
+    const MINUTE: i64 = 60;
+    const HOUR: i64 = 60 * MINUTE;
+    const DAY: i64 = 24 * HOUR;
+    const WEEK: i64 = 7 * DAY;
+    const MONTH: i64 = 30 * DAY;
+    const YEAR: i64 = 365 * DAY;
+
+    let seconds = duration.num_seconds();
+    let (count, unit) = if seconds < 0 {
+        return "in the future".to_string();
+    } else if seconds == 0 {
+        return "just now".to_string();
+    } else if seconds < MINUTE {
+        (seconds, "second")
+    } else if seconds < HOUR {
+        (seconds / MINUTE, "minute")
+    } else if seconds < DAY {
+        (seconds / HOUR, "hour")
+    } else if seconds < 2 * DAY {
+        return "yesterday".to_string();
+    } else if seconds < WEEK {
+        (seconds / DAY, "day")
+    } else if seconds < MONTH {
+        (seconds / WEEK, "week")
+    } else if seconds < YEAR {
+        (seconds / MONTH, "month")
+    } else {
+        (seconds / YEAR, "year")
+    };
+    if count == 1 {
+        format!("1 {unit} ago")
+    } else {
+        format!("{count} {unit}s ago")
+    }
+}
+
+struct DiffStatsFilesChanged;
+
+impl TemplateProperty<DiffStats, i64> for DiffStatsFilesChanged {
+    fn extract(&self, context: &DiffStats) -> i64 {
+        context.files_changed as i64
+    }
+}
+
+struct DiffStatsInsertions;
+
+impl TemplateProperty<DiffStats, i64> for DiffStatsInsertions {
+    fn extract(&self, context: &DiffStats) -> i64 {
+        context.insertions as i64
+    }
+}
+
+struct DiffStatsDeletions;
+
+impl TemplateProperty<DiffStats, i64> for DiffStatsDeletions {
+    fn extract(&self, context: &DiffStats) -> i64 {
+        context.deletions as i64
+    }
+}
+
+struct DiffStatsSummary;
+
+impl TemplateProperty<DiffStats, String> for DiffStatsSummary {
+    fn extract(&self, context: &DiffStats) -> String {
+        context.summary()
+    }
+}
+
+struct DiffStatsStat;
+
+impl TemplateProperty<DiffStats, String> for DiffStatsStat {
+    fn extract(&self, context: &DiffStats) -> String {
+        context.stat()
+    }
+}
+
+/// Whether a timestamp is more than `duration` in the past (or less, if
+/// `older` is false).
+struct TimestampAgeComparison {
+    duration: chrono::Duration,
+    older: bool,
+}
+
+impl TemplateProperty<Timestamp, bool> for TimestampAgeComparison {
+    fn extract(&self, context: &Timestamp) -> bool {
+        let age =
+            chrono::Duration::milliseconds(Utc::now().timestamp_millis() - context.timestamp.0);
+        if self.older {
+            age > self.duration
+        } else {
+            age < self.duration
+        }
+    }
+}
+
+/// Parses a duration like "30m", "12h", "7d", or "2w".
+fn parse_duration(text: &str) -> Option<chrono::Duration> {
+    let unit_index = text.find(|c: char| !c.is_ascii_digit())?;
+    let count: i64 = text[..unit_index].parse().ok()?;
+    match &text[unit_index..] {
+        "s" => Some(chrono::Duration::seconds(count)),
+        "m" => Some(chrono::Duration::minutes(count)),
+        "h" => Some(chrono::Duration::hours(count)),
+        "d" => Some(chrono::Duration::days(count)),
+        "w" => Some(chrono::Duration::weeks(count)),
+        _ => None,
+    }
+}
+
+/// Returns the string if `pair` is a method argument consisting of just a
+/// string literal.
+fn parse_string_literal_argument(pair: Pair<Rule>) -> Option<String> {
+    assert_eq!(pair.as_rule(), Rule::template);
+    let term = pair.into_inner().next()?;
+    if term.as_rule() != Rule::term {
+        return None;
+    }
+    let literal = term.into_inner().next()?;
+    (literal.as_rule() == Rule::literal).then(|| parse_string_literal(literal))
+}
+
+/// Parses the optional length argument of `short()`, like the `8` in
+/// `commit_id.short(8)`.
+fn parse_short_length_argument(pair: Pair<Rule>) -> usize {
+    let text = pair.as_str().trim();
+    if text.is_empty() {
+        12
+    } else {
+        text.parse()
+            .unwrap_or_else(|_| panic!("short() requires a length like 8 as argument"))
+    }
+}
+
 fn parse_method_chain<'a, I: 'a>(
+    repo: RepoRef<'a>,
     pair: Pair<Rule>,
     input_property: Property<'a, I>,
 ) -> Property<'a, I> {
@@ -144,45 +374,87 @@ fn parse_method_chain<'a, I: 'a>(
         let method = pair.into_inner().next().unwrap();
         match input_property {
             Property::String(property) => {
-                let next_method = parse_string_method(method);
+                let next_method = parse_string_method(repo, method);
                 next_method.after(property)
             }
             Property::Boolean(property) => {
-                let next_method = parse_boolean_method(method);
+                let next_method = parse_boolean_method(repo, method);
+                next_method.after(property)
+            }
+            Property::Integer(property) => {
+                let next_method = parse_integer_method(repo, method);
                 next_method.after(property)
             }
             Property::CommitId(property) => {
-                let next_method = parse_commit_id_method(method);
+                let next_method = parse_commit_id_method(repo, method);
+                next_method.after(property)
+            }
+            Property::CommitIds(property) => {
+                let next_method = parse_commit_ids_method(repo, method);
+                next_method.after(property)
+            }
+            Property::ChangeId(property) => {
+                let next_method = parse_change_id_method(repo, method);
                 next_method.after(property)
             }
             Property::Signature(property) => {
-                let next_method = parse_signature_method(method);
+                let next_method = parse_signature_method(repo, method);
                 next_method.after(property)
             }
             Property::Timestamp(property) => {
-                let next_method = parse_timestamp_method(method);
+                let next_method = parse_timestamp_method(repo, method);
+                next_method.after(property)
+            }
+            Property::DiffStats(property) => {
+                let next_method = parse_diff_stats_method(repo, method);
                 next_method.after(property)
             }
         }
     }
 }
 
-fn parse_string_method<'a>(method: Pair<Rule>) -> Property<'a, String> {
+fn parse_string_method<'a>(repo: RepoRef<'a>, method: Pair<Rule>) -> Property<'a, String> {
     assert_eq!(method.as_rule(), Rule::method);
     let mut inner = method.into_inner();
     let name = inner.next().unwrap();
     // TODO: validate arguments
 
     let this_function = match name.as_str() {
-        "short" => Property::String(Box::new(StringShort)),
+        "short" => Property::String(Box::new(StringShort {
+            len: parse_short_length_argument(inner.next().unwrap()),
+        })),
         "first_line" => Property::String(Box::new(StringFirstLine)),
+        "empty" => Property::Boolean(Box::new(StringEmpty)),
+        "hash" => {
+            let buckets = inner
+                .next()
+                .unwrap()
+                .as_str()
+                .trim()
+                .parse()
+                .ok()
+                .filter(|buckets| *buckets > 0)
+                .unwrap_or_else(|| panic!("hash() requires a positive integer as argument"));
+            Property::Integer(Box::new(StringHash { buckets }))
+        }
+        "contains" | "eq" => {
+            let argument =
+                parse_string_literal_argument(inner.next().unwrap()).unwrap_or_else(|| {
+                    panic!("{}() requires a string literal as argument", name.as_str())
+                });
+            if name.as_str() == "contains" {
+                Property::Boolean(Box::new(StringContains { needle: argument }))
+            } else {
+                Property::Boolean(Box::new(StringEquals { other: argument }))
+            }
+        }
         name => panic!("no such string method: {}", name),
     };
     let chain_method = inner.last().unwrap();
-    parse_method_chain(chain_method, this_function)
+    parse_method_chain(repo, chain_method, this_function)
 }
 
-fn parse_boolean_method<'a>(method: Pair<Rule>) -> Property<'a, bool> {
+fn parse_boolean_method<'a>(_repo: RepoRef<'a>, method: Pair<Rule>) -> Property<'a, bool> {
     assert_eq!(method.as_rule(), Rule::maybe_method);
     let mut inner = method.into_inner();
     let name = inner.next().unwrap();
@@ -191,23 +463,87 @@ fn parse_boolean_method<'a>(method: Pair<Rule>) -> Property<'a, bool> {
     panic!("no such boolean method: {}", name.as_str());
 }
 
-// TODO: pass a context to the returned function (we need the repo to find the
-//       shortest unambiguous prefix)
-fn parse_commit_id_method<'a>(method: Pair<Rule>) -> Property<'a, CommitId> {
+fn parse_integer_method<'a>(repo: RepoRef<'a>, method: Pair<Rule>) -> Property<'a, i64> {
+    assert_eq!(method.as_rule(), Rule::method);
+    let mut inner = method.into_inner();
+    let name = inner.next().unwrap();
+    // TODO: validate arguments
+
+    let this_function = match name.as_str() {
+        "eq" | "lt" | "gt" => {
+            let other = inner
+                .next()
+                .unwrap()
+                .as_str()
+                .trim()
+                .parse()
+                .unwrap_or_else(|_| panic!("{}() requires an integer as argument", name.as_str()));
+            let ordering = match name.as_str() {
+                "eq" => Ordering::Equal,
+                "lt" => Ordering::Less,
+                _ => Ordering::Greater,
+            };
+            Property::Boolean(Box::new(IntegerComparison { other, ordering }))
+        }
+        name => panic!("no such integer method: {}", name),
+    };
+    let chain_method = inner.last().unwrap();
+    parse_method_chain(repo, chain_method, this_function)
+}
+
+fn parse_commit_id_method<'a>(repo: RepoRef<'a>, method: Pair<Rule>) -> Property<'a, CommitId> {
     assert_eq!(method.as_rule(), Rule::method);
     let mut inner = method.into_inner();
     let name = inner.next().unwrap();
     // TODO: validate arguments
 
     let this_function = match name.as_str() {
-        "short" => Property::String(Box::new(CommitIdShortest)),
+        "short" => Property::String(Box::new(CommitIdShort {
+            len: parse_short_length_argument(inner.next().unwrap()),
+        })),
+        "shortest" => Property::String(Box::new(CommitIdShortest { repo })),
         name => panic!("no such commit ID method: {}", name),
     };
     let chain_method = inner.last().unwrap();
-    parse_method_chain(chain_method, this_function)
+    parse_method_chain(repo, chain_method, this_function)
 }
 
-fn parse_signature_method<'a>(method: Pair<Rule>) -> Property<'a, Signature> {
+fn parse_commit_ids_method<'a>(
+    repo: RepoRef<'a>,
+    method: Pair<Rule>,
+) -> Property<'a, Vec<CommitId>> {
+    assert_eq!(method.as_rule(), Rule::method);
+    let mut inner = method.into_inner();
+    let name = inner.next().unwrap();
+    // TODO: validate arguments
+
+    let this_function = match name.as_str() {
+        "short" => Property::String(Box::new(CommitIdsShort {
+            len: parse_short_length_argument(inner.next().unwrap()),
+        })),
+        "len" => Property::Integer(Box::new(CommitIdsLen)),
+        name => panic!("no such commit IDs method: {}", name),
+    };
+    let chain_method = inner.last().unwrap();
+    parse_method_chain(repo, chain_method, this_function)
+}
+
+fn parse_change_id_method<'a>(repo: RepoRef<'a>, method: Pair<Rule>) -> Property<'a, ChangeId> {
+    assert_eq!(method.as_rule(), Rule::method);
+    let mut inner = method.clone().into_inner();
+    let name = inner.next().unwrap();
+    // TODO: validate arguments
+
+    let this_function = match name.as_str() {
+        "shortest" => Property::String(Box::new(ChangeIdShortest::new(repo))),
+        // Otherwise change IDs work like strings, e.g. `change_id.short()`
+        _ => return parse_string_method(repo, method).after(Box::new(ChangeIdHex)),
+    };
+    let chain_method = inner.last().unwrap();
+    parse_method_chain(repo, chain_method, this_function)
+}
+
+fn parse_signature_method<'a>(repo: RepoRef<'a>, method: Pair<Rule>) -> Property<'a, Signature> {
     assert_eq!(method.as_rule(), Rule::method);
     let mut inner = method.into_inner();
     let name = inner.next().unwrap();
@@ -225,10 +561,10 @@ fn parse_signature_method<'a>(method: Pair<Rule>) -> Property<'a, Signature> {
         name => panic!("no such commit ID method: {}", name),
     };
     let chain_method = inner.last().unwrap();
-    parse_method_chain(chain_method, this_function)
+    parse_method_chain(repo, chain_method, this_function)
 }
 
-fn parse_timestamp_method<'a>(method: Pair<Rule>) -> Property<'a, Timestamp> {
+fn parse_timestamp_method<'a>(repo: RepoRef<'a>, method: Pair<Rule>) -> Property<'a, Timestamp> {
     assert_eq!(method.as_rule(), Rule::method);
     let mut inner = method.into_inner();
     let name = inner.next().unwrap();
@@ -236,18 +572,54 @@ fn parse_timestamp_method<'a>(method: Pair<Rule>) -> Property<'a, Timestamp> {
 
     let this_function = match name.as_str() {
         "ago" => Property::String(Box::new(RelativeTimestampString)),
+        "older_than" | "newer_than" => {
+            let duration = parse_string_literal_argument(inner.next().unwrap())
+                .and_then(|text| parse_duration(&text))
+                .unwrap_or_else(|| {
+                    panic!(
+                        "{}() requires a duration like \"7d\" as argument",
+                        name.as_str()
+                    )
+                });
+            Property::Boolean(Box::new(TimestampAgeComparison {
+                duration,
+                older: name.as_str() == "older_than",
+            }))
+        }
         name => panic!("no such timestamp method: {}", name),
     };
     let chain_method = inner.last().unwrap();
-    parse_method_chain(chain_method, this_function)
+    parse_method_chain(repo, chain_method, this_function)
+}
+
+fn parse_diff_stats_method<'a>(repo: RepoRef<'a>, method: Pair<Rule>) -> Property<'a, DiffStats> {
+    assert_eq!(method.as_rule(), Rule::method);
+    let mut inner = method.into_inner();
+    let name = inner.next().unwrap();
+    // TODO: validate arguments
+
+    let this_function: Property<'a, DiffStats> = match name.as_str() {
+        "files_changed" => Property::Integer(Box::new(DiffStatsFilesChanged)),
+        "insertions" => Property::Integer(Box::new(DiffStatsInsertions)),
+        "deletions" => Property::Integer(Box::new(DiffStatsDeletions)),
+        "summary" => Property::String(Box::new(DiffStatsSummary)),
+        "stat" => Property::String(Box::new(DiffStatsStat)),
+        name => panic!("no such diff stats method: {}", name),
+    };
+    let chain_method = inner.last().unwrap();
+    parse_method_chain(repo, chain_method, this_function)
 }
 
 enum Property<'a, I> {
     String(Box<dyn TemplateProperty<I, String> + 'a>),
     Boolean(Box<dyn TemplateProperty<I, bool> + 'a>),
+    Integer(Box<dyn TemplateProperty<I, i64> + 'a>),
     CommitId(Box<dyn TemplateProperty<I, CommitId> + 'a>),
+    CommitIds(Box<dyn TemplateProperty<I, Vec<CommitId>> + 'a>),
+    ChangeId(Box<dyn TemplateProperty<I, ChangeId> + 'a>),
     Signature(Box<dyn TemplateProperty<I, Signature> + 'a>),
     Timestamp(Box<dyn TemplateProperty<I, Timestamp> + 'a>),
+    DiffStats(Box<dyn TemplateProperty<I, DiffStats> + 'a>),
 }
 
 impl<'a, I: 'a> Property<'a, I> {
@@ -261,10 +633,22 @@ impl<'a, I: 'a> Property<'a, I> {
                 first,
                 Box::new(move |value| property.extract(&value)),
             ))),
+            Property::Integer(property) => Property::Integer(Box::new(TemplateFunction::new(
+                first,
+                Box::new(move |value| property.extract(&value)),
+            ))),
             Property::CommitId(property) => Property::CommitId(Box::new(TemplateFunction::new(
                 first,
                 Box::new(move |value| property.extract(&value)),
             ))),
+            Property::CommitIds(property) => Property::CommitIds(Box::new(TemplateFunction::new(
+                first,
+                Box::new(move |value| property.extract(&value)),
+            ))),
+            Property::ChangeId(property) => Property::ChangeId(Box::new(TemplateFunction::new(
+                first,
+                Box::new(move |value| property.extract(&value)),
+            ))),
             Property::Signature(property) => Property::Signature(Box::new(TemplateFunction::new(
                 first,
                 Box::new(move |value| property.extract(&value)),
@@ -273,6 +657,10 @@ impl<'a, I: 'a> Property<'a, I> {
                 first,
                 Box::new(move |value| property.extract(&value)),
             ))),
+            Property::DiffStats(property) => Property::DiffStats(Box::new(TemplateFunction::new(
+                first,
+                Box::new(move |value| property.extract(&value)),
+            ))),
         }
     }
 }
@@ -281,14 +669,24 @@ fn parse_commit_keyword<'a>(
     repo: RepoRef<'a>,
     workspace_id: &WorkspaceId,
     pair: Pair<Rule>,
+    maybe_method: &Pair<Rule>,
 ) -> (Property<'a, Commit>, String) {
     assert_eq!(pair.as_rule(), Rule::identifier);
     let property = match pair.as_str() {
-        "description" => Property::String(Box::new(DescriptionProperty)),
-        "change_id" => Property::String(Box::new(ChangeIdProperty)),
+        // The placeholder for empty descriptions is only meant for display, so
+        // string methods like `first_line()` see the description as it is.
+        "description" if maybe_method.as_str().is_empty() => {
+            Property::String(Box::new(DescriptionProperty))
+        }
+        "description" => Property::String(Box::new(RawDescriptionProperty)),
+        "change_id" => Property::ChangeId(Box::new(ChangeIdKeyword)),
         "commit_id" => Property::CommitId(Box::new(CommitIdKeyword)),
         "author" => Property::Signature(Box::new(AuthorProperty)),
         "committer" => Property::Signature(Box::new(CommitterProperty)),
+        "parents" => Property::CommitIds(Box::new(ParentsProperty)),
+        "merge" => Property::Boolean(Box::new(MergeProperty)),
+        "parents_count" => Property::Integer(Box::new(ParentsCountProperty)),
+        "children_count" => Property::Integer(Box::new(ChildrenCountProperty::new(repo))),
         "working_copies" => Property::String(Box::new(WorkingCopiesProperty { repo })),
         "current_working_copy" => Property::Boolean(Box::new(IsWorkingCopyProperty {
             repo,
@@ -300,6 +698,8 @@ fn parse_commit_keyword<'a>(
         "is_git_head" => Property::Boolean(Box::new(IsGitHeadProperty::new(repo))),
         "divergent" => Property::Boolean(Box::new(DivergentProperty::new(repo))),
         "conflict" => Property::Boolean(Box::new(ConflictProperty)),
+        "empty" => Property::Boolean(Box::new(EmptyProperty { repo })),
+        "diff" => Property::DiffStats(Box::new(DiffStatsProperty { repo })),
         name => panic!("unexpected identifier: {}", name),
     };
     (property, pair.as_str().to_string())
@@ -314,10 +714,27 @@ fn coerce_to_string<'a, I: 'a>(
             property,
             Box::new(|value| String::from(if value { "true" } else { "false" })),
         )),
+        Property::Integer(property) => Box::new(TemplateFunction::new(
+            property,
+            Box::new(|value| value.to_string()),
+        )),
         Property::CommitId(property) => Box::new(TemplateFunction::new(
             property,
             Box::new(CommitIdKeyword::default_format),
         )),
+        Property::CommitIds(property) => Box::new(TemplateFunction::new(
+            property,
+            Box::new(|commit_ids| {
+                commit_ids
+                    .into_iter()
+                    .map(CommitIdKeyword::default_format)
+                    .join(", ")
+            }),
+        )),
+        Property::ChangeId(property) => Box::new(TemplateFunction::new(
+            property,
+            Box::new(ChangeIdKeyword::default_format),
+        )),
         Property::Signature(property) => Box::new(TemplateFunction::new(
             property,
             Box::new(|signature| signature.name),
@@ -329,6 +746,10 @@ fn coerce_to_string<'a, I: 'a>(
                 None => "<out-of-range date>".to_string(),
             }),
         )),
+        Property::DiffStats(property) => Box::new(TemplateFunction::new(
+            property,
+            Box::new(|stats| stats.shortstat()),
+        )),
     }
 }
 
@@ -339,15 +760,27 @@ fn parse_boolean_commit_property<'a>(
 ) -> Box<dyn TemplateProperty<Commit, bool> + 'a> {
     let mut inner = pair.into_inner();
     let pair = inner.next().unwrap();
-    let _method = inner.next().unwrap();
+    let method = inner.next().unwrap();
     assert!(inner.next().is_none());
     match pair.as_rule() {
-        Rule::identifier => match parse_commit_keyword(repo, workspace_id, pair.clone()).0 {
+        Rule::identifier => match parse_method_chain(
+            repo,
+            method.clone(),
+            parse_commit_keyword(repo, workspace_id, pair.clone(), &method).0,
+        ) {
             Property::Boolean(property) => property,
             Property::String(property) => Box::new(TemplateFunction::new(
                 property,
                 Box::new(|string| !string.is_empty()),
             )),
+            Property::Integer(property) => Box::new(TemplateFunction::new(
+                property,
+                Box::new(|value| value != 0),
+            )),
+            Property::CommitIds(property) => Box::new(TemplateFunction::new(
+                property,
+                Box::new(|commit_ids| !commit_ids.is_empty()),
+            )),
             _ => panic!("cannot yet use this as boolean: {:?}", pair),
         },
         _ => panic!("cannot yet use this as boolean: {:?}", pair),
@@ -375,7 +808,7 @@ fn parse_commit_term<'a>(
                 } else {
                     let input_property =
                         Property::String(Box::new(ConstantTemplateProperty { output: text }));
-                    let property = parse_method_chain(maybe_method, input_property);
+                    let property = parse_method_chain(repo, maybe_method, input_property);
                     let string_property = coerce_to_string(property);
                     Box::new(StringPropertyTemplate {
                         property: string_property,
@@ -383,8 +816,9 @@ fn parse_commit_term<'a>(
                 }
             }
             Rule::identifier => {
-                let (term_property, labels) = parse_commit_keyword(repo, workspace_id, expr);
-                let property = parse_method_chain(maybe_method, term_property);
+                let (term_property, labels) =
+                    parse_commit_keyword(repo, workspace_id, expr, &maybe_method);
+                let property = parse_method_chain(repo, maybe_method, term_property);
                 let string_property = coerce_to_string(property);
                 Box::new(LabelTemplate::new(
                     Box::new(StringPropertyTemplate {
@@ -494,3 +928,38 @@ pub fn parse_commit_template<'a>(
 
     parse_commit_template_rule(repo, workspace_id, first_pair)
 }
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+
+    #[test]
+    fn test_format_time_ago() {
+        assert_eq!(format_time_ago(Duration::seconds(-1)), "in the future");
+        assert_eq!(format_time_ago(Duration::days(-3)), "in the future");
+        assert_eq!(format_time_ago(Duration::zero()), "just now");
+        assert_eq!(format_time_ago(Duration::milliseconds(999)), "just now");
+        assert_eq!(format_time_ago(Duration::seconds(1)), "1 second ago");
+        assert_eq!(format_time_ago(Duration::seconds(59)), "59 seconds ago");
+        assert_eq!(format_time_ago(Duration::seconds(60)), "1 minute ago");
+        assert_eq!(format_time_ago(Duration::seconds(119)), "1 minute ago");
+        assert_eq!(format_time_ago(Duration::seconds(120)), "2 minutes ago");
+        assert_eq!(format_time_ago(Duration::minutes(59)), "59 minutes ago");
+        assert_eq!(format_time_ago(Duration::minutes(60)), "1 hour ago");
+        assert_eq!(format_time_ago(Duration::hours(23)), "23 hours ago");
+        assert_eq!(format_time_ago(Duration::hours(24)), "yesterday");
+        assert_eq!(format_time_ago(Duration::hours(47)), "yesterday");
+        assert_eq!(format_time_ago(Duration::hours(48)), "2 days ago");
+        assert_eq!(format_time_ago(Duration::days(6)), "6 days ago");
+        assert_eq!(format_time_ago(Duration::days(7)), "1 week ago");
+        assert_eq!(format_time_ago(Duration::days(14)), "2 weeks ago");
+        assert_eq!(format_time_ago(Duration::days(29)), "4 weeks ago");
+        assert_eq!(format_time_ago(Duration::days(30)), "1 month ago");
+        assert_eq!(format_time_ago(Duration::days(60)), "2 months ago");
+        assert_eq!(format_time_ago(Duration::days(364)), "12 months ago");
+        assert_eq!(format_time_ago(Duration::days(365)), "1 year ago");
+        assert_eq!(format_time_ago(Duration::days(3 * 365)), "3 years ago");
+    }
+}
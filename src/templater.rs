@@ -14,15 +14,21 @@
 
 use std::borrow::BorrowMut;
 use std::collections::{HashMap, HashSet};
-use std::io;
+use std::io::{self, Read};
 use std::ops::{Add, AddAssign};
 
 use itertools::Itertools;
-use jujutsu_lib::backend::{ChangeId, CommitId, Signature, Timestamp};
+use jujutsu_lib::backend::{ChangeId, CommitId, Signature, Timestamp, TreeValue};
 use jujutsu_lib::commit::Commit;
+use jujutsu_lib::diff::{find_line_ranges, Diff, DiffHunk};
+use jujutsu_lib::index::{HexPrefix, PrefixResolution};
+use jujutsu_lib::matchers::EverythingMatcher;
 use jujutsu_lib::op_store::WorkspaceId;
 use jujutsu_lib::repo::RepoRef;
+use jujutsu_lib::repo_path::RepoPath;
 use jujutsu_lib::revset::RevsetExpression;
+use jujutsu_lib::rewrite::merge_commit_trees;
+use jujutsu_lib::{conflicts, tree};
 
 use crate::formatter::Formatter;
 
@@ -157,14 +163,81 @@ impl<'a, C> Template<C> for StringPropertyTemplate<'a, C> {
     }
 }
 
-pub struct ChangeIdProperty;
+pub struct ChangeIdKeyword;
 
-impl TemplateProperty<Commit, String> for ChangeIdProperty {
-    fn extract(&self, context: &Commit) -> String {
-        context.change_id().hex()
+impl ChangeIdKeyword {
+    pub fn default_format(change_id: ChangeId) -> String {
+        change_id.hex()
     }
 }
 
+impl TemplateProperty<Commit, ChangeId> for ChangeIdKeyword {
+    fn extract(&self, context: &Commit) -> ChangeId {
+        context.change_id().clone()
+    }
+}
+
+/// The shortest prefix of a change ID that is unambiguous among the change IDs
+/// of all visible commits, which are the ones that revsets resolve change ID
+/// prefixes against.
+pub struct ChangeIdShortest {
+    /// The distinct change IDs in hex, sorted
+    change_ids: Vec<String>,
+}
+
+impl ChangeIdShortest {
+    pub fn new(repo: RepoRef) -> Self {
+        // TODO: Create a persistent index from change id to commit ids.
+        let change_ids = RevsetExpression::all()
+            .evaluate(repo, None)
+            .unwrap()
+            .iter()
+            .map(|index_entry| index_entry.change_id().hex())
+            .sorted()
+            .dedup()
+            .collect();
+        Self { change_ids }
+    }
+}
+
+impl TemplateProperty<ChangeId, String> for ChangeIdShortest {
+    fn extract(&self, context: &ChangeId) -> String {
+        let hex = context.hex();
+        let common_prefix_len = |other: &String| {
+            hex.bytes()
+                .zip(other.bytes())
+                .take_while(|(a, b)| a == b)
+                .count()
+        };
+        // Only the neighbors in sorted order can share the longest prefix
+        let index = self
+            .change_ids
+            .binary_search(&hex)
+            .unwrap_or_else(|index| index);
+        let before = self.change_ids[..index].last();
+        let after = self.change_ids[index..].iter().find(|id| **id != hex);
+        let len = before
+            .into_iter()
+            .chain(after)
+            .map(common_prefix_len)
+            .max()
+            .unwrap_or(0)
+            + 1;
+        hex[..len.min(hex.len())].to_string()
+    }
+}
+
+/// Hashes `text` with FNV-1a. Unlike the standard library's hasher, the
+/// result stays the same across builds, so it can be used to pick colors.
+pub fn stable_hash(text: &str) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in text.bytes() {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
 pub struct DescriptionProperty;
 
 impl TemplateProperty<Commit, String> for DescriptionProperty {
@@ -180,6 +253,14 @@ impl TemplateProperty<Commit, String> for DescriptionProperty {
     }
 }
 
+pub struct RawDescriptionProperty;
+
+impl TemplateProperty<Commit, String> for RawDescriptionProperty {
+    fn extract(&self, context: &Commit) -> String {
+        context.description().to_owned()
+    }
+}
+
 pub struct AuthorProperty;
 
 impl TemplateProperty<Commit, Signature> for AuthorProperty {
@@ -354,6 +435,56 @@ impl TemplateProperty<Commit, bool> for DivergentProperty {
     }
 }
 
+pub struct ParentsProperty;
+
+impl TemplateProperty<Commit, Vec<CommitId>> for ParentsProperty {
+    fn extract(&self, context: &Commit) -> Vec<CommitId> {
+        context.parent_ids().to_vec()
+    }
+}
+
+pub struct MergeProperty;
+
+impl TemplateProperty<Commit, bool> for MergeProperty {
+    fn extract(&self, context: &Commit) -> bool {
+        context.parent_ids().len() > 1
+    }
+}
+
+pub struct ParentsCountProperty;
+
+impl TemplateProperty<Commit, i64> for ParentsCountProperty {
+    fn extract(&self, context: &Commit) -> i64 {
+        context.parent_ids().len() as i64
+    }
+}
+
+/// The number of visible children of each commit.
+pub struct ChildrenCountProperty {
+    children_counts: HashMap<CommitId, i64>,
+}
+
+impl ChildrenCountProperty {
+    pub fn new(repo: RepoRef) -> Self {
+        let mut children_counts: HashMap<CommitId, i64> = HashMap::new();
+        for index_entry in RevsetExpression::all().evaluate(repo, None).unwrap().iter() {
+            for parent_entry in index_entry.parents() {
+                children_counts
+                    .entry(parent_entry.commit_id())
+                    .or_default()
+                    .add_assign(1);
+            }
+        }
+        Self { children_counts }
+    }
+}
+
+impl TemplateProperty<Commit, i64> for ChildrenCountProperty {
+    fn extract(&self, context: &Commit) -> i64 {
+        self.children_counts.get(context.id()).copied().unwrap_or(0)
+    }
+}
+
 pub struct ConflictProperty;
 
 impl TemplateProperty<Commit, bool> for ConflictProperty {
@@ -362,6 +493,191 @@ impl TemplateProperty<Commit, bool> for ConflictProperty {
     }
 }
 
+/// Whether the commit has the same tree as its parents merged together, i.e.
+/// changes no files (like the `empty()` revset).
+pub struct EmptyProperty<'a> {
+    pub repo: RepoRef<'a>,
+}
+
+impl TemplateProperty<Commit, bool> for EmptyProperty<'_> {
+    fn extract(&self, context: &Commit) -> bool {
+        let parent_tree = merge_commit_trees(self.repo, &context.parents());
+        parent_tree.id() == context.tree_id()
+    }
+}
+
+/// How a commit changed one file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FileDiffStats {
+    /// The path within the repo, always with `/` as the separator (not relative
+    /// to the current directory)
+    pub path: String,
+    /// 'M', 'A', or 'R', like in `jj diff --summary`
+    pub status: char,
+    pub insertions: usize,
+    pub deletions: usize,
+}
+
+/// How many files a commit changed, and how many lines it inserted and deleted
+/// in them.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DiffStats {
+    pub files_changed: usize,
+    pub insertions: usize,
+    pub deletions: usize,
+    pub files: Vec<FileDiffStats>,
+}
+
+impl DiffStats {
+    /// Lists the changed files like `jj diff --summary`, e.g. "M file1", one
+    /// per line.
+    pub fn summary(&self) -> String {
+        self.files
+            .iter()
+            .map(|file| format!("{} {}", file.status, file.path))
+            .join("\n")
+    }
+
+    /// Formats the stats like `git diff --stat`: a line per file with a bar of
+    /// `+` and `-` (scaled down if a file has many changes), and the
+    /// `shortstat()` at the end.
+    pub fn stat(&self) -> String {
+        const MAX_BAR_WIDTH: usize = 40;
+        let path_width = self
+            .files
+            .iter()
+            .map(|file| file.path.chars().count())
+            .max()
+            .unwrap_or(0);
+        let max_changes = self
+            .files
+            .iter()
+            .map(|file| file.insertions + file.deletions)
+            .max()
+            .unwrap_or(0);
+        let count_width = max_changes.to_string().len();
+        let bar_length = |count: usize| {
+            if max_changes <= MAX_BAR_WIDTH || count == 0 {
+                count
+            } else {
+                (count * MAX_BAR_WIDTH / max_changes).max(1)
+            }
+        };
+        let mut lines = self
+            .files
+            .iter()
+            .map(|file| {
+                format!(
+                    " {:<path_width$} | {:>count_width$} {}{}",
+                    file.path,
+                    file.insertions + file.deletions,
+                    "+".repeat(bar_length(file.insertions)),
+                    "-".repeat(bar_length(file.deletions)),
+                )
+                .trim_end()
+                .to_string()
+            })
+            .collect_vec();
+        lines.push(format!(" {}", self.shortstat()));
+        lines.join("\n")
+    }
+
+    /// Formats the stats like `git diff --shortstat`, e.g. "2 files changed, 3
+    /// insertions(+), 1 deletion(-)".
+    pub fn shortstat(&self) -> String {
+        fn plural(count: usize) -> &'static str {
+            if count == 1 {
+                ""
+            } else {
+                "s"
+            }
+        }
+        let mut text = format!(
+            "{} file{} changed",
+            self.files_changed,
+            plural(self.files_changed)
+        );
+        if self.insertions > 0 {
+            text.push_str(&format!(
+                ", {} insertion{}(+)",
+                self.insertions,
+                plural(self.insertions)
+            ));
+        }
+        if self.deletions > 0 {
+            text.push_str(&format!(
+                ", {} deletion{}(-)",
+                self.deletions,
+                plural(self.deletions)
+            ));
+        }
+        text
+    }
+}
+
+pub struct DiffStatsProperty<'a> {
+    pub repo: RepoRef<'a>,
+}
+
+impl DiffStatsProperty<'_> {
+    /// The contents of a file, or of a conflict with markers. Other kinds of
+    /// values have no lines.
+    fn contents(&self, path: &RepoPath, value: &TreeValue) -> Vec<u8> {
+        let store = self.repo.store();
+        let mut contents = vec![];
+        match value {
+            TreeValue::File { id, .. } => {
+                let mut file = store.read_file(path, id).unwrap();
+                file.read_to_end(&mut contents).unwrap();
+            }
+            TreeValue::Conflict(id) => {
+                let conflict = store.read_conflict(path, id).unwrap();
+                conflicts::materialize_conflict(store, path, &conflict, &mut contents).unwrap();
+            }
+            _ => {}
+        }
+        contents
+    }
+}
+
+impl TemplateProperty<Commit, DiffStats> for DiffStatsProperty<'_> {
+    fn extract(&self, context: &Commit) -> DiffStats {
+        fn count_lines(text: &[u8]) -> usize {
+            text.split_inclusive(|b| *b == b'\n').count()
+        }
+
+        let parent_tree = merge_commit_trees(self.repo, &context.parents());
+        let mut stats = DiffStats::default();
+        for (path, diff) in parent_tree.diff(&context.tree(), &EverythingMatcher) {
+            let (status, left, right) = match &diff {
+                tree::Diff::Modified(left, right) => {
+                    ('M', self.contents(&path, left), self.contents(&path, right))
+                }
+                tree::Diff::Added(right) => ('A', vec![], self.contents(&path, right)),
+                tree::Diff::Removed(left) => ('R', self.contents(&path, left), vec![]),
+            };
+            let mut file_stats = FileDiffStats {
+                path: path.to_internal_file_string(),
+                status,
+                insertions: 0,
+                deletions: 0,
+            };
+            let line_diff = Diff::for_tokenizer(&[&left[..], &right[..]], &find_line_ranges);
+            for hunk in line_diff.hunks() {
+                if let DiffHunk::Different(contents) = hunk {
+                    file_stats.deletions += count_lines(contents[0]);
+                    file_stats.insertions += count_lines(contents[1]);
+                }
+            }
+            stats.files_changed += 1;
+            stats.insertions += file_stats.insertions;
+            stats.deletions += file_stats.deletions;
+            stats.files.push(file_stats);
+        }
+        stats
+    }
+}
+
 pub struct ConditionalTemplate<'a, C> {
     pub condition: Box<dyn TemplateProperty<C, bool> + 'a>,
     pub true_template: Box<dyn Template<C> + 'a>,
@@ -427,9 +743,26 @@ impl CommitIdKeyword {
         commit_id.hex()
     }
 
-    pub fn shortest_format(commit_id: CommitId) -> String {
-        // TODO: make this actually be the shortest unambiguous prefix
-        commit_id.hex()[..12].to_string()
+    pub fn short_format(commit_id: CommitId, len: usize) -> String {
+        let hex = commit_id.hex();
+        hex[..len.min(hex.len())].to_string()
+    }
+
+    /// The shortest prefix of the commit ID that is unambiguous among all
+    /// commits in the repo's index.
+    pub fn shortest_format(repo: RepoRef, commit_id: CommitId) -> String {
+        let hex = commit_id.hex();
+        let index = repo.index();
+        let len = (1..hex.len())
+            .find(|&len| {
+                let prefix = HexPrefix::new(hex[..len].to_string()).unwrap();
+                matches!(
+                    index.resolve_prefix(&prefix),
+                    PrefixResolution::SingleMatch(_)
+                )
+            })
+            .unwrap_or_else(|| hex.len());
+        hex[..len].to_string()
     }
 }
 
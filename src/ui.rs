@@ -144,6 +144,10 @@ impl Ui {
         self.paginate = choice;
     }
 
+    pub fn pagination(&self) -> PaginationChoice {
+        self.paginate
+    }
+
     /// Switches the output to use the pager, if allowed.
     pub fn request_pager(&mut self) {
         if self.paginate == PaginationChoice::No {
@@ -178,12 +182,36 @@ impl Ui {
         &self.settings
     }
 
+    /// Replaces the settings, e.g. after reading the config again.
+    pub fn reset_settings(&mut self, settings: UserSettings) {
+        self.settings = settings;
+        self.reset_color(color_setting(&self.settings));
+    }
+
     pub fn extra_toml_settings(&mut self, toml_strs: &[String]) -> Result<(), config::ConfigError> {
         self.settings = self.settings.with_toml_strings(toml_strs)?;
         self.reset_color(color_setting(&self.settings));
         Ok(())
     }
 
+    /// Layers the config of the repo at `repo_path` over the user's config.
+    /// Environment variables like `JJ_EDITOR` and `toml_strs` (from
+    /// `--config-toml`) are applied again on top so they keep precedence over
+    /// the repo config.
+    pub fn load_repo_config(
+        &mut self,
+        repo_path: &Path,
+        toml_strs: &[String],
+    ) -> Result<(), config::ConfigError> {
+        self.settings = self
+            .settings
+            .with_repo_config(repo_path)?
+            .with_overrides(crate::config::env_overrides())?
+            .with_toml_strings(toml_strs)?;
+        self.reset_color(color_setting(&self.settings));
+        Ok(())
+    }
+
     pub fn new_formatter<'output, W: Write + 'output>(
         &self,
         output: W,
@@ -210,6 +238,11 @@ impl Ui {
         }
     }
 
+    /// Whether stdout is a terminal (and not e.g. piped to another program)
+    pub fn stdout_is_terminal(&self) -> bool {
+        io::stdout().is_tty()
+    }
+
     /// Whether continuous feedback should be displayed for long-running
     /// operations
     pub fn use_progress_indicator(&self) -> bool {
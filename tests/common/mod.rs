@@ -144,6 +144,7 @@ impl TestEnvironment {
                     [merge-tools]
                     fake-editor.program="{escaped_editor_path}"
                     fake-editor.merge-args = ["$output"]
+                    fake-editor.merge-tool-edits-conflict-markers = false
                 "###
             )
             .as_bytes(),
@@ -0,0 +1,962 @@
+// Copyright 2022 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common::{get_stderr_string, get_stdout_string, TestEnvironment};
+
+pub mod common;
+
+#[test]
+fn test_config_list() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config(
+        br#"[ui]
+    color = "never"
+    relative-timestamps = true
+    "#,
+    );
+
+    let stdout = test_env.jj_cmd_success(test_env.env_root(), &["config", "list", "ui"]);
+    insta::assert_snapshot!(stdout, @r###"
+    ui.color = "never"
+    ui.relative-timestamps = true
+    "###);
+
+    // Values from the environment are included
+    let stdout = test_env.jj_cmd_success(test_env.env_root(), &["config", "list", "user.name"]);
+    insta::assert_snapshot!(stdout, @r###"
+    user.name = "Test User"
+    "###);
+
+    // Values from --config-toml are included
+    let stdout = test_env.jj_cmd_success(
+        test_env.env_root(),
+        &[
+            "config",
+            "list",
+            "ui.color",
+            "--config-toml",
+            "ui.color='always'",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    ui.color = "always"
+    "###);
+
+    // A prefix only matches whole name components
+    let stdout = test_env.jj_cmd_success(test_env.env_root(), &["config", "list", "ui.col"]);
+    insta::assert_snapshot!(stdout, @"");
+}
+
+#[test]
+fn test_config_layered_paths() {
+    let test_env = TestEnvironment::default();
+    let base_config_path = test_env.env_root().join("base.toml");
+    let user_config_path = test_env.env_root().join("user.toml");
+    std::fs::write(
+        &base_config_path,
+        "[ui]\npager = \"less\"\ncolor = \"never\"\n",
+    )
+    .unwrap();
+    std::fs::write(&user_config_path, "[ui]\npager = \"more\"\n").unwrap();
+    let config_paths = std::env::join_paths([
+        base_config_path.as_path(),
+        test_env.env_root().join("nonexistent.toml").as_path(),
+        user_config_path.as_path(),
+    ])
+    .unwrap();
+
+    // Later files override earlier ones, and missing files are skipped
+    let assert = test_env
+        .jj_cmd(test_env.env_root(), &["config", "list", "ui"])
+        .env("JJ_CONFIG", &config_paths)
+        .assert()
+        .success();
+    insta::assert_snapshot!(get_stdout_string(&assert), @r###"
+    ui.color = "never"
+    ui.pager = "more"
+    "###);
+
+    // Settings are written to the last file
+    test_env
+        .jj_cmd(
+            test_env.env_root(),
+            &["config", "set", "ui.relative-timestamps", "true"],
+        )
+        .env("JJ_CONFIG", &config_paths)
+        .assert()
+        .success();
+    insta::assert_snapshot!(std::fs::read_to_string(&user_config_path).unwrap(), @r###"
+    [ui]
+    pager = "more"
+    relative-timestamps = true
+    "###);
+}
+
+#[test]
+fn test_config_nested_directory() {
+    let test_env = TestEnvironment::default();
+    let conf_dir = test_env.env_root().join("config").join("conf.d");
+    std::fs::create_dir_all(conf_dir.join("extra")).unwrap();
+    std::fs::write(
+        conf_dir.join("10-colors.toml"),
+        "colors.commit_id = \"red\"\nui.pager = \"less\"\n",
+    )
+    .unwrap();
+    std::fs::write(
+        conf_dir.join("20-aliases.toml"),
+        "alias.l = [\"log\"]\nui.pager = \"more\"\n",
+    )
+    .unwrap();
+    std::fs::write(
+        conf_dir.join("extra").join("settings.toml"),
+        "ui.relative-timestamps = true\n",
+    )
+    .unwrap();
+    // Not a TOML file, so it's ignored even though it doesn't parse
+    std::fs::write(conf_dir.join("README"), "These are my settings\n").unwrap();
+
+    // Files are layered in order of their paths
+    let stdout = test_env.jj_cmd_success(test_env.env_root(), &["config", "list", "ui"]);
+    insta::assert_snapshot!(stdout, @r###"
+    ui.pager = "more"
+    ui.relative-timestamps = true
+    "###);
+    let stdout =
+        test_env.jj_cmd_success(test_env.env_root(), &["config", "get", "colors.commit_id"]);
+    insta::assert_snapshot!(stdout, @r###"
+    red
+    "###);
+    let stdout = test_env.jj_cmd_success(test_env.env_root(), &["config", "list", "alias"]);
+    insta::assert_snapshot!(stdout, @r###"
+    alias.l = ["log"]
+    "###);
+}
+
+#[test]
+fn test_config_profiles() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config(
+        br#"user.email = "me@example.com"
+ui.pager = "less"
+
+[profiles.work]
+user.email = "me@work.example.com"
+
+[profiles.personal]
+user.email = "me@home.example.com"
+"#,
+    );
+    let get_email = |extra_args: &[&str], profile_env: Option<&str>| {
+        let mut args = vec!["config", "get", "user.email"];
+        args.extend_from_slice(extra_args);
+        let mut cmd = test_env.jj_cmd(test_env.env_root(), &args);
+        // Otherwise the test environment's email would override everything
+        cmd.env_remove("JJ_EMAIL");
+        if let Some(profile) = profile_env {
+            cmd.env("JJ_PROFILE", profile);
+        }
+        get_stdout_string(&cmd.assert().success())
+    };
+
+    insta::assert_snapshot!(get_email(&[], None), @r###"
+    me@example.com
+    "###);
+    insta::assert_snapshot!(get_email(&[], Some("work")), @r###"
+    me@work.example.com
+    "###);
+    // The flag overrides the environment variable
+    insta::assert_snapshot!(get_email(&["--profile", "personal"], Some("work")), @r###"
+    me@home.example.com
+    "###);
+
+    // Settings that the profile doesn't set are unchanged
+    let stdout = test_env.jj_cmd_success(
+        test_env.env_root(),
+        &["config", "get", "ui.pager", "--profile", "work"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    less
+    "###);
+
+    let stderr = test_env.jj_cmd_failure(
+        test_env.env_root(),
+        &["config", "get", "user.email", "--profile", "school"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Config error: Unknown profile "school" (available profiles: personal, work)
+    "###);
+}
+
+#[test]
+fn test_config_env_precedence() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+    let get = |name: &str, env: &[(&str, &str)]| {
+        let mut cmd = test_env.jj_cmd(&repo_path, &["config", "get", name]);
+        for (key, value) in env {
+            cmd.env(key, value);
+        }
+        get_stdout_string(&cmd.assert().success())
+    };
+
+    // $PAGER and $EDITOR are only defaults
+    insta::assert_snapshot!(get("ui.pager", &[("PAGER", "env-pager")]), @r###"
+    env-pager
+    "###);
+    test_env.add_config(br#"ui.pager = "user-pager""#);
+    insta::assert_snapshot!(get("ui.pager", &[("PAGER", "env-pager")]), @r###"
+    user-pager
+    "###);
+    test_env.jj_cmd_success(
+        &repo_path,
+        &["config", "set", "--scope", "repo", "ui.pager", "repo-pager"],
+    );
+    test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "config",
+            "set",
+            "--scope",
+            "repo",
+            "ui.editor",
+            "repo-editor",
+        ],
+    );
+    insta::assert_snapshot!(get("ui.pager", &[("PAGER", "env-pager")]), @r###"
+    repo-pager
+    "###);
+    insta::assert_snapshot!(get("ui.editor", &[("EDITOR", "env-editor")]), @r###"
+    repo-editor
+    "###);
+
+    // $JJ_EDITOR beats the config files, including the repo's
+    insta::assert_snapshot!(get("ui.editor", &[("JJ_EDITOR", "jj-editor")]), @r###"
+    jj-editor
+    "###);
+}
+
+#[test]
+fn test_config_list_filter() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config(
+        br#"[ui]
+    color = "never"
+    [merge-tools.kdiff3]
+    program = "kdiff3"
+    "#,
+    );
+
+    let stdout = test_env.jj_cmd_success(
+        test_env.env_root(),
+        &["config", "list", "--filter", "merge-tools.*"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    merge-tools.kdiff3.program = "kdiff3"
+    merge-tools.meld.merge-args = ["$left", "$base", "$right", "-o", "$output", "--auto-merge"]
+    merge-tools.vimdiff.merge-args = ["-f", "-d", "$output", "-M", "$left", "$base", "$right", "-c", "wincmd J", "-c", "set modifiable", "-c", "set write"]
+    merge-tools.vimdiff.merge-tool-edits-conflict-markers = true
+    merge-tools.vimdiff.program = "vim"
+    "###);
+
+    // The pattern must match the whole name
+    let stdout = test_env.jj_cmd_success(
+        test_env.env_root(),
+        &["config", "list", "--filter", "*.program"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    merge-tools.kdiff3.program = "kdiff3"
+    merge-tools.vimdiff.program = "vim"
+    "###);
+    let stdout = test_env.jj_cmd_success(
+        test_env.env_root(),
+        &["config", "list", "--filter", "ui.colo?"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    ui.color = "never"
+    "###);
+    let stdout =
+        test_env.jj_cmd_success(test_env.env_root(), &["config", "list", "--filter", "ui"]);
+    insta::assert_snapshot!(stdout, @"");
+}
+
+#[test]
+fn test_config_list_only_explicit() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config(
+        br#"[merge-tools.kdiff3]
+    merge-args = ["$base", "$left", "$right", "-o", "$output"]
+    "#,
+    );
+
+    // Built-in defaults are listed by default
+    let stdout = test_env.jj_cmd_success(test_env.env_root(), &["config", "list", "merge-tools"]);
+    insta::assert_snapshot!(stdout, @r###"
+    merge-tools.kdiff3.merge-args = ["$base", "$left", "$right", "-o", "$output"]
+    merge-tools.meld.merge-args = ["$left", "$base", "$right", "-o", "$output", "--auto-merge"]
+    merge-tools.vimdiff.merge-args = ["-f", "-d", "$output", "-M", "$left", "$base", "$right", "-c", "wincmd J", "-c", "set modifiable", "-c", "set write"]
+    merge-tools.vimdiff.merge-tool-edits-conflict-markers = true
+    merge-tools.vimdiff.program = "vim"
+    "###);
+
+    // ...but not with --only-explicit, which still shows the overridden default
+    let stdout = test_env.jj_cmd_success(
+        test_env.env_root(),
+        &["config", "list", "merge-tools", "--only-explicit"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    merge-tools.kdiff3.merge-args = ["$base", "$left", "$right", "-o", "$output"]
+    "###);
+
+    // An option that is set to the same value as its default is still listed
+    test_env.add_config(
+        br#"[merge-tools.vimdiff]
+    program = "vim"
+    "#,
+    );
+    let stdout = test_env.jj_cmd_success(
+        test_env.env_root(),
+        &["config", "list", "merge-tools", "--only-explicit"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    merge-tools.kdiff3.merge-args = ["$base", "$left", "$right", "-o", "$output"]
+    merge-tools.vimdiff.program = "vim"
+    "###);
+    let stdout = test_env.jj_cmd_success(
+        test_env.env_root(),
+        &[
+            "config",
+            "get",
+            "merge-tools.vimdiff.program",
+            "--only-explicit",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    vim
+    "###);
+}
+
+#[test]
+fn test_config_list_show_origin() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.add_config(
+        br#"[merge-tools.kdiff3]
+    merge-args = ["$base", "$left", "$right", "-o", "$output"]
+    "#,
+    );
+    test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "config",
+            "set",
+            "--scope",
+            "repo",
+            "merge-tools.vimdiff.program",
+            "nvim",
+        ],
+    );
+
+    // Built-in values are labeled as such, and overrides with their file
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["config", "list", "merge-tools", "--show-origin"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    merge-tools.kdiff3.merge-args = ["$base", "$left", "$right", "-o", "$output"]  # $TEST_ENV/config/config0001.toml
+    merge-tools.meld.merge-args = ["$left", "$base", "$right", "-o", "$output", "--auto-merge"]  # built-in default
+    merge-tools.vimdiff.merge-args = ["-f", "-d", "$output", "-M", "$left", "$base", "$right", "-c", "wincmd J", "-c", "set modifiable", "-c", "set write"]  # built-in default
+    merge-tools.vimdiff.merge-tool-edits-conflict-markers = true  # built-in default
+    merge-tools.vimdiff.program = "nvim"  # $TEST_ENV/repo/.jj/repo/config.toml
+    "###);
+
+    // Values from the command line are labeled too
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "config",
+            "list",
+            "merge-tools.meld",
+            "--show-origin",
+            "--config-toml",
+            "merge-tools.meld.program = 'meld-3'",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    merge-tools.meld.merge-args = ["$left", "$base", "$right", "-o", "$output", "--auto-merge"]  # built-in default
+    merge-tools.meld.program = "meld-3"  # --config-toml
+    "###);
+}
+
+#[test]
+fn test_config_get() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config(
+        br#"[ui]
+    color = "never"
+    "#,
+    );
+
+    let stdout = test_env.jj_cmd_success(test_env.env_root(), &["config", "get", "ui.color"]);
+    insta::assert_snapshot!(stdout, @r###"
+    never
+    "###);
+    let stdout = test_env.jj_cmd_success(
+        test_env.env_root(),
+        &["config", "get", "merge-tools.meld.merge-args"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    ["$left", "$base", "$right", "-o", "$output", "--auto-merge"]
+    "###);
+    // With --all, each element of an array is printed on its own line
+    let stdout = test_env.jj_cmd_success(
+        test_env.env_root(),
+        &["config", "get", "merge-tools.meld.merge-args", "--all"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    $left
+    $base
+    $right
+    -o
+    $output
+    --auto-merge
+    "###);
+    // A scalar is printed as usual
+    let stdout =
+        test_env.jj_cmd_success(test_env.env_root(), &["config", "get", "ui.color", "--all"]);
+    insta::assert_snapshot!(stdout, @r###"
+    never
+    "###);
+
+    let stderr = test_env.jj_cmd_failure(
+        test_env.env_root(),
+        &[
+            "config",
+            "get",
+            "merge-tools.meld.merge-args",
+            "--only-explicit",
+        ],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Config option "merge-tools.meld.merge-args" only has its built-in default value
+    "###);
+    let stdout = test_env.jj_cmd_success(
+        test_env.env_root(),
+        &["config", "get", "ui.color", "--only-explicit"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    never
+    "###);
+
+    let stderr = test_env.jj_cmd_failure(test_env.env_root(), &["config", "get", "nonexistent"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Config option "nonexistent" is not set
+    "###);
+}
+
+#[test]
+fn test_config_env_var_interpolation() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config(
+        br#"
+        ui.editor = "${EDITOR_DIR}/edit --price $$5"
+        "#,
+    );
+
+    let assert = test_env
+        .jj_cmd(test_env.env_root(), &["config", "get", "ui.editor"])
+        .env("EDITOR_DIR", "/opt/tools")
+        .assert()
+        .success()
+        .stderr("");
+    insta::assert_snapshot!(get_stdout_string(&assert), @r###"
+    /opt/tools/edit --price $5
+    "###);
+
+    let stderr = test_env.jj_cmd_failure(test_env.env_root(), &["config", "get", "ui.editor"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Config error: Config option "ui.editor" refers to undefined environment variable "EDITOR_DIR"
+    "###);
+}
+
+#[test]
+fn test_config_get_default() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config(
+        br#"[ui]
+    color = "never"
+    "#,
+    );
+
+    // The default is printed if the option isn't set
+    let assert = test_env
+        .jj_cmd(
+            test_env.env_root(),
+            &["config", "get", "nonexistent", "--default", "fallback"],
+        )
+        .assert()
+        .success();
+    insta::assert_snapshot!(get_stdout_string(&assert), @r###"
+    fallback
+    "###);
+    insta::assert_snapshot!(get_stderr_string(&assert), @"");
+
+    // ...but not if it is
+    let stdout = test_env.jj_cmd_success(
+        test_env.env_root(),
+        &["config", "get", "ui.color", "--default", "always"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    never
+    "###);
+
+    // With --only-explicit, a built-in default counts as not set
+    let stdout = test_env.jj_cmd_success(
+        test_env.env_root(),
+        &[
+            "config",
+            "get",
+            "merge-tools.vimdiff.program",
+            "--only-explicit",
+            "--default",
+            "nvim",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    nvim
+    "###);
+}
+
+#[test]
+fn test_config_set_user() {
+    let test_env = TestEnvironment::default();
+    let user_config_path = test_env.env_root().join("config").join("config.toml");
+
+    test_env.jj_cmd_success(test_env.env_root(), &["config", "set", "ui.pager", "less"]);
+    test_env.jj_cmd_success(
+        test_env.env_root(),
+        &["config", "set", "ui.relative-timestamps", "true"],
+    );
+    insta::assert_snapshot!(std::fs::read_to_string(&user_config_path).unwrap(), @r###"
+    [ui]
+    pager = "less"
+    relative-timestamps = true
+    "###);
+    let stdout = test_env.jj_cmd_success(test_env.env_root(), &["config", "list", "ui"]);
+    insta::assert_snapshot!(stdout, @r###"
+    ui.pager = "less"
+    ui.relative-timestamps = true
+    "###);
+
+    // Can't set a key inside a non-table value
+    let stderr = test_env.jj_cmd_failure(
+        test_env.env_root(),
+        &["config", "set", "ui.pager.program", "less"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Config error: Failed to update config file $TEST_ENV/config/config.toml: "ui.pager" is not a table
+    "###);
+}
+
+#[test]
+fn test_config_set_dry_run() {
+    let test_env = TestEnvironment::default();
+    let user_config_path = test_env.env_root().join("config").join("config.toml");
+    let original = "[ui]\npager = \"less\" # for now\n";
+    std::fs::write(&user_config_path, original).unwrap();
+
+    // The planned change is printed, but the file is left alone
+    let stdout = test_env.jj_cmd_success(
+        test_env.env_root(),
+        &["config", "set", "--dry-run", "ui.pager", "less -FRX"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    Would change ui.pager in $TEST_ENV/config/config.toml from "less" to "less -FRX"
+    "###);
+    let stdout = test_env.jj_cmd_success(
+        test_env.env_root(),
+        &["config", "set", "--dry-run", "ui.color", "never"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    Would set ui.color in $TEST_ENV/config/config.toml to "never"
+    "###);
+    let stdout = test_env.jj_cmd_success(
+        test_env.env_root(),
+        &["config", "unset", "--dry-run", "ui.pager"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    Would remove ui.pager from $TEST_ENV/config/config.toml
+    "###);
+    assert_eq!(
+        std::fs::read_to_string(&user_config_path).unwrap(),
+        original
+    );
+
+    // Unsetting an option that isn't set still fails
+    let stderr = test_env.jj_cmd_failure(
+        test_env.env_root(),
+        &["config", "unset", "--dry-run", "ui.color"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Config option "ui.color" is not set in $TEST_ENV/config/config.toml
+    "###);
+}
+
+#[test]
+fn test_config_set_preserves_comments() {
+    let test_env = TestEnvironment::default();
+    let user_config_path = test_env.env_root().join("config").join("config.toml");
+    std::fs::write(
+        &user_config_path,
+        r#"# My settings
+
+[ui]
+# Use a pager that works in my terminal
+pager = "less"   # for now
+color = "never"
+
+[merge-tools.meld]
+program = "meld"
+"#,
+    )
+    .unwrap();
+
+    test_env.jj_cmd_success(
+        test_env.env_root(),
+        &["config", "set", "ui.pager", "less -FRX"],
+    );
+    test_env.jj_cmd_success(
+        test_env.env_root(),
+        &["config", "set", "merge-tools.kdiff3.program", "kdiff3"],
+    );
+    insta::assert_snapshot!(std::fs::read_to_string(&user_config_path).unwrap(), @r###"
+    # My settings
+
+    [ui]
+    # Use a pager that works in my terminal
+    pager = "less -FRX"   # for now
+    color = "never"
+
+    [merge-tools.meld]
+    program = "meld"
+
+    [merge-tools.kdiff3]
+    program = "kdiff3"
+    "###);
+}
+
+#[test]
+fn test_config_set_repo() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_success(&repo_path, &["config", "set", "ui.default-revset", "all()"]);
+    test_env.jj_cmd_success(
+        &repo_path,
+        &["config", "set", "--scope", "repo", "ui.default-revset", "@"],
+    );
+    insta::assert_snapshot!(
+        std::fs::read_to_string(repo_path.join(".jj").join("repo").join("config.toml")).unwrap(),
+        @r###"
+    [ui]
+    default-revset = "@"
+    "###);
+
+    // The repo value overrides the user value inside the repo
+    let stdout = test_env.jj_cmd_success(&repo_path, &["config", "get", "ui.default-revset"]);
+    insta::assert_snapshot!(stdout, @r###"
+    @
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-T", "description"]);
+    insta::assert_snapshot!(stdout, @r###"
+    @ (no description set)
+    ~
+    "###);
+
+    // ...but not outside it
+    let stdout =
+        test_env.jj_cmd_success(test_env.env_root(), &["config", "get", "ui.default-revset"]);
+    insta::assert_snapshot!(stdout, @r###"
+    all()
+    "###);
+
+    // --config-toml overrides the repo value
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "config",
+            "get",
+            "ui.default-revset",
+            "--config-toml",
+            "ui.default-revset='root'",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    root
+    "###);
+
+    // The repo scope requires a repo
+    let stderr = test_env.jj_cmd_failure(
+        test_env.env_root(),
+        &["config", "set", "--scope", "repo", "ui.color", "never"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Error: There is no jj repo in "."
+    "###);
+}
+
+#[test]
+fn test_config_reset() {
+    let test_env = TestEnvironment::default();
+    let user_config_path = test_env.env_root().join("config").join("config.toml");
+
+    test_env.jj_cmd_success(test_env.env_root(), &["config", "set", "ui.pager", "less"]);
+    test_env.jj_cmd_success(
+        test_env.env_root(),
+        &[
+            "config",
+            "set",
+            "merge-tools.meld.program",
+            "/opt/meld/bin/meld",
+        ],
+    );
+    test_env.jj_cmd_success(
+        test_env.env_root(),
+        &[
+            "config",
+            "set",
+            "merge-tools.meld.merge-args",
+            r#"["$output"]"#,
+        ],
+    );
+
+    // The built-in default applies again after resetting an option
+    let stdout = test_env.jj_cmd_success(
+        test_env.env_root(),
+        &["config", "reset", "merge-tools.meld.merge-args"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    Reset merge-tools.meld.merge-args to its default value ["$left", "$base", "$right", "-o", "$output", "--auto-merge"]
+    "###);
+    let stdout =
+        test_env.jj_cmd_success(test_env.env_root(), &["config", "list", "merge-tools.meld"]);
+    insta::assert_snapshot!(stdout, @r###"
+    merge-tools.meld.merge-args = ["$left", "$base", "$right", "-o", "$output", "--auto-merge"]
+    merge-tools.meld.program = "/opt/meld/bin/meld"
+    "###);
+
+    // The program has no default in the config (the tool name is used)
+    let stdout = test_env.jj_cmd_success(
+        test_env.env_root(),
+        &["config", "reset", "merge-tools.meld.program"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    Removed merge-tools.meld.program, which has no built-in default value
+    "###);
+    let stdout =
+        test_env.jj_cmd_success(test_env.env_root(), &["config", "list", "merge-tools.meld"]);
+    insta::assert_snapshot!(stdout, @r###"
+    merge-tools.meld.merge-args = ["$left", "$base", "$right", "-o", "$output", "--auto-merge"]
+    "###);
+    // Tables left empty are removed from the file
+    insta::assert_snapshot!(std::fs::read_to_string(&user_config_path).unwrap(), @r###"
+    [ui]
+    pager = "less"
+    "###);
+
+    let stderr = test_env.jj_cmd_failure(
+        test_env.env_root(),
+        &["config", "reset", "merge-tools.meld.program"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Config option "merge-tools.meld.program" is not set in $TEST_ENV/config/config.toml
+    "###);
+}
+
+#[test]
+fn test_config_reset_with_other_layers() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.jj_cmd_success(&repo_path, &["config", "set", "ui.pager", "more"]);
+    test_env.jj_cmd_success(
+        &repo_path,
+        &["config", "set", "--scope", "repo", "ui.pager", "less"],
+    );
+
+    // The user's value applies again after resetting the repo's
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["config", "reset", "--scope", "repo", "ui.pager"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    Reset ui.pager to "more" from $TEST_ENV/config/config.toml
+    "###);
+
+    // The repo's value still applies after resetting the user's
+    test_env.jj_cmd_success(
+        &repo_path,
+        &["config", "set", "--scope", "repo", "ui.pager", "less"],
+    );
+    let stdout = test_env.jj_cmd_success(&repo_path, &["config", "reset", "ui.pager"]);
+    insta::assert_snapshot!(stdout, @r###"
+    Reset ui.pager to "less" from $TEST_ENV/repo/.jj/repo/config.toml
+    "###);
+}
+
+#[test]
+fn test_config_export() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.add_config(
+        br#"
+    ui.pager = "less"
+    ui.relative-timestamps = true
+    "#,
+    );
+    test_env.jj_cmd_success(
+        &repo_path,
+        &["config", "set", "--scope", "repo", "ui.pager", "cat"],
+    );
+
+    // The repo config overrides the user config, and the defaults are included
+    let stdout = test_env.jj_cmd_success(&repo_path, &["config", "export"]);
+    let doc = stdout.parse::<toml_edit::Document>().unwrap();
+    assert_eq!(doc["ui"]["pager"].as_str(), Some("cat"));
+    assert_eq!(doc["ui"]["relative-timestamps"].as_bool(), Some(true));
+    assert_eq!(doc["user"]["email"].as_str(), Some("test.user@example.com"));
+    assert!(doc["merge-tools"]["meld"]["merge-args"].is_array());
+
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "config",
+            "export",
+            "--redact",
+            "user.email",
+            "--redact",
+            "no.such-key",
+        ],
+    );
+    let doc = stdout.parse::<toml_edit::Document>().unwrap();
+    assert_eq!(doc["user"]["email"].as_str(), Some("<redacted>"));
+    assert_eq!(doc["user"]["name"].as_str(), Some("Test User"));
+    assert!(doc.get("no").is_none());
+}
+
+#[test]
+fn test_config_where() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.add_config(br#"ui.pager = "less""#);
+    test_env.add_config(
+        br#"
+    ui.pager = "more"
+    ui.relative-timestamps = true
+    "#,
+    );
+    let where_ = |args: &[&str]| {
+        let mut cmd = test_env.jj_cmd(&repo_path, args);
+        cmd.env("EDITOR", "nano");
+        test_env.normalize_output(get_stdout_string(&cmd.assert().success()))
+    };
+
+    // The later file takes precedence
+    insta::assert_snapshot!(where_(&["config", "where", "ui.pager"]), @r###"
+    $TEST_ENV/config/config0002.toml
+    "###);
+
+    test_env.jj_cmd_success(
+        &repo_path,
+        &["config", "set", "--scope", "repo", "ui.pager", "cat"],
+    );
+    insta::assert_snapshot!(where_(&["config", "where", "ui.pager"]), @r###"
+    $TEST_ENV/repo/.jj/repo/config.toml
+    "###);
+    insta::assert_snapshot!(
+        where_(&["config", "where", "ui.pager", "--config-toml", "ui.pager = 'bat'"]), @r###"
+    --config-toml
+    "###);
+
+    insta::assert_snapshot!(where_(&["config", "where", "ui.editor"]), @r###"
+    $EDITOR
+    "###);
+    insta::assert_snapshot!(where_(&["config", "where", "user.email"]), @r###"
+    $JJ_EMAIL
+    "###);
+    insta::assert_snapshot!(where_(&["config", "where", "merge-tools.meld.merge-args"]), @r###"
+    built-in default
+    "###);
+
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["config", "where", "ui.no-such-key"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Config option "ui.no-such-key" is not set
+    "###);
+}
+
+#[test]
+fn test_config_validate() {
+    let test_env = TestEnvironment::default();
+
+    // A valid file produces no output
+    std::fs::write(
+        test_env.env_root().join("good.toml"),
+        r#"
+        [ui]
+        color = "never"
+        [merge-tools.mytool]
+        merge-args = ["$left", "$right"]
+        "#,
+    )
+    .unwrap();
+    let stdout = test_env.jj_cmd_success(test_env.env_root(), &["config", "validate", "good.toml"]);
+    insta::assert_snapshot!(stdout, @"");
+
+    // Unknown keys are reported as warnings
+    std::fs::write(
+        test_env.env_root().join("unknown.toml"),
+        r#"
+        [ui]
+        colour = "never"
+        "#,
+    )
+    .unwrap();
+    let assert = test_env
+        .jj_cmd(test_env.env_root(), &["config", "validate", "unknown.toml"])
+        .assert()
+        .success();
+    insta::assert_snapshot!(get_stdout_string(&assert), @"");
+    insta::assert_snapshot!(get_stderr_string(&assert), @r###"
+    warning: Unknown config key "ui.colour"
+    "###);
+
+    // A malformed file is an error
+    std::fs::write(
+        test_env.env_root().join("bad.toml"),
+        "[ui\ncolor = \"never\"\n",
+    )
+    .unwrap();
+    let stderr = test_env.jj_cmd_failure(test_env.env_root(), &["config", "validate", "bad.toml"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Config error: expected a right bracket, found a newline at line 1 column 4 in $TEST_ENV/bad.toml
+    "###);
+
+    // The file under validation doesn't affect the current settings
+    let stdout = test_env.jj_cmd_success(test_env.env_root(), &["config", "validate", "good.toml"]);
+    insta::assert_snapshot!(stdout, @"");
+}
@@ -14,7 +14,12 @@
 
 use std::ffi::OsString;
 
-use crate::common::{get_stderr_string, TestEnvironment};
+use jujutsu::cli_util::parse_args;
+use jujutsu::commands::default_app;
+use jujutsu::ui::{PaginationChoice, Ui};
+use jujutsu_lib::settings::UserSettings;
+
+use crate::common::{get_stderr_string, get_stdout_string, TestEnvironment};
 
 pub mod common;
 
@@ -192,6 +197,101 @@ color="always""#,
     "###);
 }
 
+#[test]
+fn test_color_flag() {
+    let mut test_env = TestEnvironment::default();
+    test_env.add_env_var("NO_COLOR", "");
+
+    // The flag sets ui.color for this command, even with NO_COLOR set
+    for choice in ["always", "never", "auto"] {
+        let stdout = test_env.jj_cmd_success(
+            test_env.env_root(),
+            &["config", "get", "ui.color", &format!("--color={choice}")],
+        );
+        assert_eq!(stdout, format!("{choice}\n"));
+    }
+
+    // It takes precedence over --config-toml
+    let stdout = test_env.jj_cmd_success(
+        test_env.env_root(),
+        &[
+            "--color=auto",
+            "--config-toml=ui.color='never'",
+            "config",
+            "where",
+            "ui.color",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    --color
+    "###);
+
+    // Output that isn't going to a terminal isn't colored with `auto`
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+    let stdout = test_env.jj_cmd_success(&repo_path, &["--color=auto", "log", "-T", "commit_id"]);
+    assert!(!stdout.contains('\u{1b}'), "{stdout}");
+    let stdout = test_env.jj_cmd_success(&repo_path, &["--color=always", "log", "-T", "commit_id"]);
+    assert!(stdout.contains('\u{1b}'), "{stdout}");
+
+    // Other values are rejected
+    let stderr = test_env.jj_cmd_cli_error(test_env.env_root(), &["--color=sometimes", "log"]);
+    assert!(stderr.contains("invalid value 'sometimes'"), "{stderr}");
+}
+
+#[test]
+fn test_no_color_env() {
+    let mut test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.add_env_var("NO_COLOR", "");
+
+    // With nothing else specified, NO_COLOR turns color off
+    let stdout = test_env.jj_cmd_success(&repo_path, &["config", "get", "ui.color"]);
+    insta::assert_snapshot!(stdout, @r###"
+    never
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["config", "where", "ui.color"]);
+    insta::assert_snapshot!(stdout, @r###"
+    $NO_COLOR
+    "###);
+
+    // --color overrides it
+    let stdout = test_env.jj_cmd_success(&repo_path, &["--color=always", "log", "-T", "commit_id"]);
+    insta::assert_snapshot!(stdout, @r###"
+    @ [1;34m230dd059e1b059aefc0da06a2e5a7dbf22362f22[0m
+    o [34m0000000000000000000000000000000000000000[0m
+    "###);
+
+    // So does --config-toml
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "--config-toml",
+            "ui.color=\"always\"",
+            "log",
+            "-T",
+            "commit_id",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    @ [1;34m230dd059e1b059aefc0da06a2e5a7dbf22362f22[0m
+    o [34m0000000000000000000000000000000000000000[0m
+    "###);
+
+    // And so does the config file, unless --color says otherwise
+    test_env.add_config(br#"ui.color = "always""#);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["config", "get", "ui.color"]);
+    insta::assert_snapshot!(stdout, @r###"
+    always
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["--color=never", "log", "-T", "commit_id"]);
+    insta::assert_snapshot!(stdout, @r###"
+    @ 230dd059e1b059aefc0da06a2e5a7dbf22362f22
+    o 0000000000000000000000000000000000000000
+    "###);
+}
+
 #[test]
 fn test_invalid_config() {
     // Test that we get a reasonable error if the config is invalid (#55)
@@ -233,6 +333,22 @@ fn test_no_user_configured() {
     "###);
 }
 
+#[test]
+fn test_no_pager() {
+    // The pager is never used when stdout isn't a terminal, like in the other
+    // tests, so check that the flag (before or after the command) turns
+    // pagination off instead
+    for (args, expected) in [
+        (&["jj", "log"][..], PaginationChoice::Auto),
+        (&["jj", "--no-pager", "log"], PaginationChoice::No),
+        (&["jj", "log", "--no-pager"], PaginationChoice::No),
+    ] {
+        let mut ui = Ui::for_terminal(UserSettings::default());
+        parse_args(&mut ui, default_app(), args.iter().map(OsString::from)).unwrap();
+        assert_eq!(ui.pagination(), expected);
+    }
+}
+
 #[test]
 fn test_help() {
     // Test that global options are separated out in the help output
@@ -255,6 +371,7 @@ fn test_help() {
           --color <WHEN>                 When to colorize output (always, never, auto)
           --no-pager                     Disable the pager
           --config-toml <TOML>           Additional configuration options
+          --profile <NAME>               Use the settings in the `[profiles.<NAME>]` table of the config
       -v, --verbose                      Enable verbose logging
     "###);
 }
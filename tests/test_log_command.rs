@@ -13,6 +13,11 @@
 // limitations under the License.
 
 use common::{get_stderr_string, get_stdout_string, TestEnvironment};
+use jujutsu::commands::{write_log, LogOptions};
+use jujutsu_lib::repo::BackendFactories;
+use jujutsu_lib::revset::{self, RevsetAliasesMap, RevsetWorkspaceContext};
+use jujutsu_lib::settings::UserSettings;
+use jujutsu_lib::workspace::Workspace;
 use regex::Regex;
 
 pub mod common;
@@ -145,189 +150,367 @@ fn test_log_with_or_without_diff() {
 }
 
 #[test]
-fn test_log_reversed() {
+fn test_log_stat() {
     let test_env = TestEnvironment::default();
     test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
     let repo_path = test_env.env_root().join("repo");
 
-    test_env.jj_cmd_success(&repo_path, &["describe", "-m", "first"]);
-    test_env.jj_cmd_success(&repo_path, &["new", "-m", "second"]);
+    std::fs::write(repo_path.join("file1"), "foo\nbar\n").unwrap();
+    std::fs::write(repo_path.join("image"), b"\0\x01").unwrap();
+    test_env.jj_cmd_success(&repo_path, &["describe", "-m", "add files"]);
+    test_env.jj_cmd_success(&repo_path, &["new", "-m", "change files"]);
+    std::fs::write(repo_path.join("file1"), "foo\nbaz\nqux\n").unwrap();
+    std::fs::write(repo_path.join("a-longer-name"), "new\n").unwrap();
+    std::fs::write(repo_path.join("image"), b"\0\x02").unwrap();
 
-    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-T", "description", "--reversed"]);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-T", "description", "--stat"]);
     insta::assert_snapshot!(stdout, @r###"
+    @ change files
+    | a-longer-name | +1/-0
+    | file1         | +2/-1
+    | image         | Bin
+    | 3 files changed, 3 insertions(+), 1 deletion(-)
+    o add files
+    | file1 | +2/-0
+    | image | Bin
+    | 2 files changed, 2 insertions(+)
     o (no description set)
-    o first
-    @ second
     "###);
 
     let stdout = test_env.jj_cmd_success(
         &repo_path,
-        &["log", "-T", "description", "--reversed", "--no-graph"],
+        &[
+            "log",
+            "-T",
+            "description",
+            "--no-graph",
+            "--stat",
+            "-r",
+            "@-",
+        ],
     );
     insta::assert_snapshot!(stdout, @r###"
-    (no description set)
-    first
-    second
+    add files
+    file1 | +2/-0
+    image | Bin
+    2 files changed, 2 insertions(+)
     "###);
 }
 
 #[test]
-fn test_log_filtered_by_path() {
+fn test_log_diff_word_wrap() {
     let test_env = TestEnvironment::default();
     test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
     let repo_path = test_env.env_root().join("repo");
 
     std::fs::write(repo_path.join("file1"), "foo\n").unwrap();
-    test_env.jj_cmd_success(&repo_path, &["describe", "-m", "first"]);
-    test_env.jj_cmd_success(&repo_path, &["new", "-m", "second"]);
-    std::fs::write(repo_path.join("file1"), "foo\nbar\n").unwrap();
-    std::fs::write(repo_path.join("file2"), "baz\n").unwrap();
+    test_env.jj_cmd_success(&repo_path, &["describe", "-m", "add a file"]);
+    test_env.jj_cmd_success(&repo_path, &["new", "-m", "add a long line"]);
+    std::fs::write(
+        repo_path.join("file1"),
+        "foo\nthe quick brown fox jumps over the lazy dog\n",
+    )
+    .unwrap();
 
-    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-T", "description", "file1"]);
+    // Continuation lines stay next to the graph
+    test_env.add_config(br#"ui.diff.word-wrap = 30"#);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-T", "description", "-p"]);
     insta::assert_snapshot!(stdout, @r###"
-    @ second
-    o first
-    ~ 
+    @ add a long line
+    | Modified regular file file1:
+    |    1    1: foo
+    |         2: the quick brown 
+    | fox jumps over the lazy dog
+    o add a file
+    | Added regular file file1:
+    |         1: foo
+    o (no description set)
     "###);
 
-    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-T", "description", "file2"]);
+    // The gutter counts towards the width
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "-T", "description", "-p", "--gutter-width", "2"],
+    );
     insta::assert_snapshot!(stdout, @r###"
-    @ second
-    ~ 
+    @   add a long line
+    |   Modified regular file file1:
+    |      1    1: foo
+    |           2: the quick 
+    |   brown fox jumps over the 
+    |   lazy dog
+    o   add a file
+    |   Added regular file file1:
+    |           1: foo
+    o   (no description set)
     "###);
+}
 
-    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-T", "description", "-s", "file1"]);
+#[cfg(unix)]
+#[test]
+fn test_log_diff_external_command() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file1"), "foo\n").unwrap();
+    test_env.jj_cmd_success(&repo_path, &["describe", "-m", "add a file"]);
+
+    // The command's output replaces the diff
+    test_env.add_config(br#"diff.external-command = ["sed", "s/^/> /"]"#);
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "-T", "description", "-r", "@", "--no-graph", "-p"],
+    );
     insta::assert_snapshot!(stdout, @r###"
-    @ second
-    | M file1
-    o first
-    ~ A file1
+    add a file
+    > diff --git a/file1 b/file1
+    > new file mode 100644
+    > index 0000000000..257cc5642c
+    > --- /dev/null
+    > +++ b/file1
+    > @@ -1,0 +1,1 @@
+    > +foo
     "###);
 
+    // The summary isn't passed to the command
     let stdout = test_env.jj_cmd_success(
         &repo_path,
-        &["log", "-T", "description", "-s", "file2", "--no-graph"],
+        &["log", "-T", "description", "-r", "@", "--no-graph", "-s"],
     );
     insta::assert_snapshot!(stdout, @r###"
-    second
-    A file2
+    add a file
+    A file1
     "###);
 
-    // file() revset doesn't filter the diff.
+    // Falls back to the built-in diff if the command fails
+    test_env.add_config(br#"diff.external-command = "false""#);
+    let assert = test_env
+        .jj_cmd(
+            &repo_path,
+            &["log", "-T", "description", "-r", "@", "--no-graph", "-p"],
+        )
+        .assert()
+        .success();
+    insta::assert_snapshot!(get_stdout_string(&assert), @r###"
+    add a file
+    Added regular file file1:
+            1: foo
+    "###);
+    insta::assert_snapshot!(get_stderr_string(&assert), @r###"
+    warning: Failed to run external diff command 'false': exited with exit status: 1; showing the built-in diff instead
+    "###);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_log_diff_tool() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file"), "a\n").unwrap();
+    test_env.jj_cmd_success(&repo_path, &["describe", "-m", "first"]);
+    test_env.jj_cmd_success(&repo_path, &["new", "-m", "second"]);
+    std::fs::write(repo_path.join("file"), "b\n").unwrap();
+
+    // The tool is passed the directories with the two sides of the diff
+    test_env.add_config(
+        br#"
+        [merge-tools.show-sides]
+        program = "sh"
+        diff-args = [
+            "-c",
+            'for arg; do echo "$(basename "$arg"): $(cat "$arg/file")"; done',
+            "sh",
+            "$left",
+            "$right",
+        ]
+        "#,
+    );
     let stdout = test_env.jj_cmd_success(
         &repo_path,
         &[
             "log",
             "-T",
             "description",
-            "-s",
-            "-rfile(file2)",
+            "-r",
+            "@",
             "--no-graph",
+            "-p",
+            "--tool",
+            "show-sides",
         ],
     );
     insta::assert_snapshot!(stdout, @r###"
     second
-    M file1
-    A file2
+    left: a
+    right: b
     "###);
+
+    // A failing tool is an error
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &["log", "-r", "@", "--no-graph", "-p", "--tool", "false"],
+    );
+    assert!(stderr
+        .starts_with("Error: Failed to show diff with 'false': Tool exited with a non-zero code."));
+
+    // The tool is only used for the patch
+    let stderr = test_env.jj_cmd_cli_error(&repo_path, &["log", "--tool", "show-sides"]);
+    assert!(stderr.contains("--patch"));
 }
 
 #[test]
-fn test_log_warn_path_might_be_revset() {
+fn test_log_library_api() {
     let test_env = TestEnvironment::default();
     test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
     let repo_path = test_env.env_root().join("repo");
 
     std::fs::write(repo_path.join("file1"), "foo\n").unwrap();
+    test_env.jj_cmd_success(&repo_path, &["describe", "-m", "add a file"]);
+    test_env.jj_cmd_success(&repo_path, &["new", "-m", "a new commit"]);
 
-    // Don't warn if the file actually exists.
-    let assert = test_env
-        .jj_cmd(&repo_path, &["log", "file1", "-T", "description"])
-        .assert()
-        .success();
-    insta::assert_snapshot!(get_stdout_string(&assert), @r###"
-    @ (no description set)
-    ~ 
-    "###);
-    insta::assert_snapshot!(get_stderr_string(&assert), @"");
+    let cli_stdout = test_env.jj_cmd_success(&repo_path, &["log", "-T", "description"]);
 
-    // Warn for `jj log .` specifically, for former Mercurial users.
-    let assert = test_env
-        .jj_cmd(&repo_path, &["log", ".", "-T", "description"])
-        .assert()
-        .success();
-    insta::assert_snapshot!(get_stdout_string(&assert), @r###"
-    @ (no description set)
-    ~ 
+    // Render the same log by calling the library function directly
+    let settings = UserSettings::default();
+    let workspace = Workspace::load(&settings, &repo_path, &BackendFactories::default()).unwrap();
+    let repo = workspace
+        .repo_loader()
+        .load_at_head()
+        .resolve(&settings)
+        .unwrap();
+    let workspace_ctx = RevsetWorkspaceContext {
+        cwd: &repo_path,
+        workspace_id: workspace.workspace_id(),
+        workspace_root: workspace.workspace_root(),
+    };
+    let revset_expression = revset::parse(
+        &settings.default_revset(),
+        &RevsetAliasesMap::new(),
+        Some(&workspace_ctx),
+    )
+    .unwrap();
+    let revset = revset::optimize(revset_expression)
+        .evaluate(repo.as_repo_ref(), Some(&workspace_ctx))
+        .unwrap();
+    let template = jujutsu::template_parser::parse_commit_template(
+        repo.as_repo_ref(),
+        workspace.workspace_id(),
+        "description",
+    );
+    let mut output = vec![];
+    let warnings = write_log(
+        &repo,
+        workspace.workspace_id(),
+        &settings,
+        &mut output,
+        revset.as_ref(),
+        template.as_ref(),
+        &LogOptions::default(),
+    )
+    .unwrap();
+    assert!(warnings.is_empty());
+    let library_stdout = String::from_utf8(output).unwrap();
+    assert_eq!(library_stdout, cli_stdout);
+    insta::assert_snapshot!(library_stdout, @r###"
+    @ a new commit
+    o add a file
+    o (no description set)
     "###);
-    insta::assert_snapshot!(get_stderr_string(&assert), @r###"warning: The argument "." is being interpreted as a path, but this is often not useful because all non-empty commits touch '.'.  If you meant to show the working copy commit, pass -r '@' instead."###);
+}
 
-    // ...but checking `jj log .` makes sense in a subdirectory.
-    let subdir = repo_path.join("dir");
-    std::fs::create_dir_all(&subdir).unwrap();
-    let assert = test_env.jj_cmd(&subdir, &["log", "."]).assert().success();
-    insta::assert_snapshot!(get_stdout_string(&assert), @"");
-    insta::assert_snapshot!(get_stderr_string(&assert), @"");
+#[test]
+fn test_log_gutter_width() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
 
-    // Warn for `jj log @` instead of `jj log -r @`.
-    let assert = test_env
-        .jj_cmd(&repo_path, &["log", "@", "-T", "description"])
-        .assert()
-        .success();
-    insta::assert_snapshot!(get_stdout_string(&assert), @"");
-    insta::assert_snapshot!(get_stderr_string(&assert), @r###"
-    warning: The argument "@" is being interpreted as a path. To specify a revset, pass -r "@" instead.
-    "###);
+    test_env.jj_cmd_success(&repo_path, &["describe", "-m", "first"]);
+    test_env.jj_cmd_success(&repo_path, &["new", "-m", "second"]);
 
-    // Warn when there's no path with the provided name.
-    let assert = test_env
-        .jj_cmd(&repo_path, &["log", "file2", "-T", "description"])
-        .assert()
-        .success();
-    insta::assert_snapshot!(get_stdout_string(&assert), @"");
-    insta::assert_snapshot!(get_stderr_string(&assert), @r###"
-    warning: The argument "file2" is being interpreted as a path. To specify a revset, pass -r "file2" instead.
+    let default_stdout = test_env.jj_cmd_success(&repo_path, &["log", "-T", "description"]);
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "-T", "description", "--gutter-width", "3"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    @    second
+    o    first
+    o    (no description set)
     "###);
+    // The description starts 3 columns further right than by default
+    for (default_line, line) in default_stdout.lines().zip(stdout.lines()) {
+        assert_eq!(
+            line.find("second").or_else(|| line.find("first")),
+            default_line
+                .find("second")
+                .or_else(|| default_line.find("first"))
+                .map(|column| column + 3)
+        );
+    }
 
-    // If an explicit revision is provided, then suppress the warning.
-    let assert = test_env
-        .jj_cmd(&repo_path, &["log", "@", "-r", "@", "-T", "description"])
-        .assert()
-        .success();
-    insta::assert_snapshot!(get_stdout_string(&assert), @"");
-    insta::assert_snapshot!(get_stderr_string(&assert), @r###"
+    // The width can be set in the config, and the flag overrides it
+    test_env.add_config(br#"ui.graph.gutter-width = 2"#);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-T", "description"]);
+    insta::assert_snapshot!(stdout, @r###"
+    @   second
+    o   first
+    o   (no description set)
+    "###);
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "-T", "description", "--gutter-width", "0"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    @ second
+    o first
+    o (no description set)
     "###);
 }
 
 #[test]
-fn test_default_revset() {
+fn test_log_legend() {
     let test_env = TestEnvironment::default();
     test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
     let repo_path = test_env.env_root().join("repo");
 
-    std::fs::write(repo_path.join("file1"), "foo\n").unwrap();
-    test_env.jj_cmd_success(&repo_path, &["describe", "-m", "add a file"]);
+    test_env.jj_cmd_success(&repo_path, &["describe", "-m", "first"]);
+    test_env.jj_cmd_success(&repo_path, &["new", "-m", "second"]);
 
-    // Set configuration to only show the root commit.
-    test_env.add_config(
-        br#"[ui]
-        default-revset = "root"
-        "#,
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "-T", "description", "-r", "@", "--legend"],
     );
+    insta::assert_snapshot!(stdout, @r###"
+    Legend: @ working copy, o commit, ~ elided history
+    @ second
+    ~
+    "###);
 
-    // Log should only contain one line (for the root commit), and not show the
-    // commit created above.
-    assert_eq!(
-        1,
-        test_env
-            .jj_cmd_success(&repo_path, &["log", "-T", "commit_id"])
-            .lines()
-            .count()
+    // The legend reflects customized symbols
+    test_env.add_config(
+        br#"[ui.graph]
+    working-copy-symbol = "W"
+    commit-symbol = "*"
+    "#,
     );
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-T", "description", "--legend"]);
+    insta::assert_snapshot!(stdout, @r###"
+    Legend: W working copy, * commit, ~ elided history
+    W second
+    * first
+    * (no description set)
+    "###);
+
+    // The legend is about the graph, so it can't be combined with --no-graph
+    let stderr = test_env.jj_cmd_cli_error(&repo_path, &["log", "--legend", "--no-graph"]);
+    assert!(stderr.contains("cannot be used with"), "{stderr}");
 }
 
 #[test]
-fn test_log_author_timestamp() {
+fn test_log_graph_symbols() {
     let test_env = TestEnvironment::default();
     test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
     let repo_path = test_env.env_root().join("repo");
@@ -335,27 +518,2295 @@ fn test_log_author_timestamp() {
     test_env.jj_cmd_success(&repo_path, &["describe", "-m", "first"]);
     test_env.jj_cmd_success(&repo_path, &["new", "-m", "second"]);
 
-    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-T", "author.timestamp()"]);
+    test_env.add_config(
+        r#"[ui.graph]
+    working-copy-symbol = "◉"
+    commit-symbol = "○"
+    elided-symbol = "…"
+    "#
+        .as_bytes(),
+    );
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "-T", "description", "-r", "@", "--legend"],
+    );
     insta::assert_snapshot!(stdout, @r###"
-    @ 2001-02-03 04:05:09.000 +07:00
-    o 2001-02-03 04:05:07.000 +07:00
-    o 1970-01-01 00:00:00.000 +00:00
+    Legend: ◉ working copy, ○ commit, … elided history
+    ◉ second
+    …
+    "###);
+    // They're used by `jj obslog` too
+    test_env.jj_cmd_success(&repo_path, &["describe", "-m", "second v2"]);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["obslog", "-T", "description"]);
+    insta::assert_snapshot!(stdout, @r###"
+    ◉ second v2
+    ○ second
+    "###);
+
+    // Symbols that would misalign the graph are rejected
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &[
+            "log",
+            "-T",
+            "description",
+            "--config-toml",
+            "ui.graph.elided-symbol = '~~'",
+        ],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Config option "ui.graph.elided-symbol" must be a single character that is one column wide, not "~~"
+    "###);
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &[
+            "log",
+            "-T",
+            "description",
+            "--config-toml",
+            "ui.graph.commit-symbol = ''",
+        ],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Config option "ui.graph.commit-symbol" must be a single character that is one column wide, not ""
+    "###);
+    // Double-width characters would misalign it too
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &[
+            "log",
+            "-T",
+            "description",
+            "--config-toml",
+            "ui.graph.commit-char = '木'",
+        ],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Config option "ui.graph.commit-char" must be a single character that is one column wide, not "木"
     "###);
 }
 
 #[test]
-fn test_log_author_timestamp_ago() {
+fn test_log_graph_chars() {
     let test_env = TestEnvironment::default();
     test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
     let repo_path = test_env.env_root().join("repo");
-
     test_env.jj_cmd_success(&repo_path, &["describe", "-m", "first"]);
     test_env.jj_cmd_success(&repo_path, &["new", "-m", "second"]);
 
-    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-T", "author.timestamp().ago()"]);
-    let line_re = Regex::new(r"@|o [0-9]+ years ago").unwrap();
-    assert!(
-        stdout.lines().all(|x| line_re.is_match(x)),
-        "expected every line to match regex"
+    // The `*-char` keys are aliases of the `*-symbol` keys
+    test_env.add_config(
+        br#"[ui.graph]
+    working-copy-char = "W"
+    commit-char = "*"
+    elided-char = ":"
+    "#,
+    );
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-T", "description", "-r", "@-"]);
+    insta::assert_snapshot!(stdout, @r###"
+    * first
+    :
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-T", "description"]);
+    insta::assert_snapshot!(stdout, @r###"
+    W second
+    * first
+    * (no description set)
+    "###);
+}
+
+#[test]
+fn test_log_node_template() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file1"), "a\n").unwrap();
+    test_env.jj_cmd_success(&repo_path, &["describe", "-m", "a"]);
+    test_env.jj_cmd_success(&repo_path, &["new", "-m", "b"]);
+    std::fs::write(repo_path.join("file1"), "b\n").unwrap();
+    test_env.jj_cmd_success(&repo_path, &["new", "-m", "c"]);
+    std::fs::write(repo_path.join("file1"), "c\n").unwrap();
+    // Make "c" conflict by moving it next to "b"
+    test_env.jj_cmd_success(&repo_path, &["rebase", "-r", "@", "-d", "@--"]);
+    test_env.jj_cmd_success(&repo_path, &["new", "-m", "d"]);
+
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "-T",
+            "description",
+            "-r",
+            ":@",
+            "--node-template",
+            r#"if(current_working_copy, "*", if(conflict, "!", "o"))"#,
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    * d
+    ! c
+    o a
+    o (no description set)
+    "###);
+
+    // The template can be set in the config
+    test_env.add_config(br#"ui.graph.node-template = 'if(conflict, "!", "o")'"#);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-T", "description", "-r", ":@"]);
+    insta::assert_snapshot!(stdout, @r###"
+    ! d
+    ! c
+    o a
+    o (no description set)
+    "###);
+
+    // Falls back to the default symbol if the result isn't a single character
+    let commit_id = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "--no-graph", "-r", "@-", "-T", "commit_id"],
+    );
+    let assert = test_env
+        .jj_cmd(
+            &repo_path,
+            &[
+                "log",
+                "-T",
+                "description",
+                "-r",
+                "@-",
+                "--node-template",
+                "description",
+            ],
+        )
+        .assert()
+        .success();
+    insta::assert_snapshot!(get_stdout_string(&assert), @r###"
+    o c
+    ~
+    "###);
+    assert_eq!(
+        get_stderr_string(&assert),
+        format!(
+            "warning: Node template produced \"c\\n\" for commit {}, which is not a single \
+             character; using \"o\" instead\n",
+            &commit_id[..12]
+        )
+    );
+
+    // The warning is only printed once even if several commits fall back
+    let assert = test_env
+        .jj_cmd(
+            &repo_path,
+            &[
+                "log",
+                "-T",
+                "description",
+                "-r",
+                ":@",
+                "--node-template",
+                "description",
+            ],
+        )
+        .assert()
+        .success();
+    insta::assert_snapshot!(get_stdout_string(&assert), @r###"
+    @ d
+    o c
+    o a
+    o (no description set)
+    "###);
+    assert_eq!(get_stderr_string(&assert).lines().count(), 1);
+}
+
+#[test]
+fn test_log_hide_working_copy_if_empty() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_success(&repo_path, &["describe", "-m", "first"]);
+    test_env.jj_cmd_success(&repo_path, &["new"]);
+
+    // The empty working-copy commit is left out, and its parent is shown as the
+    // working copy
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "-T", "description", "--hide-working-copy-if-empty"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    @ first
+    o (no description set)
+    "###);
+
+    // Not if it has changes
+    std::fs::write(repo_path.join("file1"), "foo\n").unwrap();
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "-T", "description", "--hide-working-copy-if-empty"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    @ (no description set)
+    o first
+    o (no description set)
+    "###);
+
+    // Or a description
+    std::fs::remove_file(repo_path.join("file1")).unwrap();
+    test_env.jj_cmd_success(&repo_path, &["describe", "-m", "second"]);
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "-T", "description", "--hide-working-copy-if-empty"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    @ second
+    o first
+    o (no description set)
+    "###);
+
+    // Can be enabled in the config
+    test_env.jj_cmd_success(&repo_path, &["new"]);
+    test_env.add_config(br#"ui.log-hide-empty-working-copy = true"#);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-T", "description", "--reversed"]);
+    insta::assert_snapshot!(stdout, @r###"
+    o (no description set)
+    o first
+    @ second
+    "###);
+}
+
+#[test]
+fn test_log_edge_style() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_success(&repo_path, &["describe", "-m", "a"]);
+    test_env.jj_cmd_success(&repo_path, &["branch", "create", "a"]);
+    test_env.jj_cmd_success(&repo_path, &["new", "-m", "b"]);
+    test_env.jj_cmd_success(&repo_path, &["branch", "create", "b"]);
+    test_env.jj_cmd_success(&repo_path, &["new", "-m", "c"]);
+    test_env.jj_cmd_success(&repo_path, &["rebase", "-r", "@", "-d", "a"]);
+    test_env.jj_cmd_success(&repo_path, &["new", "-m", "d", "@", "b"]);
+
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-T", "description"]);
+    insta::assert_snapshot!(stdout, @r###"
+    @   d
+    |\  
+    o | c
+    | o b
+    |/  
+    o a
+    o (no description set)
+    "###);
+
+    // Only the edge from the rebased commit to its new parent is dashed, not
+    // the ones from the merge
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "-T", "description", "--edge-style", "rebase"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    @   d
+    |\  
+    o | c
+    ' o b
+    '/  
+    o a
+    o (no description set)
+    "###);
+}
+
+#[test]
+fn test_log_graph_style() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_success(&repo_path, &["describe", "-m", "a"]);
+    test_env.jj_cmd_success(&repo_path, &["new", "-m", "b"]);
+    test_env.jj_cmd_success(&repo_path, &["new", "-m", "c", "description(a)"]);
+    test_env.jj_cmd_success(&repo_path, &["new", "-m", "d", "@", "description(b)"]);
+
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "-T", "description", "--graph-style", "ascii"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    @   d
+    |\  
+    o | c
+    | o b
+    |/  
+    o a
+    o (no description set)
+    "###);
+
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "-T", "description", "--graph-style", "curved"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    @   d
+    ├─╮ 
+    o │ c
+    │ o b
+    ├─╯ 
+    o a
+    o (no description set)
+    "###);
+
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "-T", "description", "--graph-style", "square"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    @   d
+    ├─┐ 
+    o │ c
+    │ o b
+    ├─┘ 
+    o a
+    o (no description set)
+    "###);
+
+    // The style can be configured, and the option overrides the config
+    test_env.add_config(br#"ui.graph.style = "curved""#);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-T", "description"]);
+    insta::assert_snapshot!(stdout, @r###"
+    @   d
+    ├─╮ 
+    o │ c
+    │ o b
+    ├─╯ 
+    o a
+    o (no description set)
+    "###);
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "-T", "description", "--graph-style", "ascii"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    @   d
+    |\  
+    o | c
+    | o b
+    |/  
+    o a
+    o (no description set)
+    "###);
+
+    // An unknown style is an error
+    test_env.add_config(br#"ui.graph.style = "fancy""#);
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["log", "-T", "description"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Config option "ui.graph.style" must be one of ascii, curved, square, not "fancy"
+    "###);
+}
+
+#[test]
+fn test_log_color_by_author() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_success(&repo_path, &["describe", "-m", "first"]);
+    test_env
+        .jj_cmd(&repo_path, &["new", "-m", "second"])
+        .env("JJ_USER", "Other User")
+        .env("JJ_EMAIL", "other.user@example.com")
+        .assert()
+        .success();
+    test_env.jj_cmd_success(&repo_path, &["new", "-m", "third"]);
+    test_env.jj_cmd_success(&repo_path, &["new"]);
+
+    // Commits by the same author get the same color
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "--color=always",
+            "log",
+            "-T",
+            "description",
+            "-r",
+            "all() ~ root ~ @",
+            "--color-by-author",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    [32mo[0m third
+    [34mo[0m second
+    [32mo[0m first
+    ~ 
+    "###);
+
+    // Without color, the option has no effect
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "-T",
+            "description",
+            "-r",
+            "all() ~ root ~ @",
+            "--color-by-author",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    o third
+    o second
+    o first
+    ~ 
+    "###);
+}
+
+#[test]
+fn test_log_group_by_author() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_success(&repo_path, &["describe", "-m", "first"]);
+    test_env
+        .jj_cmd(&repo_path, &["new", "-m", "second"])
+        .env("JJ_USER", "Other User")
+        .env("JJ_EMAIL", "other.user@example.com")
+        .assert()
+        .success();
+    test_env.jj_cmd_success(&repo_path, &["new", "-m", "third"]);
+
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "-T",
+            r#"description.first_line() " " author.timestamp() "\n""#,
+            "-r",
+            "all() ~ root",
+            "--no-graph",
+            "--group-by-author",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    Other User <other.user@example.com>
+    second 2001-02-03 04:05:09.000 +07:00
+
+    Test User <test.user@example.com>
+    third 2001-02-03 04:05:10.000 +07:00
+    first 2001-02-03 04:05:07.000 +07:00
+    "###);
+
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "-T",
+            "description",
+            "-r",
+            "all() ~ root",
+            "--no-graph",
+            "--group-by-author",
+            "--reversed",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    Other User <other.user@example.com>
+    second
+
+    Test User <test.user@example.com>
+    first
+    third
+    "###);
+
+    // Grouping doesn't work with the graph
+    let stderr = test_env.jj_cmd_cli_error(&repo_path, &["log", "--group-by-author"]);
+    assert!(stderr.contains("--no-graph"), "{stderr}");
+}
+
+#[test]
+fn test_log_since_divergence() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_success(&repo_path, &["describe", "-m", "first"]);
+    test_env.jj_cmd_success(&repo_path, &["new", "-m", "second"]);
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["log", "--since-divergence", "@"]);
+    assert!(stderr.starts_with("Error: Change "), "{stderr}");
+    assert!(stderr.ends_with(" is not divergent\n"), "{stderr}");
+
+    // Make the change divergent by rewriting it in two concurrent operations
+    test_env.jj_cmd_success(&repo_path, &["describe", "-m", "second v1"]);
+    test_env.jj_cmd_success(
+        &repo_path,
+        &["describe", "-m", "second v2", "--at-op", "@-"],
+    );
+    test_env.jj_cmd_success(&repo_path, &["log", "-T", "description"]);
+    let change_id = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "--no-graph", "-r", "@", "-T", "change_id"],
+    );
+    let fork_point = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "--no-graph", "-r", "@-", "-T", "commit_id"],
+    );
+
+    // Both versions are shown, down to the commit they split from
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "-T", "description", "--since-divergence", "@"],
+    );
+    let (header, graph) = stdout.split_once('\n').unwrap();
+    assert_eq!(
+        header,
+        format!(
+            "Change {} has 2 versions, which split at commit {}",
+            &change_id[..12],
+            &fork_point[..12]
+        )
+    );
+    insta::assert_snapshot!(graph, @r###"
+    o second v2
+    | @ second v1
+    |/  
+    o first
+    ~
+    "###);
+
+    // The change can also be given by its id, which resolves to all versions
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "-T", "description", "--since-divergence", &change_id],
+    );
+    assert_eq!(stdout, format!("{header}\n{graph}"));
+}
+
+#[test]
+fn test_log_since() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_success(&repo_path, &["describe", "-m", "base"]);
+    test_env.jj_cmd_success(&repo_path, &["new", "-m", "a"]);
+    test_env.jj_cmd_success(&repo_path, &["new", "-m", "b"]);
+    test_env.jj_cmd_success(&repo_path, &["new", "@--", "-m", "c"]);
+    test_env.jj_cmd_success(&repo_path, &["branch", "create", "main"]);
+
+    // Only the commits that aren't in main are shown, down to where they forked
+    let stdout =
+        test_env.jj_cmd_success(&repo_path, &["log", "-T", "description", "--since", "main"]);
+    insta::assert_snapshot!(stdout, @r###"
+    o b
+    o a
+    o base
+    ~
+    "###);
+
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-T", "description", "--since", "b"]);
+    insta::assert_snapshot!(stdout, @r###"
+    @ c
+    o base
+    ~
+    "###);
+
+    // It can't be combined with -r
+    let stderr = test_env.jj_cmd_cli_error(&repo_path, &["log", "--since", "main", "-r", "@"]);
+    assert!(stderr.contains("cannot be used with"), "{stderr}");
+}
+
+#[test]
+fn test_log_oldest_ancestor() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_success(&repo_path, &["describe", "-m", "base"]);
+    test_env.jj_cmd_success(&repo_path, &["new", "-m", "a"]);
+    test_env.jj_cmd_success(&repo_path, &["new", "-m", "b"]);
+    test_env.jj_cmd_success(&repo_path, &["new", "-m", "c"]);
+
+    // The walk stops before the boundary, which is marked as elided
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "-T", "description", "--oldest-ancestor", "@--"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    @ c
+    o b
+    ~
+    "###);
+
+    // It also applies to an explicit revset
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "-T",
+            "description",
+            "-r",
+            "all()",
+            "--oldest-ancestor",
+            "@---",
+            "--no-graph",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    c
+    b
+    a
+    "###);
+}
+
+#[test]
+fn test_log_limit() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_success(&repo_path, &["describe", "-m", "first"]);
+    test_env.jj_cmd_success(&repo_path, &["new", "-m", "second"]);
+    test_env.jj_cmd_success(&repo_path, &["new", "-m", "third"]);
+    test_env.jj_cmd_success(&repo_path, &["new", "-m", "fourth"]);
+
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-T", "description", "--limit", "2"]);
+    insta::assert_snapshot!(stdout, @r###"
+    @ fourth
+    o third
+    "###);
+
+    // The newest commits are shown, oldest first
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "-T", "description", "--limit", "2", "--reversed"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    o third
+    @ fourth
+    "###);
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "-T", "description", "--reversed", "--limit", "1"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    @ fourth
+    "###);
+
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "-T", "description", "--limit", "2", "--no-graph"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    fourth
+    third
+    "###);
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "-T",
+            "description",
+            "--limit",
+            "2",
+            "--no-graph",
+            "--reversed",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    third
+    fourth
+    "###);
+
+    // Commits are counted, not lines
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "-T", r#"description "more\n""#, "--limit", "2"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    @ fourth
+    | more
+    o third
+    | more
+    "###);
+
+    // History that's only cut off by the limit isn't marked as elided
+    test_env.jj_cmd_success(&repo_path, &["new", "@---", "-m", "side"]);
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "-T",
+            "description",
+            "-r",
+            "all() ~ first ~ root",
+            "--limit",
+            "1",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    @ side
+    "###);
+
+    // Neither are edges to revisions that were cut off
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-T", "description", "--limit", "2"]);
+    insta::assert_snapshot!(stdout, @r###"
+    @ side
+    o fourth
+    "###);
+}
+
+#[test]
+fn test_log_topo_groups() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    // Two branches whose commits were created alternately
+    test_env.jj_cmd_success(&repo_path, &["describe", "-m", "a1"]);
+    test_env.jj_cmd_success(&repo_path, &["new", "root", "-m", "b1"]);
+    test_env.jj_cmd_success(&repo_path, &["new", "description(a1)", "-m", "a2"]);
+    test_env.jj_cmd_success(&repo_path, &["new", "description(b1)", "-m", "b2"]);
+
+    let descriptions = |stdout: &str| {
+        stdout
+            .lines()
+            .filter_map(|line| line.split(' ').last())
+            .filter(|word| matches!(*word, "a1" | "a2" | "b1" | "b2"))
+            .map(|word| word.to_string())
+            .collect::<Vec<_>>()
+    };
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-T", "description"]);
+    assert_eq!(descriptions(&stdout), ["b2", "a2", "b1", "a1"]);
+
+    let stdout =
+        test_env.jj_cmd_success(&repo_path, &["log", "-T", "description", "--topo-groups"]);
+    assert_eq!(descriptions(&stdout), ["b2", "b1", "a2", "a1"]);
+    insta::assert_snapshot!(stdout, @r###"
+    @ b2
+    o b1
+    | o a2
+    | o a1
+    |/
+    o (no description set)
+    "###);
+
+    // Reversing keeps the branches grouped
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "-T", "description", "--topo-groups", "--reversed"],
+    );
+    assert_eq!(descriptions(&stdout), ["a1", "a2", "b1", "b2"]);
+}
+
+#[test]
+fn test_log_collapse() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_success(&repo_path, &["describe", "-m", "base"]);
+    test_env.jj_cmd_success(&repo_path, &["new", "-m", "side 1"]);
+    test_env.jj_cmd_success(&repo_path, &["new", "-m", "side 2"]);
+    test_env.jj_cmd_success(&repo_path, &["new", "-m", "side 3"]);
+    test_env.jj_cmd_success(&repo_path, &["branch", "create", "side"]);
+    test_env.jj_cmd_success(&repo_path, &["new", "@---", "-m", "main"]);
+
+    // The side branch becomes a single node, still forking off from base
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "-T", "description", "--collapse", "base..side"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    @ main
+    | + (3 commits)
+    |/
+    o base
+    o (no description set)
+    "###);
+
+    // Commits that aren't connected are collapsed separately
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "-T", "description", "--collapse", "side | @ | root"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    + (1 commit)
+    | + (1 commit)
+    | o side 2
+    | o side 1
+    |/
+    o base
+    + (1 commit)
+    "###);
+
+    // It can't be combined with --no-graph
+    let stderr =
+        test_env.jj_cmd_cli_error(&repo_path, &["log", "--no-graph", "--collapse", "side"]);
+    assert!(stderr.contains("cannot be used with"), "{stderr}");
+}
+
+#[test]
+fn test_log_collapse_obsolete() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_success(&repo_path, &["describe", "-m", "base"]);
+    test_env.jj_cmd_success(&repo_path, &["new", "-m", "first"]);
+    test_env.jj_cmd_success(&repo_path, &["describe", "-m", "second"]);
+    test_env.jj_cmd_success(&repo_path, &["describe", "-m", "third"]);
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["obslog", "--no-graph", "-T", r#"commit_id "\n""#],
+    );
+    let predecessors = stdout.lines().skip(1).collect::<Vec<_>>();
+    assert_eq!(predecessors.len(), 2, "{stdout}");
+    let revset = format!(":@ | {}", predecessors.join(" | "));
+
+    // The obsolete predecessors are shown when asked for
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-T", "description", "-r", &revset]);
+    assert!(stdout.contains("first"), "{stdout}");
+    assert!(stdout.contains("second"), "{stdout}");
+
+    // They become a single node next to the amended commit
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "-T",
+            "description",
+            "-r",
+            &revset,
+            "--collapse-obsolete",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    @ third
+    | + (2 obsolete commits)
+    |/
+    o base
+    o (no description set)
+    "###);
+
+    // Without hidden commits, nothing changes
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "-T", "description", "--collapse-obsolete"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    @ third
+    o base
+    o (no description set)
+    "###);
+}
+
+#[test]
+fn test_log_relative_to() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_success(&repo_path, &["describe", "-m", "base"]);
+    test_env.jj_cmd_success(&repo_path, &["new", "-m", "main 1"]);
+    test_env.jj_cmd_success(&repo_path, &["new", "-m", "main 2"]);
+    test_env.jj_cmd_success(&repo_path, &["branch", "create", "main"]);
+    test_env.jj_cmd_success(&repo_path, &["new", "@-", "-m", "feature 1"]);
+    test_env.jj_cmd_success(&repo_path, &["new", "-m", "feature 2"]);
+
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "-T", "description", "--relative-to", "main"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    @ [ahead] feature 2
+    o [ahead] feature 1
+    | o [on] main 2
+    |/
+    o [behind] main 1
+    o [behind] base
+    o [behind] (no description set)
+    "###);
+}
+
+#[test]
+fn test_log_reversed() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_success(&repo_path, &["describe", "-m", "first"]);
+    test_env.jj_cmd_success(&repo_path, &["new", "-m", "second"]);
+
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-T", "description", "--reversed"]);
+    insta::assert_snapshot!(stdout, @r###"
+    o (no description set)
+    o first
+    @ second
+    "###);
+
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "-T", "description", "--reversed", "--no-graph"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    (no description set)
+    first
+    second
+    "###);
+}
+
+#[test]
+fn test_log_reversed_with_diff() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file1"), "foo\n").unwrap();
+    test_env.jj_cmd_success(&repo_path, &["describe", "-m", "add a file"]);
+    test_env.jj_cmd_success(&repo_path, &["new", "-m", "modify a file"]);
+    std::fs::write(repo_path.join("file1"), "foo\nbar\n").unwrap();
+
+    // Each commit still shows its own diff when the order is reversed
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "-T", "description", "--reversed", "-s"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    o (no description set)
+    o add a file
+    | A file1
+    @ modify a file
+      M file1
+    "###);
+
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "-T", "description", "--reversed", "--no-graph", "-s"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    (no description set)
+    add a file
+    A file1
+    modify a file
+    M file1
+    "###);
+}
+
+#[test]
+fn test_log_filtered_by_path() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file1"), "foo\n").unwrap();
+    test_env.jj_cmd_success(&repo_path, &["describe", "-m", "first"]);
+    test_env.jj_cmd_success(&repo_path, &["new", "-m", "second"]);
+    std::fs::write(repo_path.join("file1"), "foo\nbar\n").unwrap();
+    std::fs::write(repo_path.join("file2"), "baz\n").unwrap();
+
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-T", "description", "file1"]);
+    insta::assert_snapshot!(stdout, @r###"
+    @ second
+    o first
+    ~ 
+    "###);
+
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-T", "description", "file2"]);
+    insta::assert_snapshot!(stdout, @r###"
+    @ second
+    ~ 
+    "###);
+
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-T", "description", "-s", "file1"]);
+    insta::assert_snapshot!(stdout, @r###"
+    @ second
+    | M file1
+    o first
+    ~ A file1
+    "###);
+
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "-T", "description", "-s", "file2", "--no-graph"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    second
+    A file2
+    "###);
+
+    // file() revset doesn't filter the diff.
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "-T",
+            "description",
+            "-s",
+            "-rfile(file2)",
+            "--no-graph",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    second
+    M file1
+    A file2
+    "###);
+}
+
+#[test]
+fn test_log_warn_path_might_be_revset() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file1"), "foo\n").unwrap();
+
+    // Don't warn if the file actually exists.
+    let assert = test_env
+        .jj_cmd(&repo_path, &["log", "file1", "-T", "description"])
+        .assert()
+        .success();
+    insta::assert_snapshot!(get_stdout_string(&assert), @r###"
+    @ (no description set)
+    ~ 
+    "###);
+    insta::assert_snapshot!(get_stderr_string(&assert), @"");
+
+    // Warn for `jj log .` specifically, for former Mercurial users.
+    let assert = test_env
+        .jj_cmd(&repo_path, &["log", ".", "-T", "description"])
+        .assert()
+        .success();
+    insta::assert_snapshot!(get_stdout_string(&assert), @r###"
+    @ (no description set)
+    ~ 
+    "###);
+    insta::assert_snapshot!(get_stderr_string(&assert), @r###"warning: The argument "." is being interpreted as a path, but this is often not useful because all non-empty commits touch '.'.  If you meant to show the working copy commit, pass -r '@' instead."###);
+
+    // ...but checking `jj log .` makes sense in a subdirectory.
+    let subdir = repo_path.join("dir");
+    std::fs::create_dir_all(&subdir).unwrap();
+    let assert = test_env.jj_cmd(&subdir, &["log", "."]).assert().success();
+    insta::assert_snapshot!(get_stdout_string(&assert), @"");
+    insta::assert_snapshot!(get_stderr_string(&assert), @"");
+
+    // Warn for `jj log @` instead of `jj log -r @`.
+    let assert = test_env
+        .jj_cmd(&repo_path, &["log", "@", "-T", "description"])
+        .assert()
+        .success();
+    insta::assert_snapshot!(get_stdout_string(&assert), @"");
+    insta::assert_snapshot!(get_stderr_string(&assert), @r###"
+    warning: The argument "@" is being interpreted as a path. To specify a revset, pass -r "@" instead.
+    "###);
+
+    // Warn when there's no path with the provided name.
+    let assert = test_env
+        .jj_cmd(&repo_path, &["log", "file2", "-T", "description"])
+        .assert()
+        .success();
+    insta::assert_snapshot!(get_stdout_string(&assert), @"");
+    insta::assert_snapshot!(get_stderr_string(&assert), @r###"
+    warning: The argument "file2" is being interpreted as a path. To specify a revset, pass -r "file2" instead.
+    "###);
+
+    // Suggest the jj revision for common Git revision spellings.
+    let assert = test_env
+        .jj_cmd(&repo_path, &["log", "HEAD", "-T", "description"])
+        .assert()
+        .success();
+    insta::assert_snapshot!(get_stdout_string(&assert), @"");
+    insta::assert_snapshot!(get_stderr_string(&assert), @r###"
+    warning: The argument "HEAD" is being interpreted as a path, but it looks like a Git revision. In jj, pass -r "@" instead.
+    "###);
+    let assert = test_env
+        .jj_cmd(&repo_path, &["log", "HEAD~1", "-T", "description"])
+        .assert()
+        .success();
+    insta::assert_snapshot!(get_stdout_string(&assert), @"");
+    insta::assert_snapshot!(get_stderr_string(&assert), @r###"
+    warning: The argument "HEAD~1" is being interpreted as a path, but it looks like a Git revision. In jj, pass -r "@-" instead.
+    "###);
+    let assert = test_env
+        .jj_cmd(&repo_path, &["log", "@^", "-T", "description"])
+        .assert()
+        .success();
+    insta::assert_snapshot!(get_stdout_string(&assert), @"");
+    insta::assert_snapshot!(get_stderr_string(&assert), @r###"
+    warning: The argument "@^" is being interpreted as a path, but it looks like a Git revision. In jj, pass -r "@-" instead.
+    "###);
+    let assert = test_env
+        .jj_cmd(&repo_path, &["log", "@~2", "-T", "description"])
+        .assert()
+        .success();
+    insta::assert_snapshot!(get_stdout_string(&assert), @"");
+    insta::assert_snapshot!(get_stderr_string(&assert), @r###"
+    warning: The argument "@~2" is being interpreted as a path, but it looks like a Git revision. In jj, pass -r "@--" instead.
+    "###);
+
+    // If an explicit revision is provided, then suppress the warning.
+    let assert = test_env
+        .jj_cmd(&repo_path, &["log", "@", "-r", "@", "-T", "description"])
+        .assert()
+        .success();
+    insta::assert_snapshot!(get_stdout_string(&assert), @"");
+    insta::assert_snapshot!(get_stderr_string(&assert), @r###"
+    "###);
+    let assert = test_env
+        .jj_cmd(
+            &repo_path,
+            &["log", "HEAD~1", "-r", "@", "-T", "description"],
+        )
+        .assert()
+        .success();
+    insta::assert_snapshot!(get_stdout_string(&assert), @"");
+    insta::assert_snapshot!(get_stderr_string(&assert), @"");
+
+    // The warnings can be turned off
+    test_env.add_config(br#"ui.warn-path-revset = false"#);
+    for path in [".", "@", "file2", "HEAD~1"] {
+        let assert = test_env
+            .jj_cmd(&repo_path, &["log", path, "-T", "description"])
+            .assert()
+            .success();
+        insta::assert_snapshot!(get_stderr_string(&assert), @"");
+    }
+}
+
+#[test]
+fn test_default_revset() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file1"), "foo\n").unwrap();
+    test_env.jj_cmd_success(&repo_path, &["describe", "-m", "add a file"]);
+
+    // Set configuration to only show the root commit.
+    test_env.add_config(
+        br#"[ui]
+        default-revset = "root"
+        "#,
+    );
+
+    // Log should only contain one line (for the root commit), and not show the
+    // commit created above.
+    assert_eq!(
+        1,
+        test_env
+            .jj_cmd_success(&repo_path, &["log", "-T", "commit_id"])
+            .lines()
+            .count()
+    );
+}
+
+#[test]
+fn test_default_revset_symbols() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.jj_cmd_success(&repo_path, &["describe", "-m", "first"]);
+    test_env.jj_cmd_success(&repo_path, &["new", "-m", "second"]);
+
+    // `root`, `@`, and expressions built from them work as the default revset
+    // just like they do on the command line
+    test_env.add_config(
+        br#"[ui]
+        default-revset = "@-"
+        "#,
+    );
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-T", "description", "--no-graph"]);
+    insta::assert_snapshot!(stdout, @r###"
+    first
+    "###);
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "-T", "description", "-r", "root | @", "--no-graph"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    second
+    (no description set)
+    "###);
+}
+
+#[test]
+fn test_default_revset_invalid() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.add_config(
+        br#"[ui]
+        default-revset = "@ |"
+        "#,
+    );
+    // The error points at the config option instead of the command line
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["log"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Invalid `ui.default-revset` "@ |":  --> 1:4
+      |
+    1 | @ |
+      |    ^---
+      |
+      = expected dag_range_pre_op, range_pre_op, negate_op, or primary
+    Hint: Fix the `ui.default-revset` setting in $TEST_ENV/config/config0001.toml.
+    "###);
+    // It's reported even if the default isn't used
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["log", "-r", "@"]);
+    insta::assert_snapshot!(stderr.lines().next().unwrap(), @r###"Error: Invalid `ui.default-revset` "@ |":  --> 1:4"###);
+}
+
+#[test]
+fn test_default_revset_env_override() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.jj_cmd_success(&repo_path, &["describe", "-m", "first"]);
+
+    // The environment variable takes precedence over a broken config
+    test_env.add_config(
+        br#"[ui]
+        default-revset = "@ |"
+        "#,
+    );
+    let assert = test_env
+        .jj_cmd(&repo_path, &["log", "-T", "description", "--no-graph"])
+        .env("JJ_DEFAULT_REVSET", "@")
+        .assert()
+        .success()
+        .stderr("");
+    insta::assert_snapshot!(get_stdout_string(&assert), @r###"
+    first
+    "###);
+
+    // An invalid value is reported with a hint about the variable
+    let assert = test_env
+        .jj_cmd(&repo_path, &["log"])
+        .env("JJ_DEFAULT_REVSET", "@ &")
+        .assert()
+        .code(1);
+    insta::assert_snapshot!(get_stderr_string(&assert), @r###"
+    Error: Invalid `ui.default-revset` "@ &":  --> 1:4
+      |
+    1 | @ &
+      |    ^---
+      |
+      = expected dag_range_pre_op, range_pre_op, negate_op, or primary
+    Hint: The setting comes from the JJ_DEFAULT_REVSET environment variable. Fix or unset it.
+    "###);
+
+    // --config-toml takes precedence over the variable, so the hint points there
+    let assert = test_env
+        .jj_cmd(
+            &repo_path,
+            &["log", "--config-toml", "ui.default-revset = '@ &'"],
+        )
+        .env("JJ_DEFAULT_REVSET", "@")
+        .assert()
+        .code(1);
+    insta::assert_snapshot!(get_stderr_string(&assert), @r###"
+    Error: Invalid `ui.default-revset` "@ &":  --> 1:4
+      |
+    1 | @ &
+      |    ^---
+      |
+      = expected dag_range_pre_op, range_pre_op, negate_op, or primary
+    Hint: The setting comes from a --config-toml argument.
+    "###);
+}
+
+#[test]
+fn test_log_multiple_templates() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.jj_cmd_success(&repo_path, &["describe", "-m", "first"]);
+
+    // The outputs are concatenated in order
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "-T",
+            "description.first_line()",
+            "-T",
+            r#"" by ""#,
+            "-T",
+            "author.email()",
+            "-T",
+            r#""\n""#,
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    @ first by test.user@example.com
+    o  by 
+    "###);
+
+    // Templates from the default arguments are concatenated too, but can't be
+    // mixed with ones given on the command line
+    let assert = test_env
+        .jj_cmd(&repo_path, &["log", "-r", "@"])
+        .env(
+            "JJ_LOG_DEFAULT_ARGS",
+            r#"-T description.first_line() -T '"!\n"'"#,
+        )
+        .assert()
+        .success();
+    insta::assert_snapshot!(get_stdout_string(&assert), @r###"
+    @ first!
+    ~
+    "###);
+    let assert = test_env
+        .jj_cmd(
+            &repo_path,
+            &["log", "-r", "@", "-T", "author.email()", "-T", r#""\n""#],
+        )
+        .env(
+            "JJ_LOG_DEFAULT_ARGS",
+            r#"-T description.first_line() -T '"!\n"'"#,
+        )
+        .assert()
+        .code(1);
+    insta::assert_snapshot!(get_stderr_string(&assert), @r###"
+    Error: Templates were given both on the command line and in the JJ_LOG_DEFAULT_ARGS environment variable (`-T 'description.first_line()' -T '"!\n"'`)
+    Hint: Give the templates in only one of them.
+    "###);
+    test_env.add_config(
+        br#"[ui]
+        log-default-args = ["-T", "description"]
+        "#,
+    );
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["log", "-T", "commit_id"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Templates were given both on the command line and in the ui.log-default-args config (`-T description`)
+    Hint: Give the templates in only one of them.
+    "###);
+}
+
+#[test]
+fn test_log_default_args() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.jj_cmd_success(&repo_path, &["describe", "-m", "first"]);
+
+    // The template from the environment is used when none is given
+    let assert = test_env
+        .jj_cmd(&repo_path, &["log"])
+        .env("JJ_LOG_DEFAULT_ARGS", "-T description --no-graph")
+        .assert()
+        .success();
+    insta::assert_snapshot!(get_stdout_string(&assert), @r###"
+    first
+    "###);
+
+    // Arguments on the command line override it
+    let assert = test_env
+        .jj_cmd(&repo_path, &["log", "-r", "@"])
+        .env("JJ_LOG_DEFAULT_ARGS", "-T description --no-graph -r root")
+        .assert()
+        .success();
+    insta::assert_snapshot!(get_stdout_string(&assert), @r###"
+    first
+    "###);
+
+    // Arguments in the string are split like in a shell, and boolean flags can
+    // be turned off again
+    let assert = test_env
+        .jj_cmd(&repo_path, &["log", "--graph", "--no-reversed"])
+        .env(
+            "JJ_LOG_DEFAULT_ARGS",
+            r#"-T 'description.first_line() " <\n"' --no-graph --reversed"#,
+        )
+        .assert()
+        .success();
+    insta::assert_snapshot!(get_stdout_string(&assert), @r###"
+    @ first <
+    o  <
+    "###);
+
+    // The config is used too
+    test_env.add_config(
+        br#"[ui]
+        log-default-args = ["-T", "description", "-r", "@"]
+        "#,
+    );
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log"]);
+    insta::assert_snapshot!(stdout, @r###"
+    @ first
+    ~
+    "###);
+}
+
+#[test]
+fn test_log_description_first_line() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.jj_cmd_success(
+        &repo_path,
+        &["describe", "-m", "subject line\n\nbody of the\ndescription"],
+    );
+    test_env.jj_cmd_success(&repo_path, &["new"]);
+
+    // Only the first line is shown, and empty descriptions yield an empty
+    // string rather than the placeholder
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "-T", r#""<" description.first_line() ">\n""#],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    @ <>
+    o <subject line>
+    o <>
+    "###);
+
+    // The placeholder is still used for the bare keyword
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-r", "@", "-T", "description"]);
+    insta::assert_snapshot!(stdout, @r###"
+    @ (no description set)
+    ~
+    "###);
+}
+
+#[test]
+fn test_log_author_timestamp() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_success(&repo_path, &["describe", "-m", "first"]);
+    test_env.jj_cmd_success(&repo_path, &["new", "-m", "second"]);
+
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-T", "author.timestamp()"]);
+    insta::assert_snapshot!(stdout, @r###"
+    @ 2001-02-03 04:05:09.000 +07:00
+    o 2001-02-03 04:05:07.000 +07:00
+    o 1970-01-01 00:00:00.000 +00:00
+    "###);
+}
+
+#[test]
+fn test_log_author_column() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_success(&repo_path, &["describe", "-m", "first"]);
+    test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "new",
+            "-m",
+            "second",
+            "--config-toml",
+            r#"user.name="Ann"
+            user.email="ann@example.com""#,
+        ],
+    );
+
+    // The authors are aligned, and cut off if they're too long
+    let template = r#"description.first_line() "|""#;
+    let stdout =
+        test_env.jj_cmd_success(&repo_path, &["log", "-T", template, "--author-column=25"]);
+    insta::assert_snapshot!(stdout, @r###"
+    @ Ann <ann@example.com>     second|
+    o Test User <test.user@exa… first|
+    o                           |
+    "###);
+
+    // The width defaults to 24
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "-T", template, "--author-column", "-r", "@-"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    o Test User <test.user@ex… first|
+    ~
+    "###);
+}
+
+#[test]
+fn test_log_committer_timestamp() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_success(&repo_path, &["describe", "-m", "first"]);
+    test_env.jj_cmd_success(&repo_path, &["new", "-m", "second"]);
+
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-T", "committer.timestamp()"]);
+    insta::assert_snapshot!(stdout, @r###"
+    @ 2001-02-03 04:05:09.000 +07:00
+    o 2001-02-03 04:05:08.000 +07:00
+    o 1970-01-01 00:00:00.000 +00:00
+    "###);
+
+    // Rewriting a commit (and rebasing its descendants) only updates the
+    // committer timestamp
+    test_env.jj_cmd_success(&repo_path, &["describe", "-r", "@-", "-m", "first edited"]);
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "-T",
+            r#"author.timestamp() " " committer.timestamp()"#,
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    @ 2001-02-03 04:05:09.000 +07:00 2001-02-03 04:05:11.000 +07:00
+    o 2001-02-03 04:05:07.000 +07:00 2001-02-03 04:05:11.000 +07:00
+    o 1970-01-01 00:00:00.000 +00:00 1970-01-01 00:00:00.000 +00:00
+    "###);
+}
+
+#[test]
+fn test_log_author_timestamp_older_than() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_success(&repo_path, &["describe", "-m", "first"]);
+    test_env.jj_cmd_success(&repo_path, &["new", "-m", "second"]);
+
+    // The test environment creates commits at a fixed time
+    let timestamp = chrono::DateTime::parse_from_rfc3339("2001-02-03T04:05:07+07:00").unwrap();
+    let age_in_days = chrono::Utc::now()
+        .signed_duration_since(timestamp)
+        .num_days();
+    let render = |template: String| {
+        test_env.jj_cmd_success(
+            &repo_path,
+            &["log", "--no-graph", "-r", "@-", "-T", &template],
+        )
+    };
+
+    // The result flips when the duration passes the age of the commit
+    let stdout = render(format!(
+        r#"if(author.timestamp().older_than("{}d"), "old", "new")"#,
+        age_in_days - 1
+    ));
+    insta::assert_snapshot!(stdout, @"old");
+    let stdout = render(format!(
+        r#"if(author.timestamp().older_than("{}d"), "old", "new")"#,
+        age_in_days + 1
+    ));
+    insta::assert_snapshot!(stdout, @"new");
+    let stdout = render(format!(
+        r#"if(author.timestamp().newer_than("{}w"), "new", "old")"#,
+        age_in_days / 7 + 1
+    ));
+    insta::assert_snapshot!(stdout, @"new");
+    let stdout = render(r#"if(committer.timestamp().newer_than("1h"), "new", "old")"#.to_string());
+    insta::assert_snapshot!(stdout, @"old");
+}
+
+#[test]
+fn test_log_author_timestamp_ago() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_success(&repo_path, &["describe", "-m", "first"]);
+    test_env.jj_cmd_success(&repo_path, &["new", "-m", "second"]);
+
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-T", "author.timestamp().ago()"]);
+    let line_re = Regex::new(r"@|o [0-9]+ years ago").unwrap();
+    assert!(
+        stdout.lines().all(|x| line_re.is_match(x)),
+        "expected every line to match regex"
+    );
+}
+
+#[test]
+fn test_log_parents_summary() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_success(&repo_path, &["describe", "-m", "left"]);
+    test_env.jj_cmd_success(&repo_path, &["new", "root", "-m", "right"]);
+    test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "new",
+            "description(left)",
+            "description(right)",
+            "-m",
+            "merge",
+        ],
+    );
+    let short_id = |revision: &str| {
+        test_env.jj_cmd_success(
+            &repo_path,
+            &[
+                "log",
+                "--no-graph",
+                "-r",
+                revision,
+                "-T",
+                "commit_id.short()",
+            ],
+        )
+    };
+    let left_id = short_id("description(left)");
+    let right_id = short_id("description(right)");
+
+    // Only the merge gets the summary
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "-T",
+            "description",
+            "--no-graph",
+            "--parents-summary",
+        ],
+    );
+    let parents = stdout
+        .lines()
+        .filter(|line| line.starts_with("(parents: "))
+        .collect::<Vec<_>>();
+    assert_eq!(parents.len(), 1, "{stdout}");
+    assert!(parents[0].contains(&left_id), "{stdout}");
+    assert!(parents[0].contains(&right_id), "{stdout}");
+    assert!(stdout.starts_with("merge\n(parents: "), "{stdout}");
+
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "-T",
+            r#"parents.len() " " if(merge, "merge", "not a merge") "\n""#,
+            "--no-graph",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    2 merge
+    1 not a merge
+    1 not a merge
+    0 not a merge
+    "###);
+}
+
+#[test]
+fn test_log_annotate_change_count() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file1"), "foo\nbar\n").unwrap();
+    std::fs::write(repo_path.join("file2"), "foo\n").unwrap();
+    test_env.jj_cmd_success(&repo_path, &["describe", "-m", "first"]);
+    test_env.jj_cmd_success(&repo_path, &["new", "-m", "second"]);
+    std::fs::write(repo_path.join("file1"), "foo\nbaz\nqux\n").unwrap();
+    std::fs::remove_file(repo_path.join("file2")).unwrap();
+
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "-T",
+            "description",
+            "--annotate-change-count",
+            "-r",
+            "root:",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    @ second
+    | 2 files changed, 2 insertions(+), 2 deletions(-)
+    o first
+    | 2 files changed, 3 insertions(+)
+    o (no description set)
+      0 files changed
+    "###);
+
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "-T",
+            r#"diff.files_changed() " " diff.insertions() " " diff.deletions() "\n""#,
+            "-r",
+            "@",
+            "--no-graph",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    2 2 2
+    "###);
+}
+
+#[test]
+fn test_log_format_json() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file1"), "foo\n").unwrap();
+    test_env.jj_cmd_success(&repo_path, &["describe", "-m", "first"]);
+    test_env.jj_cmd_success(&repo_path, &["new", "-m", "second"]);
+
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "--format", "json", "-r", "@-:"]);
+    // One object per line, so the array can be streamed
+    assert_eq!(stdout.lines().count(), 4);
+    let commits: Vec<serde_json::Value> = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(commits.len(), 2);
+    assert_eq!(commits[0]["description"], "second\n");
+    assert_eq!(commits[1]["description"], "first\n");
+    assert_eq!(commits[0]["parents"][0], commits[1]["commit_id"]);
+    assert_eq!(commits[0]["author"]["name"], "Test User");
+    assert_eq!(commits[0]["author"]["email"], "test.user@example.com");
+    assert!(commits[0]["author"]["timestamp"]
+        .as_str()
+        .unwrap()
+        .starts_with("2001-02-03T04:05:"));
+    assert!(commits[0]["change_id"].is_string());
+    assert!(commits[0].get("diff").is_none());
+
+    // `-p` adds the diff as a string
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "--format", "json", "-r", "@-", "--git", "--reversed"],
+    );
+    let commits: Vec<serde_json::Value> = serde_json::from_str(&stdout).unwrap();
+    insta::assert_snapshot!(commits[0]["diff"].as_str().unwrap(), @r###"
+    diff --git a/file1 b/file1
+    new file mode 100644
+    index 0000000000..257cc5642c
+    --- /dev/null
+    +++ b/file1
+    @@ -1,0 +1,1 @@
+    +foo
+    "###);
+
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "--format", "json", "-r", "none()"]);
+    insta::assert_snapshot!(stdout, @r###"
+    []
+    "###);
+
+    // There's no template in JSON output
+    let stderr = test_env.jj_cmd_cli_error(
+        &repo_path,
+        &["log", "--format", "json", "-T", "description"],
+    );
+    assert!(stderr.contains("cannot be used with"));
+}
+
+#[test]
+fn test_log_mark() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_success(&repo_path, &["describe", "-m", "first reviewed"]);
+    test_env.jj_cmd_success(&repo_path, &["new", "-m", "second"]);
+    test_env.jj_cmd_success(&repo_path, &["new", "-m", "third reviewed"]);
+
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "-T",
+            "description",
+            "--mark",
+            "description(reviewed)=✓",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    ✓ third reviewed
+    o second
+    ✓ first reviewed
+    o (no description set)
+    "###);
+
+    // Later marks win
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "-T",
+            "description",
+            "--mark",
+            "description(reviewed)=✓",
+            "--mark",
+            "@=!",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    ! third reviewed
+    o second
+    ✓ first reviewed
+    o (no description set)
+    "###);
+
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["log", "--mark", "@"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Invalid mark "@" (expected a revset and a symbol, like "@=*")
+    "###);
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["log", "--mark", "@=ab"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Invalid mark "@=ab" (the symbol must be a single character)
+    "###);
+}
+
+#[test]
+fn test_log_highlight_path() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file1"), "foo\n").unwrap();
+    test_env.jj_cmd_success(&repo_path, &["describe", "-m", "first"]);
+    test_env.jj_cmd_success(&repo_path, &["new", "-m", "second"]);
+    std::fs::write(repo_path.join("file2"), "foo\n").unwrap();
+    test_env.jj_cmd_success(&repo_path, &["new", "-m", "third"]);
+    std::fs::write(repo_path.join("file1"), "bar\n").unwrap();
+
+    // Only the commits that modify the path are highlighted, but all of them
+    // are shown
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "--color=always",
+            "log",
+            "-T",
+            "description",
+            "--highlight-path",
+            "file1",
+        ],
+    );
+    let highlight = "\u{1b}[1;33m";
+    let is_highlighted = |description: &str| {
+        let line = stdout
+            .lines()
+            .find(|line| line.contains(description))
+            .unwrap();
+        line.contains(highlight)
+    };
+    assert!(is_highlighted("third"), "{stdout}");
+    assert!(!is_highlighted("second"), "{stdout}");
+    assert!(is_highlighted("first"), "{stdout}");
+    assert!(!is_highlighted("(no description set)"), "{stdout}");
+
+    // Without color, the option has no effect
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "-T", "description", "--highlight-path", "file1"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    @ third
+    o second
+    o first
+    o (no description set)
+    "###);
+}
+
+#[test]
+fn test_log_no_color_working_copy() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    let stdout = test_env.jj_cmd_success(&repo_path, &["--color=always", "log", "-T", "commit_id"]);
+    insta::assert_snapshot!(stdout, @r###"
+    @ [1;34m230dd059e1b059aefc0da06a2e5a7dbf22362f22[0m
+    o [34m0000000000000000000000000000000000000000[0m
+    "###);
+
+    // The working-copy row gets the same colors as the other rows
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "--color=always",
+            "log",
+            "-T",
+            "commit_id",
+            "--no-color-working-copy",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    @ [34m230dd059e1b059aefc0da06a2e5a7dbf22362f22[0m
+    o [34m0000000000000000000000000000000000000000[0m
+    "###);
+
+    // The setting has the same effect
+    test_env.add_config(br#"ui.log-color-working-copy = false"#);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["--color=always", "log", "-T", "commit_id"]);
+    insta::assert_snapshot!(stdout, @r###"
+    @ [34m230dd059e1b059aefc0da06a2e5a7dbf22362f22[0m
+    o [34m0000000000000000000000000000000000000000[0m
+    "###);
+}
+
+#[test]
+fn test_log_dim_root() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "--color=always",
+            "log",
+            "-T",
+            "description",
+            "-r",
+            "root",
+            "--dim-root",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    [1;30mo[0m [1;30m(no description set)[0m
+    "###);
+
+    // Other commits aren't dimmed
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["--color=always", "log", "-r", "@", "--dim-root"],
+    );
+    assert!(!stdout.contains("\u{1b}[1;30m"), "{stdout}");
+
+    // The setting has the same effect
+    test_env.add_config(br#"ui.dim-root = true"#);
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["--color=always", "log", "-T", "description", "-r", "root"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    [1;30mo[0m [1;30m(no description set)[0m
+    "###);
+
+    // Without color, it has no effect
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-T", "description", "-r", "root"]);
+    insta::assert_snapshot!(stdout, @r###"
+    o (no description set)
+    "###);
+}
+
+#[test]
+fn test_log_show_empty_marker() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file"), "contents\n").unwrap();
+    test_env.jj_cmd_success(&repo_path, &["describe", "-m", "non-empty"]);
+    test_env.jj_cmd_success(&repo_path, &["new", "-m", "empty"]);
+
+    // Only the commit that changes no files is marked (the root commit is
+    // empty too)
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "-T", "description", "--show-empty-marker"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    @ (empty) empty
+    o non-empty
+    o (empty) (no description set)
+    "###);
+
+    // The marker is also available as the `empty` keyword in templates
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "--no-graph",
+            "-r",
+            "@ | @-",
+            "-T",
+            r#"description.first_line() ": " if(empty, "yes", "no") "\n""#,
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    empty: yes
+    non-empty: no
+    "###);
+}
+
+#[test]
+fn test_log_author() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_success(&repo_path, &["describe", "-m", "first"]);
+    test_env
+        .jj_cmd(&repo_path, &["new", "-m", "second"])
+        .env("JJ_USER", "Other User")
+        .env("JJ_EMAIL", "other.user@example.org")
+        .assert()
+        .success();
+    test_env.jj_cmd_success(&repo_path, &["new", "-m", "third"]);
+
+    // Matches the name, ignoring case
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "-T",
+            "description",
+            "--no-graph",
+            "--author",
+            "test user",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    third
+    first
+    "###);
+
+    // Matches the email
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "-T", "description", "--author", "example.org"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    o second
+    ~ 
+    "###);
+
+    // Regular expressions are case-sensitive
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "-T",
+            "description",
+            "--no-graph",
+            "--author",
+            "regex:^(Other|Test) User$",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    third
+    second
+    first
+    "###);
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "-T", "description", "--author", "regex:^other"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+
+    // It narrows down the given revisions
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "-T",
+            "description",
+            "--no-graph",
+            "-r",
+            "@-::",
+            "--author",
+            "user",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    third
+    second
+    "###);
+
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["log", "--author", "regex:("]);
+    assert!(stderr.starts_with(r#"Error: Invalid author pattern "regex:(""#));
+}
+
+#[test]
+fn test_log_date_range() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_success(&repo_path, &["describe", "-m", "first"]);
+    test_env.jj_cmd_success(&repo_path, &["new", "-m", "second"]);
+    test_env.jj_cmd_success(&repo_path, &["new", "-m", "third"]);
+    let log = |args: &[&str]| {
+        let mut full_args = vec![
+            "log",
+            "-T",
+            r#"description.first_line() " " author.timestamp() "\n""#,
+            "--no-graph",
+            "-r",
+            "all() ~ root",
+        ];
+        full_args.extend_from_slice(args);
+        test_env.jj_cmd_success(&repo_path, &full_args)
+    };
+    insta::assert_snapshot!(log(&[]), @r###"
+    third 2001-02-03 04:05:10.000 +07:00
+    second 2001-02-03 04:05:09.000 +07:00
+    first 2001-02-03 04:05:07.000 +07:00
+    "###);
+
+    // --after includes the boundary, --before doesn't
+    insta::assert_snapshot!(log(&["--after", "2001-02-03T04:05:09+07:00"]), @r###"
+    third 2001-02-03 04:05:10.000 +07:00
+    second 2001-02-03 04:05:09.000 +07:00
+    "###);
+    insta::assert_snapshot!(log(&["--before", "2001-02-03T04:05:09+07:00"]), @r###"
+    first 2001-02-03 04:05:07.000 +07:00
+    "###);
+    insta::assert_snapshot!(
+        log(&[
+            "--after",
+            "2001-02-03T04:05:08+07:00",
+            "--before",
+            "2001-02-03T04:05:10+07:00"
+        ]),
+        @r###"
+    second 2001-02-03 04:05:09.000 +07:00
+    "###);
+
+    // Dates are midnight UTC
+    insta::assert_snapshot!(log(&["--after", "2001-02-02", "--before", "2001-02-03"]), @r###"
+    third 2001-02-03 04:05:10.000 +07:00
+    second 2001-02-03 04:05:09.000 +07:00
+    first 2001-02-03 04:05:07.000 +07:00
+    "###);
+    insta::assert_snapshot!(log(&["--after", "2001-02-03"]), @"");
+
+    // Relative dates
+    insta::assert_snapshot!(log(&["--after", "3 days ago"]), @"");
+    insta::assert_snapshot!(log(&["--before", "yesterday", "--after", "2001-02-03T04:05:10+07:00"]), @r###"
+    third 2001-02-03 04:05:10.000 +07:00
+    "###);
+
+    // The filters narrow down the given revisions
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "-T",
+            r#"description.first_line() "\n""#,
+            "--no-graph",
+            "-r",
+            "::@-",
+            "--after",
+            "2001-02-03T04:05:08+07:00",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    second
+    "###);
+
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["log", "--after", "someday"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Invalid date "someday"
+    Hint: Pass e.g. "2022-01-31", "2022-01-31T12:00:00+01:00", "yesterday", or "3 days ago"
+    "###);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_log_show_signature_status() {
+    let test_env = TestEnvironment::default();
+    let workspace_root = test_env.env_root().join("repo");
+    let git_repo = git2::Repository::init(&workspace_root).unwrap();
+
+    // Create a chain of commits with a good, a bad, and no signature
+    let tree_oid = git_repo.treebuilder(None).unwrap().write().unwrap();
+    let tree = git_repo.find_tree(tree_oid).unwrap();
+    let signature = git2::Signature::new(
+        "Someone",
+        "someone@example.com",
+        &git2::Time::new(1234567890, 60),
+    )
+    .unwrap();
+    let commit_signed = |message: &str, gpg_signature: &str, parents: &[&git2::Commit]| {
+        let buffer = git_repo
+            .commit_create_buffer(&signature, &signature, message, &tree, parents)
+            .unwrap();
+        let oid = git_repo
+            .commit_signed(buffer.as_str().unwrap(), gpg_signature, None)
+            .unwrap();
+        git_repo.find_commit(oid).unwrap()
+    };
+    let good = commit_signed("good\n", "good signature", &[]);
+    let bad = commit_signed("bad\n", "bad signature", &[&good]);
+    git_repo
+        .commit(
+            Some("refs/heads/master"),
+            &signature,
+            &signature,
+            "unsigned\n",
+            &tree,
+            &[&bad],
+        )
+        .unwrap();
+    test_env.jj_cmd_success(&workspace_root, &["init", "--git-repo", "."]);
+
+    // The stub verifier accepts signatures containing "good"
+    let verify_config = r#"signing.verify-command = ["sh", "-c", "grep -q good \"$1\"", "sh"]"#;
+    let stdout = test_env.jj_cmd_success(
+        &workspace_root,
+        &[
+            "log",
+            "-T",
+            "description",
+            "-r",
+            "::master ~ root",
+            "--show-signature-status",
+            "--config-toml",
+            verify_config,
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    o   unsigned
+    o B bad
+    o G good
+    ~ 
+    "###);
+
+    // Good and bad signatures are colored
+    let stdout = test_env.jj_cmd_success(
+        &workspace_root,
+        &[
+            "--color=always",
+            "log",
+            "-T",
+            "description",
+            "-r",
+            "::master ~ root",
+            "--show-signature-status",
+            "--config-toml",
+            verify_config,
+        ],
+    );
+    assert!(stdout.contains("\u{1b}[32mG\u{1b}[0m"), "{stdout}");
+    assert!(stdout.contains("\u{1b}[31mB\u{1b}[0m"), "{stdout}");
+
+    // Without a command to verify them with, the signatures are unknown
+    let stdout = test_env.jj_cmd_success(
+        &workspace_root,
+        &[
+            "log",
+            "-T",
+            "description",
+            "-r",
+            "::master ~ root",
+            "--show-signature-status",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    o   unsigned
+    o U bad
+    o U good
+    ~ 
+    "###);
+
+    // An invalid command is an error rather than treated as unset
+    let stderr = test_env.jj_cmd_failure(
+        &workspace_root,
+        &[
+            "log",
+            "-T",
+            "description",
+            "--show-signature-status",
+            "--config-toml",
+            "signing.verify-command = []",
+        ],
     );
+    assert!(stderr.starts_with("Config error: "), "{stderr}");
 }
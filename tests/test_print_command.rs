@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::common::TestEnvironment;
+use crate::common::{get_stderr_string, get_stdout_string, TestEnvironment};
 
 pub mod common;
 
@@ -69,3 +69,1055 @@ fn test_print() {
     >>>>>>>
     "###);
 }
+
+#[test]
+fn test_print_from_subdir() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+    let subdir_path = repo_path.join("dir");
+
+    std::fs::write(repo_path.join("file1"), "root file\n").unwrap();
+    std::fs::create_dir(&subdir_path).unwrap();
+    std::fs::write(subdir_path.join("file1"), "dir file\n").unwrap();
+    std::fs::write(subdir_path.join("file2"), "c\n").unwrap();
+
+    // Paths are relative to the current directory by default
+    let stdout = test_env.jj_cmd_success(&subdir_path, &["print", "file2"]);
+    insta::assert_snapshot!(stdout, @r###"
+    c
+    "###);
+    let stdout = test_env.jj_cmd_success(&subdir_path, &["print", "file1"]);
+    insta::assert_snapshot!(stdout, @r###"
+    dir file
+    "###);
+    let parent_file = if cfg!(unix) { "../file1" } else { "..\\file1" };
+    let stdout = test_env.jj_cmd_success(&subdir_path, &["print", parent_file]);
+    insta::assert_snapshot!(stdout, @r###"
+    root file
+    "###);
+
+    // With --repo-relative, they're relative to the workspace root
+    let stdout = test_env.jj_cmd_success(&subdir_path, &["print", "--repo-relative", "file1"]);
+    insta::assert_snapshot!(stdout, @r###"
+    root file
+    "###);
+    let subdir_file = if cfg!(unix) {
+        "dir/file2"
+    } else {
+        "dir\\file2"
+    };
+    let stdout = test_env.jj_cmd_success(&subdir_path, &["print", "--repo-relative", subdir_file]);
+    insta::assert_snapshot!(stdout, @r###"
+    c
+    "###);
+    let stderr = test_env.jj_cmd_failure(&subdir_path, &["print", "--repo-relative", "file2"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: No such path
+    "###);
+}
+
+#[test]
+fn test_cat_alias() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file1"), "a\n").unwrap();
+    test_env.jj_cmd_success(&repo_path, &["new"]);
+    std::fs::write(repo_path.join("file1"), "b\n").unwrap();
+
+    // `jj cat` behaves exactly like `jj print`
+    for args in [&["file1"][..], &["file1", "-r", "@-"], &["-n", "file1"]] {
+        let print_stdout = test_env.jj_cmd_success(&repo_path, &[&["print"][..], args].concat());
+        let cat_stdout = test_env.jj_cmd_success(&repo_path, &[&["cat"][..], args].concat());
+        assert_eq!(cat_stdout, print_stdout);
+    }
+    let stdout = test_env.jj_cmd_success(&repo_path, &["cat", "file1"]);
+    insta::assert_snapshot!(stdout, @r###"
+    b
+    "###);
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["cat", "nonexistent"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: No such path
+    "###);
+
+    // It's listed as an alias in the help
+    let stdout = test_env.jj_cmd_success(&repo_path, &["help"]);
+    assert!(stdout.contains("[aliases: cat]"), "{stdout}");
+}
+
+#[test]
+fn test_print_nth_ancestor() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    for content in ["a\n", "b\n", "c\n"] {
+        std::fs::write(repo_path.join("file1"), content).unwrap();
+        test_env.jj_cmd_success(&repo_path, &["new"]);
+    }
+    std::fs::write(repo_path.join("file1"), "d\n").unwrap();
+
+    let stdout = test_env.jj_cmd_success(&repo_path, &["print", "file1", "-r", "@~2"]);
+    insta::assert_snapshot!(stdout, @r###"
+    b
+    "###);
+    // Same as repeating "-"
+    let stdout = test_env.jj_cmd_success(&repo_path, &["print", "file1", "-r", "@---"]);
+    insta::assert_snapshot!(stdout, @r###"
+    a
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["print", "file1", "-r", "@~0"]);
+    insta::assert_snapshot!(stdout, @r###"
+    d
+    "###);
+
+    // Stepping back through a merge commit is ambiguous
+    test_env.jj_cmd_success(&repo_path, &["new", "@", "@~3"]);
+    test_env.jj_cmd_success(&repo_path, &["new"]);
+    let merge_id = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "--no-graph", "-r", "@-", "-T", "commit_id"],
+    );
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["print", "file1", "-r", "@~2"]);
+    assert_eq!(
+        stderr,
+        format!(
+            "Error: Cannot follow \"~N\" through merge commit {merge_id}; use \"-\" to select all \
+             parents\n"
+        )
+    );
+}
+
+#[test]
+fn test_print_multiple_revisions() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.add_config(br#"template.commit_summary = 'description.first_line()'"#);
+
+    std::fs::write(repo_path.join("file1"), "a\n").unwrap();
+    test_env.jj_cmd_success(&repo_path, &["describe", "-m", "add file"]);
+    test_env.jj_cmd_success(&repo_path, &["new", "-m", "remove file"]);
+    std::fs::remove_file(repo_path.join("file1")).unwrap();
+    test_env.jj_cmd_success(&repo_path, &["new", "-m", "restore file"]);
+    std::fs::write(repo_path.join("file1"), "b\n").unwrap();
+
+    // Each revision gets a header, and revisions without the file don't stop
+    // the others from being printed
+    let stdout = test_env.jj_cmd_success(&repo_path, &["print", "file1", "-r", "root+:"]);
+    insta::assert_snapshot!(stdout, @r###"
+    ==> restore file <==
+    b
+
+    ==> remove file <==
+    (absent)
+
+    ==> add file <==
+    a
+    "###);
+
+    // Options apply to each revision
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["print", "file1", "-r", "root+:", "--count-lines"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    ==> restore file <==
+    1
+
+    ==> remove file <==
+    (absent)
+
+    ==> add file <==
+    1
+    "###);
+
+    // A single revision is printed without a header
+    let stdout = test_env.jj_cmd_success(&repo_path, &["print", "file1", "-r", "root+"]);
+    insta::assert_snapshot!(stdout, @r###"
+    a
+    "###);
+}
+
+#[test]
+fn test_print_number() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file1"), "a\n").unwrap();
+    test_env.jj_cmd_success(&repo_path, &["new"]);
+    std::fs::write(repo_path.join("file1"), "b\n").unwrap();
+    std::fs::write(repo_path.join("file2"), "one\n\nthree").unwrap();
+
+    // The last line is numbered even if it's unterminated
+    let stdout = test_env.jj_cmd_success(&repo_path, &["print", "-n", "file2"]);
+    assert_eq!(stdout, "     1\tone\n     2\t\n     3\tthree");
+
+    // Conflicts are numbered as materialized (see `test_print`)
+    test_env.jj_cmd_success(&repo_path, &["new"]);
+    std::fs::write(repo_path.join("file1"), "c\n").unwrap();
+    test_env.jj_cmd_success(&repo_path, &["rebase", "-r", "@", "-d", "@--"]);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["print", "--number", "file1"]);
+    assert_eq!(
+        stdout,
+        "     1\t<<<<<<<\n     2\t%%%%%%%\n     3\t-b\n     4\t+a\n     5\t+++++++\n     \
+         6\tc\n     7\t>>>>>>>\n"
+    );
+
+    let stderr = test_env.jj_cmd_cli_error(&repo_path, &["print", "-n", "--count-lines", "file1"]);
+    assert!(stderr.contains("cannot be used with"), "{stderr}");
+}
+
+#[test]
+fn test_print_from_to() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file1"), "foo\n").unwrap();
+    test_env.jj_cmd_success(&repo_path, &["describe", "-m", "first"]);
+    test_env.jj_cmd_success(&repo_path, &["new", "-m", "second"]);
+    std::fs::write(repo_path.join("file2"), "foo\n").unwrap();
+    test_env.jj_cmd_success(&repo_path, &["new", "-m", "third"]);
+    std::fs::write(repo_path.join("file1"), "foo\nbar\n").unwrap();
+
+    // Only the given path is diffed, across all the revisions in between
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["print", "--from", "@--", "--to", "@", "file1"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    diff --git a/file1 b/file1
+    index 257cc5642c...3bd1f0e297 100644
+    --- a/file1
+    +++ b/file1
+    @@ -1,1 +1,2 @@
+     foo
+    +bar
+    "###);
+
+    // --to defaults to the working copy
+    let stdout = test_env.jj_cmd_success(&repo_path, &["print", "--from", "@--", "file2"]);
+    insta::assert_snapshot!(stdout, @r###"
+    diff --git a/file2 b/file2
+    new file mode 100644
+    index 0000000000..257cc5642c
+    --- /dev/null
+    +++ b/file2
+    @@ -1,0 +1,1 @@
+    +foo
+    "###);
+
+    // An unchanged file has an empty diff
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["print", "--from", "@--", "--to", "@-", "file1"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["print", "--from", "@--", "nonexistent"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: No such path
+    "###);
+}
+
+#[test]
+fn test_print_binary() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file1"), b"a\0b\n").unwrap();
+    std::fs::write(repo_path.join("file2"), b"\xff\xfe\n").unwrap();
+    std::fs::write(repo_path.join("file3.bin"), "text\n").unwrap();
+
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["print", "file1"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: file1 appears to be binary; pass --binary to print raw bytes
+    "###);
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["print", "file2"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: file2 appears to be binary; pass --binary to print raw bytes
+    "###);
+    // The contents matter, not the file name
+    let stdout = test_env.jj_cmd_success(&repo_path, &["print", "file3.bin"]);
+    insta::assert_snapshot!(stdout, @r###"
+    text
+    "###);
+
+    let assert = test_env
+        .jj_cmd(&repo_path, &["print", "--binary", "file1"])
+        .assert()
+        .success();
+    assert_eq!(assert.get_output().stdout, b"a\0b\n");
+    let assert = test_env
+        .jj_cmd(&repo_path, &["print", "--binary", "file2"])
+        .assert()
+        .success();
+    assert_eq!(assert.get_output().stdout, b"\xff\xfe\n");
+
+    // UTF-16 text looks binary, but can be printed with the alias too
+    let utf16 = b"\xff\xfeh\0i\0\n\0";
+    std::fs::write(repo_path.join("utf16.txt"), utf16).unwrap();
+    test_env.jj_cmd_failure(&repo_path, &["print", "utf16.txt"]);
+    let assert = test_env
+        .jj_cmd(&repo_path, &["print", "--assume-text", "utf16.txt"])
+        .assert()
+        .success();
+    assert_eq!(assert.get_output().stdout, utf16);
+
+    // Counting lines doesn't print the contents
+    let stdout = test_env.jj_cmd_success(&repo_path, &["print", "--count-lines", "file1"]);
+    insta::assert_snapshot!(stdout, @r###"
+    1
+    "###);
+}
+
+#[test]
+fn test_print_count_lines() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file1"), "a\nb\nc\n").unwrap();
+    std::fs::write(repo_path.join("file2"), "a\nb").unwrap();
+    std::fs::write(repo_path.join("file3"), "").unwrap();
+
+    let stdout = test_env.jj_cmd_success(&repo_path, &["print", "--count-lines", "file1"]);
+    insta::assert_snapshot!(stdout, @r###"
+    3
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["print", "--count-lines", "file3"]);
+    insta::assert_snapshot!(stdout, @r###"
+    0
+    "###);
+    // An unterminated last line isn't counted
+    let assert = test_env
+        .jj_cmd(&repo_path, &["print", "--count-lines", "file2"])
+        .assert()
+        .success();
+    insta::assert_snapshot!(get_stdout_string(&assert), @r###"
+    1
+    "###);
+    insta::assert_snapshot!(get_stderr_string(&assert), @r###"
+    Hint: The last line has no trailing newline, so it wasn't counted
+    "###);
+
+    // Conflicts are counted as materialized (see `test_print`)
+    test_env.jj_cmd_success(&repo_path, &["new"]);
+    std::fs::write(repo_path.join("file1"), "a\n").unwrap();
+    test_env.jj_cmd_success(&repo_path, &["new"]);
+    std::fs::write(repo_path.join("file1"), "b\n").unwrap();
+    test_env.jj_cmd_success(&repo_path, &["new"]);
+    std::fs::write(repo_path.join("file1"), "c\n").unwrap();
+    test_env.jj_cmd_success(&repo_path, &["rebase", "-r", "@", "-d", "@--"]);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["print", "--count-lines", "file1"]);
+    insta::assert_snapshot!(stdout, @r###"
+    7
+    "###);
+}
+
+#[test]
+fn test_print_expand_keywords() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    let contents = "// $Id$\n// $Author$\n// $Id: old $\n// $Unknown$ $Id\n";
+    std::fs::write(repo_path.join("file1"), contents).unwrap();
+    test_env.jj_cmd_success(&repo_path, &["describe", "-m", "first"]);
+    let commit_id = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "--no-graph", "-r", "@", "-T", "commit_id"],
+    );
+
+    let stdout = test_env.jj_cmd_success(&repo_path, &["print", "--expand-keywords", "file1"]);
+    insta::assert_snapshot!(stdout.replace(&commit_id, "<commit id>"), @r###"
+    // $Id: <commit id> $
+    // $Author: Test User <test.user@example.com> $
+    // $Id: <commit id> $
+    // $Unknown$ $Id
+    "###);
+
+    // Without the flag, and in the working copy, the keywords are unexpanded
+    let stdout = test_env.jj_cmd_success(&repo_path, &["print", "file1"]);
+    assert_eq!(stdout, contents);
+    assert_eq!(
+        std::fs::read_to_string(repo_path.join("file1")).unwrap(),
+        contents
+    );
+}
+
+#[test]
+fn test_print_resolve() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file1"), "a\n2\n3\n4\nx\n").unwrap();
+    test_env.jj_cmd_success(&repo_path, &["new"]);
+    std::fs::write(repo_path.join("file1"), "b\n2\n3\n4\nx\n").unwrap();
+    test_env.jj_cmd_success(&repo_path, &["new"]);
+    std::fs::write(repo_path.join("file1"), "c\n2\n3\n4\ny\n").unwrap();
+    std::fs::write(repo_path.join("file2"), "d\n").unwrap();
+    test_env.jj_cmd_success(&repo_path, &["rebase", "-r", "@", "-d", "@--"]);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["print", "file1"]);
+    insta::assert_snapshot!(stdout, @r###"
+    <<<<<<<
+    %%%%%%%
+    -b
+    +a
+    +++++++
+    c
+    >>>>>>>
+    2
+    3
+    4
+    y
+    "###);
+
+    // Only the conflicting lines are picked from the side, the rest is merged
+    let stdout = test_env.jj_cmd_success(&repo_path, &["print", "file1", "--resolve", "ours"]);
+    insta::assert_snapshot!(stdout, @r###"
+    a
+    2
+    3
+    4
+    y
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["print", "file1", "--resolve", "theirs"]);
+    insta::assert_snapshot!(stdout, @r###"
+    c
+    2
+    3
+    4
+    y
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["print", "file1", "--resolve", "base"]);
+    insta::assert_snapshot!(stdout, @r###"
+    b
+    2
+    3
+    4
+    y
+    "###);
+
+    // Files without conflicts are printed as usual
+    let stdout = test_env.jj_cmd_success(&repo_path, &["print", "file2", "--resolve", "ours"]);
+    insta::assert_snapshot!(stdout, @r###"
+    d
+    "###);
+}
+
+#[test]
+fn test_print_conflict_style() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file1"), "a\n2\n3\n4\nx\n").unwrap();
+    test_env.jj_cmd_success(&repo_path, &["new"]);
+    std::fs::write(repo_path.join("file1"), "b\n2\n3\n4\nx\n").unwrap();
+    test_env.jj_cmd_success(&repo_path, &["new"]);
+    std::fs::write(repo_path.join("file1"), "c\n2\n3\n4\ny\n").unwrap();
+    test_env.jj_cmd_success(&repo_path, &["rebase", "-r", "@", "-d", "@--"]);
+
+    // The default is jj's own markers
+    let stdout =
+        test_env.jj_cmd_success(&repo_path, &["print", "file1", "--conflict-style", "diff"]);
+    insta::assert_snapshot!(stdout, @r###"
+    <<<<<<<
+    %%%%%%%
+    -b
+    +a
+    +++++++
+    c
+    >>>>>>>
+    2
+    3
+    4
+    y
+    "###);
+
+    // Git's markers, with the base in the middle
+    let stdout =
+        test_env.jj_cmd_success(&repo_path, &["print", "file1", "--conflict-style", "diff3"]);
+    insta::assert_snapshot!(stdout, @r###"
+    <<<<<<<
+    a
+    |||||||
+    b
+    =======
+    c
+    >>>>>>>
+    2
+    3
+    4
+    y
+    "###);
+
+    // jj's markers with every version in full
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["print", "file1", "--conflict-style", "snapshot"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    <<<<<<<
+    +++++++
+    a
+    -------
+    b
+    +++++++
+    c
+    >>>>>>>
+    2
+    3
+    4
+    y
+    "###);
+}
+
+#[test]
+fn test_print_versions() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file1"), "a\n2\n3\n4\nx\n").unwrap();
+    test_env.jj_cmd_success(&repo_path, &["new"]);
+    std::fs::write(repo_path.join("file1"), "b\n2\n3\n4\nx\n").unwrap();
+    test_env.jj_cmd_success(&repo_path, &["new"]);
+    std::fs::write(repo_path.join("file1"), "c\n2\n3\n4\ny\n").unwrap();
+    std::fs::write(repo_path.join("file2"), "d\n").unwrap();
+    test_env.jj_cmd_success(&repo_path, &["rebase", "-r", "@", "-d", "@--"]);
+
+    // Only the conflicting lines differ between the resolutions
+    let stdout =
+        test_env.jj_cmd_success(&repo_path, &["print", "file1", "--versions", "ours,theirs"]);
+    insta::assert_snapshot!(stdout, @r###"
+    --- ours
+    +++ theirs
+    @@ -1,4 +1,4 @@
+    -a
+    +c
+     2
+     3
+     4
+    "###);
+    let stdout =
+        test_env.jj_cmd_success(&repo_path, &["print", "file1", "--versions", "base,ours"]);
+    insta::assert_snapshot!(stdout, @r###"
+    --- base
+    +++ ours
+    @@ -1,4 +1,4 @@
+    -b
+    +a
+     2
+     3
+     4
+    "###);
+
+    let stderr =
+        test_env.jj_cmd_failure(&repo_path, &["print", "file2", "--versions", "ours,theirs"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Path is not conflicted
+    "###);
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["print", "file1", "--versions", "ours"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: --versions takes exactly two sides, like "ours,theirs"
+    "###);
+}
+
+#[test]
+fn test_print_base_rev() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file1"), "x\n").unwrap();
+    test_env.jj_cmd_success(&repo_path, &["branch", "create", "other-base"]);
+    test_env.jj_cmd_success(&repo_path, &["new", "root"]);
+    std::fs::write(repo_path.join("file1"), "a\n").unwrap();
+    test_env.jj_cmd_success(&repo_path, &["new"]);
+    std::fs::write(repo_path.join("file1"), "b\n").unwrap();
+    test_env.jj_cmd_success(&repo_path, &["new"]);
+    std::fs::write(repo_path.join("file1"), "c\n").unwrap();
+    std::fs::write(repo_path.join("file2"), "d\n").unwrap();
+    test_env.jj_cmd_success(&repo_path, &["rebase", "-r", "@", "-d", "@--"]);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["print", "file1"]);
+    insta::assert_snapshot!(stdout, @r###"
+    <<<<<<<
+    %%%%%%%
+    -b
+    +a
+    +++++++
+    c
+    >>>>>>>
+    "###);
+
+    // The diff is from the chosen base instead
+    let stdout =
+        test_env.jj_cmd_success(&repo_path, &["print", "file1", "--base-rev", "other-base"]);
+    insta::assert_snapshot!(stdout, @r###"
+    <<<<<<<
+    %%%%%%%
+    -x
+    +a
+    +++++++
+    c
+    >>>>>>>
+    "###);
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "print",
+            "file1",
+            "--base-rev",
+            "other-base",
+            "--resolve",
+            "base",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    x
+    "###);
+
+    let stderr =
+        test_env.jj_cmd_failure(&repo_path, &["print", "file2", "--base-rev", "other-base"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Path is not conflicted
+    "###);
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["print", "file1", "--base-rev", "root"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Path is not a file in the base revision
+    "###);
+}
+
+#[test]
+fn test_print_mode() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file"), "a\n").unwrap();
+    std::fs::create_dir(repo_path.join("dir")).unwrap();
+    std::fs::write(repo_path.join("dir").join("file"), "b\n").unwrap();
+
+    let stdout = test_env.jj_cmd_success(&repo_path, &["print", "--mode", "file"]);
+    insta::assert_snapshot!(stdout, @r###"
+    100644 regular
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["print", "--mode", "dir"]);
+    insta::assert_snapshot!(stdout, @r###"
+    040000 tree
+    "###);
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["print", "--mode", "nonexistent"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: No such path
+    "###);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        let mut permissions = std::fs::metadata(repo_path.join("file"))
+            .unwrap()
+            .permissions();
+        permissions.set_mode(0o755);
+        std::fs::set_permissions(repo_path.join("file"), permissions.clone()).unwrap();
+        let stdout = test_env.jj_cmd_success(&repo_path, &["print", "--mode", "file"]);
+        insta::assert_snapshot!(stdout, @r###"
+        100755 executable
+        "###);
+        // Each revision has its own mode
+        test_env.jj_cmd_success(&repo_path, &["new"]);
+        permissions.set_mode(0o644);
+        std::fs::set_permissions(repo_path.join("file"), permissions).unwrap();
+        let stdout = test_env.jj_cmd_success(&repo_path, &["print", "--mode", "-r", "@-", "file"]);
+        insta::assert_snapshot!(stdout, @r###"
+        100755 executable
+        "###);
+        let stdout = test_env.jj_cmd_success(&repo_path, &["print", "--mode", "file"]);
+        insta::assert_snapshot!(stdout, @r###"
+        100644 regular
+        "###);
+
+        std::os::unix::fs::symlink("file", repo_path.join("link")).unwrap();
+        let stdout = test_env.jj_cmd_success(&repo_path, &["print", "--mode", "link"]);
+        insta::assert_snapshot!(stdout, @r###"
+        120000 symlink
+        "###);
+    }
+}
+
+#[test]
+fn test_print_show_mode() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file"), "a\n").unwrap();
+
+    let stdout = test_env.jj_cmd_success(&repo_path, &["print", "--show-mode", "file"]);
+    insta::assert_snapshot!(stdout, @r###"
+    mode: 100644
+    a
+    "###);
+    // `--mode-only` prints just the mode
+    let stdout = test_env.jj_cmd_success(&repo_path, &["print", "--mode-only", "file"]);
+    insta::assert_snapshot!(stdout, @r###"
+    100644 regular
+    "###);
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["print", "--show-mode", "nonexistent"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: No such path
+    "###);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        let mut permissions = std::fs::metadata(repo_path.join("file"))
+            .unwrap()
+            .permissions();
+        permissions.set_mode(0o755);
+        std::fs::set_permissions(repo_path.join("file"), permissions).unwrap();
+        let stdout = test_env.jj_cmd_success(&repo_path, &["print", "--show-mode", "file"]);
+        insta::assert_snapshot!(stdout, @r###"
+        mode: 100755
+        a
+        "###);
+
+        // The target of a symlink is printed instead of its contents
+        std::os::unix::fs::symlink("file", repo_path.join("link")).unwrap();
+        let stdout = test_env.jj_cmd_success(&repo_path, &["print", "--show-mode", "link"]);
+        insta::assert_snapshot!(stdout, @r###"
+        symlink -> file
+        "###);
+    }
+}
+
+#[test]
+fn test_print_git_blob() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file1"), "a\n").unwrap();
+    // Snapshot the working copy so the blob gets written
+    test_env.jj_cmd_success(&repo_path, &["new"]);
+
+    // `git hash-object` of "a\n"
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "print",
+            "--git-blob",
+            "78981922613b2afb6025042ff6bd878ac1994e85",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    a
+    "###);
+
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["print", "--git-blob", "789819"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Invalid git object id "789819" (expected 40 hex digits)
+    "###);
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &[
+            "print",
+            "--git-blob",
+            "0000000000000000000000000000000000000001",
+        ],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Error: No git object with id 0000000000000000000000000000000000000001
+    "###);
+    // The tree of the first commit
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &[
+            "print",
+            "--git-blob",
+            "8e58a4d9eab499e368bd6ddb4af2d8c6efe951bc",
+        ],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Git object 8e58a4d9eab499e368bd6ddb4af2d8c6efe951bc is a tree, not a blob
+    "###);
+
+    // A path is required otherwise
+    let stderr = test_env.jj_cmd_cli_error(&repo_path, &["print"]);
+    assert!(stderr.contains("<PATH>"), "{stderr}");
+}
+
+#[test]
+fn test_print_git_blob_native_backend() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config(br#"ui.allow-init-native = true"#);
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &[
+            "print",
+            "--git-blob",
+            "78981922613b2afb6025042ff6bd878ac1994e85",
+        ],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Error: The repo is not backed by a git repo
+    "###);
+}
+
+#[test]
+fn test_print_git_object_info() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file1"), "a\n").unwrap();
+    std::fs::create_dir(repo_path.join("dir")).unwrap();
+    std::fs::write(repo_path.join("dir").join("file"), "b\n").unwrap();
+
+    // `git hash-object` of "a\n"
+    let stdout = test_env.jj_cmd_success(&repo_path, &["print", "--git-object-info", "file1"]);
+    insta::assert_snapshot!(stdout, @r###"
+    blob 78981922613b2afb6025042ff6bd878ac1994e85 2
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["print", "--git-object-info", "dir"]);
+    insta::assert_snapshot!(stdout, @r###"
+    tree 84bf061d017459b4be45a49b8d8dc945e7a7fdf5 32
+    "###);
+    let stderr =
+        test_env.jj_cmd_failure(&repo_path, &["print", "--git-object-info", "nonexistent"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: No such path
+    "###);
+
+    // A conflicted path reports the object the conflict is stored in
+    test_env.jj_cmd_success(&repo_path, &["new"]);
+    std::fs::write(repo_path.join("file1"), "b\n").unwrap();
+    test_env.jj_cmd_success(&repo_path, &["new"]);
+    std::fs::write(repo_path.join("file1"), "c\n").unwrap();
+    test_env.jj_cmd_success(&repo_path, &["rebase", "-r", "@", "-d", "@--"]);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["print", "--git-object-info", "file1"]);
+    let fields = stdout.split_whitespace().collect::<Vec<_>>();
+    assert_eq!(fields.len(), 4, "{stdout}");
+    assert_eq!(fields[0], "blob");
+    assert_eq!(fields[1].len(), 40);
+    assert_eq!(fields[3], "(conflict)");
+    let stdout =
+        test_env.jj_cmd_success(&repo_path, &["print", "--git-blob", fields[1], "--binary"]);
+    assert_eq!(stdout.len().to_string(), fields[2]);
+}
+
+#[test]
+fn test_print_git_object_info_native_backend() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config(br#"ui.allow-init-native = true"#);
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    std::fs::write(repo_path.join("file1"), "a\n").unwrap();
+
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["print", "--git-object-info", "file1"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: The repo is not backed by a git repo
+    "###);
+}
+
+#[test]
+fn test_print_max_bytes() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file1"), "line 1\nline 2\nline 3\n").unwrap();
+
+    // The output stops after the given number of bytes
+    let assert = test_env
+        .jj_cmd(&repo_path, &["print", "--max-bytes", "10", "file1"])
+        .assert()
+        .success();
+    insta::assert_snapshot!(get_stdout_string(&assert), @r###"
+    line 1
+    lin
+    "###);
+    insta::assert_snapshot!(get_stderr_string(&assert), @r###"
+    ... (truncated, 11 more bytes)
+    "###);
+
+    // Nothing is left out if the file fits
+    let assert = test_env
+        .jj_cmd(&repo_path, &["print", "--max-bytes", "21", "file1"])
+        .assert()
+        .success();
+    insta::assert_snapshot!(get_stdout_string(&assert), @r###"
+    line 1
+    line 2
+    line 3
+    "###);
+    insta::assert_snapshot!(get_stderr_string(&assert), @"");
+
+    // Line numbers are added to the truncated contents
+    let assert = test_env
+        .jj_cmd(&repo_path, &["print", "-n", "--max-bytes", "7", "file1"])
+        .assert()
+        .success();
+    insta::assert_snapshot!(get_stdout_string(&assert), @r###"
+         1	line 1
+    "###);
+    insta::assert_snapshot!(get_stderr_string(&assert), @r###"
+    ... (truncated, 14 more bytes)
+    "###);
+
+    // The configured limit only applies to output to a terminal, unlike
+    // --max-bytes
+    test_env.add_config(br#"ui.print-max-bytes = 4"#);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["print", "file1"]);
+    insta::assert_snapshot!(stdout, @r###"
+    line 1
+    line 2
+    line 3
+    "###);
+    let assert = test_env
+        .jj_cmd(&repo_path, &["print", "--max-bytes", "4", "file1"])
+        .assert()
+        .success();
+    insta::assert_snapshot!(get_stdout_string(&assert), @r###"
+    line
+    "###);
+    insta::assert_snapshot!(get_stderr_string(&assert), @r###"
+    ... (truncated, 17 more bytes)
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["print", "--no-limit", "file1"]);
+    insta::assert_snapshot!(stdout, @r###"
+    line 1
+    line 2
+    line 3
+    "###);
+}
+
+#[test]
+fn test_print_line_ending() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    // Mixed line endings, and no newline at the end
+    std::fs::write(repo_path.join("file"), b"a\r\nb\nc").unwrap();
+
+    let print = |line_ending: &str| {
+        let assert = test_env
+            .jj_cmd(&repo_path, &["print", "--line-ending", line_ending, "file"])
+            .assert()
+            .success()
+            .stderr("");
+        assert.get_output().stdout.clone()
+    };
+    assert_eq!(print("lf"), b"a\nb\nc");
+    assert_eq!(print("crlf"), b"a\r\nb\r\nc");
+    assert_eq!(print("keep"), b"a\r\nb\nc");
+    // Keeping them is the default
+    let assert = test_env
+        .jj_cmd(&repo_path, &["print", "file"])
+        .assert()
+        .success();
+    assert_eq!(assert.get_output().stdout, b"a\r\nb\nc");
+
+    // The raw bytes of --binary aren't converted
+    let stderr = test_env.jj_cmd_cli_error(
+        &repo_path,
+        &["print", "--binary", "--line-ending", "lf", "file"],
+    );
+    assert!(stderr.contains("cannot be used with"), "{stderr}");
+}
+
+#[test]
+fn test_print_at_change() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file1"), "a\n").unwrap();
+    test_env.jj_cmd_success(&repo_path, &["new"]);
+    std::fs::write(repo_path.join("file1"), "b\n").unwrap();
+    let change_id = |revision: &str| {
+        test_env.jj_cmd_success(
+            &repo_path,
+            &["log", "--no-graph", "-r", revision, "-T", "change_id"],
+        )
+    };
+    let parent_change_id = change_id("@-");
+
+    // A full change id or a prefix of one can be given
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["print", "--at-change", &parent_change_id, "file1"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    a
+    "###);
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["print", "--at-change", &parent_change_id[..12], "file1"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    a
+    "###);
+
+    // It's never interpreted as a revset
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["print", "--at-change", "@", "file1"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Invalid change id "@"
+    "###);
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["print", "--at-change", "abc", "file1"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: No visible commit has change id "abc"
+    "###);
+    let stderr = test_env.jj_cmd_cli_error(
+        &repo_path,
+        &[
+            "print",
+            "--at-change",
+            &parent_change_id,
+            "-r",
+            "@",
+            "file1",
+        ],
+    );
+    assert!(stderr.contains("cannot be used with"), "{stderr}");
+
+    // Make the working-copy change divergent by rewriting it in two concurrent
+    // operations
+    test_env.jj_cmd_success(&repo_path, &["describe", "-m", "v1"]);
+    test_env.jj_cmd_success(&repo_path, &["describe", "-m", "v2", "--at-op", "@-"]);
+    let divergent_change_id = change_id("@");
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &["print", "--at-change", &divergent_change_id, "file1"],
+    );
+    let mut lines = stderr.lines();
+    assert_eq!(
+        lines.next().unwrap(),
+        format!(
+            "Error: Change {} is divergent: it has 2 visible commits",
+            &divergent_change_id[..12]
+        )
+    );
+    assert_eq!(
+        lines.next().unwrap(),
+        "Hint: Pass one of them with -r instead:"
+    );
+    let mut descriptions = lines
+        .map(|line| line.split_once(" (").unwrap().1)
+        .collect::<Vec<_>>();
+    descriptions.sort();
+    assert_eq!(descriptions, ["v1)", "v2)"]);
+}
@@ -62,3 +62,30 @@ fn test_show_relative_timestamps() {
     (no description set)
     "###);
 }
+
+#[test]
+fn test_show_trailers() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "describe",
+            "-m",
+            "subject\n\nbody\n\nSigned-off-by: Test User <test.user@example.com>\nnot a \
+             trailer\nReviewed-by: Other User <other.user@example.com>\n",
+        ],
+    );
+    let stdout = test_env.jj_cmd_success(&repo_path, &["show", "--trailers", "-r", "@"]);
+    insta::assert_snapshot!(stdout, @r###"
+    Signed-off-by: Test User <test.user@example.com>
+    Reviewed-by: Other User <other.user@example.com>
+    "###);
+
+    // A description without trailers prints nothing
+    test_env.jj_cmd_success(&repo_path, &["describe", "-m", "subject\n\nbody\n"]);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["show", "--trailers"]);
+    insta::assert_snapshot!(stdout, @"");
+}
@@ -76,3 +76,375 @@ fn test_templater_branches() {
     o 000000000000 
     "###);
 }
+
+#[test]
+fn test_templater_diff() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file1"), "foo\nbar\n").unwrap();
+    std::fs::write(repo_path.join("file2"), "foo\n").unwrap();
+    test_env.jj_cmd_success(&repo_path, &["new", "-m", "change files"]);
+    std::fs::write(repo_path.join("file1"), "foo\nbaz\nqux\n").unwrap();
+    std::fs::remove_file(repo_path.join("file2")).unwrap();
+    std::fs::create_dir(repo_path.join("dir")).unwrap();
+    std::fs::write(repo_path.join("dir").join("file3"), "new\n").unwrap();
+
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "-r",
+            "@",
+            "--no-graph",
+            "-T",
+            r#"description.first_line() " (" diff.summary() ")\n""#,
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    change files (A dir/file3
+    M file1
+    R file2)
+    "###);
+
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "-r", "@", "--no-graph", "-T", r#"diff.stat() "\n""#],
+    );
+    // Not a snapshot, since those would strip the leading spaces
+    assert_eq!(
+        stdout,
+        " dir/file3 | 1 +\n file1     | 3 ++-\n file2     | 1 -\n 3 files changed, 3 \
+         insertions(+), 2 deletions(-)\n"
+    );
+
+    // The counts are available as numbers too
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "-r",
+            "@",
+            "--no-graph",
+            "-T",
+            r#"diff.files_changed() "+" diff.insertions() "-" diff.deletions() "\n""#,
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    3+3-2
+    "###);
+}
+
+#[test]
+fn test_templater_short_ids() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    // The length defaults to 12 but can be given as argument
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "-r",
+            "root",
+            "--no-graph",
+            "-T",
+            r#"commit_id.short() " " commit_id.short(8) " " change_id.short(4) "\n""#,
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    000000000000 00000000 0000
+    "###);
+}
+
+#[test]
+fn test_templater_shortest_commit_id() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+    for i in 0..10 {
+        test_env.jj_cmd_success(&repo_path, &["new", "-m", &format!("commit {}", i)]);
+    }
+
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "--no-graph",
+            "-T",
+            r#"commit_id.shortest() " " commit_id "\n""#,
+        ],
+    );
+    let lines = stdout.lines().collect::<Vec<_>>();
+    assert!(lines.len() > 10);
+    for line in lines {
+        let (shortest, full) = line.split_once(' ').unwrap();
+        assert!(full.starts_with(shortest), "{}", line);
+        assert!(shortest.len() < 12, "{}", line);
+        // The prefix resolves to the commit, and no shorter prefix does
+        let stdout = test_env.jj_cmd_success(
+            &repo_path,
+            &[
+                "log",
+                "--no-graph",
+                "-r",
+                shortest,
+                "-T",
+                r#"commit_id "\n""#,
+            ],
+        );
+        assert_eq!(stdout, format!("{}\n", full));
+        if shortest.len() > 1 {
+            test_env.jj_cmd_failure(&repo_path, &["log", "-r", &shortest[..shortest.len() - 1]]);
+        }
+    }
+}
+
+#[test]
+fn test_templater_shortest_change_id() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+    for i in 0..10 {
+        test_env.jj_cmd_success(&repo_path, &["new", "-m", &format!("commit {}", i)]);
+    }
+
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "--no-graph",
+            "-r",
+            "all()",
+            "-T",
+            r#"change_id.shortest() " " change_id "\n""#,
+        ],
+    );
+    let lines = stdout.lines().collect::<Vec<_>>();
+    assert!(lines.len() > 10);
+    let change_ids = lines
+        .iter()
+        .map(|line| line.split_once(' ').unwrap().1)
+        .collect::<Vec<_>>();
+    for line in &lines {
+        let (shortest, full) = line.split_once(' ').unwrap();
+        assert!(full.starts_with(shortest), "{}", line);
+        assert!(shortest.len() < 12, "{}", line);
+        // No other change ID has the prefix, but another one has any shorter
+        // prefix
+        let others_with_prefix = |prefix: &str| {
+            change_ids
+                .iter()
+                .filter(|change_id| *change_id != full && change_id.starts_with(prefix))
+                .count()
+        };
+        assert_eq!(others_with_prefix(shortest), 0, "{}", line);
+        if shortest.len() > 1 {
+            assert_ne!(
+                others_with_prefix(&shortest[..shortest.len() - 1]),
+                0,
+                "{}",
+                line
+            );
+        }
+    }
+
+    // Other methods still treat the change ID as a string
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "--no-graph",
+            "-r",
+            "root",
+            "-T",
+            "change_id.short(4)",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"0000");
+}
+
+#[test]
+fn test_templater_conditions() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.jj_cmd_success(&repo_path, &["describe", "-m", "first\n\nbody"]);
+    test_env.jj_cmd_success(&repo_path, &["new"]);
+
+    let template = r#"if(description.empty(), "(none)", description.first_line()) "\n""#;
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "--no-graph", "-T", template]);
+    insta::assert_snapshot!(stdout, @r###"
+    (none)
+    first
+    (none)
+    "###);
+
+    // Strings can be checked for substrings and compared
+    let template = r#"if(description.contains("bod"), "body", "no body") " "
+        if(description.first_line().eq("first"), "first", "not first") "\n""#;
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "--no-graph", "-T", template]);
+    insta::assert_snapshot!(stdout, @r###"
+    no body not first
+    body first
+    no body not first
+    "###);
+
+    // Integers can be compared
+    let template = r#"if(parents.len().eq(0), "no parents", "parents")
+        if(parents.len().gt(0), " gt") if(parents.len().lt(1), " lt") "\n""#;
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "--no-graph", "-T", template]);
+    insta::assert_snapshot!(stdout, @r###"
+    parents gt
+    parents gt
+    no parents lt
+    "###);
+}
+
+#[test]
+fn test_templater_parents_children_count() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.jj_cmd_success(&repo_path, &["describe", "-m", "left"]);
+    test_env.jj_cmd_success(&repo_path, &["branch", "create", "left"]);
+    test_env.jj_cmd_success(&repo_path, &["new", "root", "-m", "right"]);
+    test_env.jj_cmd_success(&repo_path, &["branch", "create", "right"]);
+    test_env.jj_cmd_success(&repo_path, &["new", "left", "right", "-m", "merge"]);
+
+    let template = r#"description.first_line() " " parents_count " " children_count
+        if(parents_count.gt(1), " (merge)") if(children_count.gt(1), " (fork)") "\n""#;
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "-r", "~root", "--no-graph", "-T", template],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    merge 2 0 (merge)
+    right 1 1
+    left 1 1
+    "###);
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "-r",
+            "root",
+            "--no-graph",
+            "-T",
+            r#"parents_count " " children_count "\n""#,
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    0 2
+    "###);
+}
+
+#[test]
+fn test_templater_label() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.jj_cmd_success(&repo_path, &["describe", "-m", "first"]);
+    test_env.add_config(br#"colors.fancy = "green""#);
+
+    // The label's color from the config is used
+    let template = r#"label("fancy", description.first_line()) "\n""#;
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "--color=always",
+            "log",
+            "-r",
+            "@",
+            "--no-graph",
+            "-T",
+            template,
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    [32mfirst[0m
+    "###);
+
+    // Builtin labels get their default colors
+    let template = r#"label("commit_id", "id") "\n""#;
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "--color=always",
+            "log",
+            "-r",
+            "@",
+            "--no-graph",
+            "-T",
+            template,
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    [34mid[0m
+    "###);
+
+    // Without color, the labels have no effect
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "--color=never",
+            "log",
+            "-r",
+            "@",
+            "--no-graph",
+            "-T",
+            template,
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    id
+    "###);
+}
+
+#[test]
+fn test_templater_hash() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_success(test_env.env_root(), &["init", "repo", "--git"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    // Different strings spread over the buckets
+    let template = r#""a".hash(6) " " "b".hash(6) " " "c".hash(6) " " "d".hash(6) " "
+        "e".hash(6) " " "f".hash(6) " " "g".hash(6) " " "h".hash(6) "\n""#;
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "-r", "root", "--no-graph", "-T", template],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    4 1 0 1 0 3 2 1
+    "###);
+
+    // The same string always gets the same bucket
+    let template = r#"author.email().hash(100) " " author.email().hash(100) "\n""#;
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "-r", "@", "--no-graph", "-T", template],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    87 87
+    "###);
+
+    // With 6 buckets, it matches the colors of `jj log --color-by-author`
+    let template = r#"label("author_color_" author.email().hash(6), "author") "\n""#;
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "--color=always",
+            "log",
+            "-r",
+            "@",
+            "--no-graph",
+            "-T",
+            template,
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    [32mauthor[0m
+    "###);
+}